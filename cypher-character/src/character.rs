@@ -1,67 +1,205 @@
+use std::sync::Arc;
+
 use bevy::prelude::Component;
-use cypher_core::stat::{Stat, StatList, StatModifier};
+use cypher_core::{
+    damage::Damage,
+    stat::{StatId, StatList, StatModifier, StatModifierKind, HEALTH},
+    stat_condition::{ConditionState, StatCondition},
+    stat_derivation::{
+        breakdown::{StatBreakdown, StatSource},
+        database::StatDerivationDatabase,
+    },
+    stat_registry::database::StatRegistry,
+};
+
+use cypher_item::item::instance::ItemInstance;
 
-use crate::equipment::Equipment;
+use crate::equipment::{EquipError, Equipment};
 
 #[derive(Component)]
 pub struct Character {
-    pub stats: Vec<StatList>,
+    base_stats: Vec<StatList>,
 
-    pub equipment: Equipment,
+    equipment: Equipment,
 
-    // TODO: refactor
-    current_health: u32,
-}
+    /// Bumped whenever base stats, equipment or game data change, so that cached stats know to recompute.
+    revision: u64,
 
-impl Default for Character {
-    fn default() -> Self {
-        Character::new(vec![StatList::from(&[StatModifier(Stat::Health, 10.)])])
-    }
+    /// Defaults and bounds of every stat, from the game data currently loaded.
+    stat_registry: Arc<StatRegistry>,
+
+    /// Stats granted by other stats, from the game data currently loaded.
+    derivations: Arc<StatDerivationDatabase>,
+
+    /// What conditional stat modifiers are checked against. Kept up to date by the world's systems every tick.
+    pub conditions: ConditionState,
 }
 
 impl Character {
-    pub fn new(stat_lists: Vec<StatList>) -> Character {
+    /// Creates a [Character] with the stats every player starts with.
+    pub fn with_starting_stats(
+        stat_registry: Arc<StatRegistry>,
+        derivations: Arc<StatDerivationDatabase>,
+    ) -> Character {
+        Character::new(
+            vec![StatList::from(&[StatModifier(
+                HEALTH,
+                10.,
+                StatModifierKind::Flat,
+            )])],
+            stat_registry,
+            derivations,
+        )
+    }
+
+    pub fn new(
+        stat_lists: Vec<StatList>,
+        stat_registry: Arc<StatRegistry>,
+        derivations: Arc<StatDerivationDatabase>,
+    ) -> Character {
         let mut new_char = Character {
-            stats: stat_lists,
+            base_stats: stat_lists,
             equipment: Equipment::new(),
-            current_health: 0,
+            revision: 0,
+            stat_registry,
+            derivations,
+            conditions: ConditionState::default(),
         };
 
-        new_char.current_health = new_char
-            .stats
-            .iter()
-            .map(|stat_list| *stat_list.get_stat(&Stat::Health).unwrap_or(&0.))
-            .reduce(|acc, next| acc + next)
-            .unwrap()
-            .floor() as u32;
+        new_char.conditions.full_health = true;
 
         new_char
     }
 
-    /// Combines all player [StatList]s into a singular, cumulative [StatList].
-    /// **This isn't performant**: creates a temporary. Should instead be implemented with iterators.
+    pub fn equipment(&self) -> &Equipment {
+        &self.equipment
+    }
+
+    /// Equips `item`, returning whatever was previously equipped in its slot.
+    pub fn equip(&mut self, item: ItemInstance) -> Result<Option<ItemInstance>, EquipError> {
+        let previous = self.equipment.equip(item)?;
+        self.revision += 1;
+
+        Ok(previous)
+    }
+
+    /// Adds stats that aren't granted by equipment, such as from leveling up.
+    pub fn add_base_stats(&mut self, stat_list: StatList) {
+        self.base_stats.push(stat_list);
+        self.revision += 1;
+    }
+
+    /// Replaces the game data stats are computed from, such as after game data is reloaded.
+    /// Bumps the revision, as any stat may have changed.
+    pub fn set_game_data(
+        &mut self,
+        stat_registry: Arc<StatRegistry>,
+        derivations: Arc<StatDerivationDatabase>,
+    ) {
+        self.stat_registry = stat_registry;
+        self.derivations = derivations;
+        self.revision += 1;
+    }
+
+    /// Changes whenever base stats, equipment or game data change. Conditions don't change it, see [Character::conditions_in_use].
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Defaults and bounds of every stat, from the game data currently loaded.
+    pub fn stat_registry(&self) -> &Arc<StatRegistry> {
+        &self.stat_registry
+    }
+
+    /// Every condition that any of the character's conditional modifiers depend on.
+    pub fn conditions_in_use(&self) -> Vec<StatCondition> {
+        let mut conditions = vec![];
+
+        let equipment_stats = self.equipment.stats();
+        let stat_lists = self
+            .base_stats
+            .iter()
+            .chain(equipment_stats.iter().map(|(_, stat_list)| stat_list));
+        for stat_list in stat_lists {
+            for (condition, _) in stat_list.conditional_mods() {
+                if !conditions.contains(&condition) {
+                    conditions.push(condition);
+                }
+            }
+        }
+
+        conditions
+    }
+
+    /// Updates the state conditional stat modifiers are checked against, once per tick.
+    /// Health is tracked by the world rather than the character, so is passed in along with the character's maximum.
+    pub fn update_conditions(
+        &mut self,
+        moving: bool,
+        current_health: f32,
+        max_health: f32,
+        delta_seconds: f32,
+    ) {
+        self.conditions.moving = moving;
+        self.conditions.tick(delta_seconds);
+        self.conditions.full_health = current_health >= max_health;
+    }
+
+    /// Final value of `stat`, after every modifier, the stat's default value and its bounds are applied.
+    pub fn stat(&self, stat: StatId) -> f32 {
+        self.stat_registry.resolve(&self.stats(), stat)
+    }
+
+    /// Combines all player [StatList]s, equipment and derived stats into a singular, cumulative [StatList].
+    /// Read final values through [Character::stat], as modifiers are only combined per layer.
+    /// **This isn't performant**: recomputes every time. Systems should read the cached
+    /// [CharacterStats](crate::character_stats::CharacterStats) instead.
     pub fn stats(&self) -> StatList {
-        let mut new_list = StatList::from(&[]);
+        self.breakdown().stats()
+    }
+
+    /// [Character::stats] against a target on `team_id`, including modifiers that only apply against that team.
+    pub fn stats_against(&self, team_id: u16) -> StatList {
+        let mut conditions = self.conditions.clone();
+        conditions.target_team = Some(team_id);
+
+        self.breakdown_with(&conditions).stats()
+    }
+
+    /// `damage` plus the damage this character adds to hits against a target on `team_id`,
+    /// including added damage that only applies against that team.
+    pub fn damage_against(&self, damage: Damage, team_id: u16) -> Damage {
+        damage.with_added_damage(&self.stat_registry, &self.stats_against(team_id))
+    }
 
-        for stat_list in &self.stats {
-            new_list.add_list(stat_list);
+    /// Every modifier that makes up [Character::stats], and where it came from.
+    /// Derived stats are recomputed from the current stats and equipment every time.
+    pub fn breakdown(&self) -> StatBreakdown {
+        self.breakdown_with(&self.conditions)
+    }
+
+    /// [Character::breakdown], only including conditional modifiers whose condition holds in `conditions`.
+    fn breakdown_with(&self, conditions: &ConditionState) -> StatBreakdown {
+        let mut breakdown = StatBreakdown::default();
+
+        for stat_list in &self.base_stats {
+            breakdown.add(StatSource::Base, &stat_list.applying(conditions));
         }
 
-        for stat_list in self.equipment.stats() {
-            new_list.add_list(&stat_list);
+        for (affix_id, stat_list) in self.equipment.stats() {
+            breakdown.add(StatSource::Affix(affix_id), &stat_list.applying(conditions));
         }
 
-        new_list
+        self.derivations.derive(&mut breakdown);
+
+        breakdown
     }
 }
 
 #[cfg(test)]
 #[allow(unused_must_use)]
 mod tests {
-    use std::{
-        collections::BTreeMap,
-        sync::{Arc, Mutex},
-    };
+    use std::{collections::BTreeMap, sync::Arc};
 
     use cypher_core::{
         affix::{
@@ -71,44 +209,73 @@ mod tests {
             instance::AffixInstance,
             placement::AffixPlacement,
         },
-        stat::{Stat, StatList, StatModifier},
+        damage::DamageType,
+        data::{DataDefinitionDatabase, DefinitionStore},
+        stat::{StatList, StatModifier, StatModifierKind, ADDED_ELEMENTAL_DAMAGE, HEALTH},
+        stat_condition::StatCondition,
+        stat_derivation::definition::StatDerivationDefinition,
     };
     use cypher_item::item::{
         classification::{ItemClassification, ItemEquipSlot},
         definition::ItemDefinition,
-        instance::ItemInstance,
+        instance::{ItemInstance, ItemInstanceRarityTier},
     };
 
     use super::*;
 
+    const RESOLVE: StatId = 1;
+    const COMPLEXITY: StatId = 3;
+
+    fn stat_registry() -> Arc<StatRegistry> {
+        Arc::new(StatRegistry::initialize())
+    }
+
+    fn no_derivations() -> Arc<StatDerivationDatabase> {
+        Arc::new(StatDerivationDatabase::from_store(DefinitionStore::new(
+            vec![],
+        )))
+    }
+
     #[test]
-    fn new_sets_current_health_to_max() {
-        let stat_list_1 = StatList::from(&[StatModifier(Stat::Health, 3.)]);
-        let stat_list_2 = StatList::from(&[StatModifier(Stat::Health, 5.8)]);
+    fn health_combines_stat_lists() {
+        let stat_list_1 = StatList::from(&[StatModifier(HEALTH, 3., StatModifierKind::Flat)]);
+        let stat_list_2 = StatList::from(&[StatModifier(HEALTH, 5.8, StatModifierKind::Flat)]);
 
-        let character = Character::new(vec![stat_list_1, stat_list_2]);
-        assert_eq!(character.current_health, 8);
+        let character = Character::new(
+            vec![stat_list_1, stat_list_2],
+            stat_registry(),
+            no_derivations(),
+        );
+        assert_eq!(character.stat(HEALTH), 8.);
     }
 
     #[test]
     fn can_combine_stat_lists() {
-        let stat_list_1 = StatList::from(&[StatModifier(Stat::Resolve, 1.)]);
-        let stat_list_2 = StatList::from(&[StatModifier(Stat::Resolve, 1.)]);
+        let stat_list_1 = StatList::from(&[StatModifier(RESOLVE, 1., StatModifierKind::Flat)]);
+        let stat_list_2 = StatList::from(&[StatModifier(RESOLVE, 1., StatModifierKind::Flat)]);
 
-        let character = Character::new(vec![stat_list_1, stat_list_2]);
+        let character = Character::new(
+            vec![stat_list_1, stat_list_2],
+            stat_registry(),
+            no_derivations(),
+        );
 
         let combined = character.stats();
 
-        assert_eq!(*combined.get_stat(&Stat::Resolve).unwrap(), 2.);
+        assert_eq!(combined.get_stat(RESOLVE).unwrap(), 2.);
     }
 
     #[test]
     fn negative_health_becomes_zero() {
-        let stat_list_1 = StatList::from(&[StatModifier(Stat::Health, -1.)]);
-        let stat_list_2 = StatList::from(&[StatModifier(Stat::Health, -1.)]);
+        let stat_list_1 = StatList::from(&[StatModifier(HEALTH, -1., StatModifierKind::Flat)]);
+        let stat_list_2 = StatList::from(&[StatModifier(HEALTH, -1., StatModifierKind::Flat)]);
 
-        let character = Character::new(vec![stat_list_1, stat_list_2]);
-        assert_eq!(character.current_health, 0);
+        let character = Character::new(
+            vec![stat_list_1, stat_list_2],
+            stat_registry(),
+            no_derivations(),
+        );
+        assert_eq!(character.stat(HEALTH), 0.);
     }
 
     #[test]
@@ -119,40 +286,135 @@ mod tests {
             AffixDefinitionTier {
                 tier: 1,
                 stats: vec![AffixDefinitionStat {
-                    stat: Stat::Complexity,
+                    stat: COMPLEXITY,
+                    kind: StatModifierKind::Flat,
                     value: AffixDefinitionValue::Range(1., 3.),
+                    condition: None,
+                    template: None,
                 }],
                 item_level_req: None,
                 precision_places: None,
+                weight: None,
             },
         );
 
         let head_item = ItemInstance {
             guid: uuid::Uuid::new_v4().to_string(),
-            definition: Arc::new(Mutex::new(ItemDefinition {
+            definition: Arc::new(ItemDefinition {
                 id: 1,
                 classification: ItemClassification::Equippable(ItemEquipSlot::Head),
                 affix_pools: vec![],
                 fixed_affixes: vec![],
                 name: String::from("test item"),
-            })),
+            }),
             affixes: vec![AffixInstance {
-                definition: Arc::new(Mutex::new(AffixDefinition {
+                definition: Arc::new(AffixDefinition {
                     id: 1,
                     placement: AffixPlacement::Prefix,
                     tiers: affix_def_tiers,
                     name: String::from("test affix"),
-                })),
+                    tags: vec![],
+                    mod_group: None,
+                }),
                 tier: 1,
-                stats: StatList::from(&[StatModifier(Stat::Complexity, 2.)]),
+                stats: StatList::from(&[StatModifier(COMPLEXITY, 2., StatModifierKind::Flat)]),
             }],
+            rarity: ItemInstanceRarityTier::Common,
+            item_level: 1,
         };
 
         // ZJ-TODO: characters don't have health by default but require it - that sucks
-        let mut character = Character::new(vec![StatList::from(&[StatModifier(Stat::Health, 1.)])]);
-        character.equipment.equip(head_item);
+        let mut character = Character::new(
+            vec![StatList::from(&[StatModifier(
+                HEALTH,
+                1.,
+                StatModifierKind::Flat,
+            )])],
+            stat_registry(),
+            no_derivations(),
+        );
+        character.equip(head_item);
 
         let stats = character.stats();
-        assert!(stats.mods().len() > 0);
+        assert!(!stats.mods().is_empty());
+    }
+
+    #[test]
+    fn attributes_grant_derived_stats() {
+        let derivations = Arc::new(StatDerivationDatabase::from_store(DefinitionStore::new(
+            vec![StatDerivationDefinition {
+                id: 1,
+                source: RESOLVE,
+                target: HEALTH,
+                kind: StatModifierKind::Flat,
+                per_point: 2.,
+            }],
+        )));
+
+        let character = Character::new(
+            vec![StatList::from(&[
+                StatModifier(HEALTH, 10., StatModifierKind::Flat),
+                StatModifier(RESOLVE, 5., StatModifierKind::Flat),
+            ])],
+            stat_registry(),
+            derivations,
+        );
+        assert_eq!(character.stat(HEALTH), 20.);
+
+        let breakdown = character.breakdown();
+        let health = breakdown.contributions(HEALTH);
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].0, StatSource::Base);
+        assert_eq!(health[1].0, StatSource::Derivation(1));
+    }
+
+    #[test]
+    fn conditional_stats_follow_character_state() {
+        let mut stat_list = StatList::from(&[StatModifier(HEALTH, 10., StatModifierKind::Flat)]);
+        stat_list.add_conditional_mod(
+            StatCondition::AfterKill(4.),
+            &StatModifier(RESOLVE, 5., StatModifierKind::Flat),
+        );
+        stat_list.add_conditional_mod(
+            StatCondition::AgainstTeam(2),
+            &StatModifier(COMPLEXITY, 3., StatModifierKind::Flat),
+        );
+
+        let mut character = Character::new(vec![stat_list], stat_registry(), no_derivations());
+        assert_eq!(character.stat(RESOLVE), 0.);
+        assert!(character.conditions.full_health);
+
+        character.conditions.record_kill();
+        character.update_conditions(false, 10., 10., 1.);
+        assert_eq!(character.stat(RESOLVE), 5.);
+
+        character.update_conditions(false, 4., 10., 5.);
+        assert_eq!(character.stat(RESOLVE), 0.);
+        assert!(!character.conditions.full_health);
+
+        assert_eq!(character.stats().get_stat(COMPLEXITY), None);
+        assert_eq!(character.stats_against(2).get_stat(COMPLEXITY), Some(3.));
+    }
+
+    #[test]
+    fn added_damage_against_team_only_hits_that_team() {
+        let mut stat_list = StatList::from(&[StatModifier(
+            ADDED_ELEMENTAL_DAMAGE,
+            2.,
+            StatModifierKind::Flat,
+        )]);
+        stat_list.add_conditional_mod(
+            StatCondition::AgainstTeam(2),
+            &StatModifier(ADDED_ELEMENTAL_DAMAGE, 3., StatModifierKind::Flat),
+        );
+
+        let character = Character::new(vec![stat_list], stat_registry(), no_derivations());
+
+        let damage = character.damage_against(Damage::of(DamageType::Physical, 1.), 2);
+        assert_eq!(damage.amount(DamageType::Physical), 1.);
+        assert_eq!(damage.amount(DamageType::Elemental), 5.);
+
+        let damage = character.damage_against(Damage::of(DamageType::Physical, 1.), 3);
+        assert_eq!(damage.amount(DamageType::Elemental), 2.);
     }
 }
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use bevy::prelude::{Component, Entity, Event};
+use cypher_core::{
+    stat::{StatId, StatList},
+    stat_condition::StatCondition,
+    stat_registry::database::StatRegistry,
+};
+
+use crate::character::Character;
+
+/// Cached [Character::stats] of the [Character] on the same entity.
+/// Only recomputed when the character's base stats, equipment or game data change, or one of its conditions starts or
+/// stops holding.
+#[derive(Component)]
+pub struct CharacterStats {
+    stats: StatList,
+
+    stat_registry: Arc<StatRegistry>,
+
+    /// [Character::revision] when the stats were computed.
+    revision: u64,
+
+    /// Every condition the character's modifiers depend on, and whether it held when the stats were computed.
+    conditions: Vec<(StatCondition, bool)>,
+}
+
+/// Sent whenever a character's [CharacterStats] are recomputed and any of them changed.
+#[derive(Event)]
+pub struct CharacterStatsChanged {
+    pub entity: Entity,
+}
+
+impl CharacterStats {
+    pub fn new(character: &Character) -> CharacterStats {
+        let mut character_stats = CharacterStats {
+            stats: StatList::from(&[]),
+            stat_registry: character.stat_registry().clone(),
+            revision: 0,
+            conditions: vec![],
+        };
+        character_stats.refresh(character);
+
+        character_stats
+    }
+
+    /// The cached [Character::stats].
+    pub fn stats(&self) -> &StatList {
+        &self.stats
+    }
+
+    /// Final value of `stat`, as [Character::stat] resolved it when the stats were computed.
+    pub fn stat(&self, stat: StatId) -> f32 {
+        self.stat_registry.resolve(&self.stats, stat)
+    }
+
+    /// Whether `character` changed in a way that could change its stats since they were computed.
+    pub fn is_stale(&self, character: &Character) -> bool {
+        character.revision() != self.revision
+            || self
+                .conditions
+                .iter()
+                .any(|(condition, held)| condition.holds(&character.conditions) != *held)
+    }
+
+    /// Recomputes the stats of `character`, returning whether any of them changed.
+    pub fn refresh(&mut self, character: &Character) -> bool {
+        let stats = character.stats();
+        let changed = stats.mods() != self.stats.mods();
+
+        self.stats = stats;
+        self.stat_registry = character.stat_registry().clone();
+        self.revision = character.revision();
+        self.conditions = character
+            .conditions_in_use()
+            .into_iter()
+            .map(|condition| (condition, condition.holds(&character.conditions)))
+            .collect();
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cypher_core::{
+        data::{DataDefinitionDatabase, DefinitionStore},
+        stat::{StatModifier, StatModifierKind, HEALTH, MOVE_SPEED},
+        stat_derivation::database::StatDerivationDatabase,
+        stat_registry::definition::{StatDefinition, StatDisplayFormat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn stats_are_only_stale_after_changes() {
+        let mut stat_list = StatList::from(&[StatModifier(HEALTH, 10., StatModifierKind::Flat)]);
+        stat_list.add_conditional_mod(
+            StatCondition::Moving,
+            &StatModifier(MOVE_SPEED, 50., StatModifierKind::Increased),
+        );
+
+        let mut character = Character::new(
+            vec![stat_list],
+            Arc::new(StatRegistry::initialize()),
+            Arc::new(StatDerivationDatabase::from_store(DefinitionStore::new(
+                vec![],
+            ))),
+        );
+        let mut character_stats = CharacterStats::new(&character);
+        assert!(!character_stats.is_stale(&character));
+        assert_eq!(character_stats.stat(MOVE_SPEED), 100.);
+
+        character.update_conditions(false, 10., 10., 1.);
+        assert!(!character_stats.is_stale(&character));
+
+        character.update_conditions(true, 10., 10., 1.);
+        assert!(character_stats.is_stale(&character));
+        assert!(character_stats.refresh(&character));
+        assert_eq!(character_stats.stat(MOVE_SPEED), 150.);
+
+        character.add_base_stats(StatList::from(&[StatModifier(
+            HEALTH,
+            5.,
+            StatModifierKind::Flat,
+        )]));
+        assert!(character_stats.is_stale(&character));
+        assert!(character_stats.refresh(&character));
+        assert_eq!(character_stats.stat(HEALTH), 15.);
+        assert!(!character_stats.is_stale(&character));
+    }
+
+    #[test]
+    fn new_game_data_makes_stats_stale() {
+        let mut character = Character::new(
+            vec![StatList::from(&[StatModifier(
+                HEALTH,
+                10.,
+                StatModifierKind::Flat,
+            )])],
+            Arc::new(StatRegistry::initialize()),
+            Arc::new(StatDerivationDatabase::from_store(DefinitionStore::new(
+                vec![],
+            ))),
+        );
+        let mut character_stats = CharacterStats::new(&character);
+        assert_eq!(character_stats.stat(MOVE_SPEED), 100.);
+
+        let stat_registry = Arc::new(StatRegistry::from_store(DefinitionStore::new(vec![
+            StatDefinition {
+                id: MOVE_SPEED,
+                name: String::from("stat.move_speed"),
+                default_value: 120.,
+                min: None,
+                max: None,
+                integer: false,
+                format: StatDisplayFormat::Number,
+            },
+        ])));
+        character.set_game_data(
+            stat_registry,
+            Arc::new(StatDerivationDatabase::from_store(DefinitionStore::new(
+                vec![],
+            ))),
+        );
+        assert!(character_stats.is_stale(&character));
+
+        character_stats.refresh(&character);
+        assert_eq!(character_stats.stat(MOVE_SPEED), 120.);
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use cypher_core::stat::StatList;
+use cypher_core::{affix::definition::AffixDefinitionId, stat::StatList};
 use cypher_item::item::{
     classification::{ItemClassification::Equippable, ItemEquipSlot},
     instance::ItemInstance,
@@ -27,7 +27,7 @@ impl Equipment {
         &mut self,
         equipable_item: ItemInstance,
     ) -> Result<Option<ItemInstance>, EquipError> {
-        let maybe_slot = match equipable_item.definition.lock().unwrap().classification {
+        let maybe_slot = match equipable_item.definition.classification {
             Equippable(slot) => Some(slot),
             _ => None,
         };
@@ -53,10 +53,9 @@ impl Equipment {
         }
     }
 
-    pub fn stats(&self) -> Vec<StatList> {
-        let mut stat_list = vec![];
-
-        let items = vec![
+    /// Every equipped item.
+    pub fn items(&self) -> impl Iterator<Item = &ItemInstance> {
+        [
             &self.head,
             &self.left_arm,
             &self.right_arm,
@@ -64,11 +63,18 @@ impl Equipment {
             &self.belt,
             &self.legs,
             &self.boots,
-        ];
+        ]
+        .into_iter()
+        .flatten()
+    }
 
-        for item in items.into_iter().flatten() {
+    /// Stats of every affix on equipped items, keyed by the affix they came from.
+    pub fn stats(&self) -> Vec<(AffixDefinitionId, StatList)> {
+        let mut stat_list = vec![];
+
+        for item in self.items() {
             for affix in &item.affixes {
-                stat_list.push(affix.stats.clone());
+                stat_list.push((affix.definition.id, affix.stats.clone()));
             }
         }
 
@@ -78,17 +84,7 @@ impl Equipment {
 
 impl Display for Equipment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let items = vec![
-            &self.head,
-            &self.left_arm,
-            &self.right_arm,
-            &self.body,
-            &self.belt,
-            &self.legs,
-            &self.boots,
-        ];
-
-        for item in items.into_iter().flatten() {
+        for item in self.items() {
             write!(f, "{}", item)?;
         }
 
@@ -98,13 +94,13 @@ impl Display for Equipment {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
     use uuid;
 
     use cypher_item::item::{
         classification::{ItemClassification, ItemEquipSlot},
         definition::ItemDefinition,
-        instance::ItemInstance,
+        instance::{ItemInstance, ItemInstanceRarityTier},
     };
 
     use super::Equipment;
@@ -113,14 +109,16 @@ mod tests {
     fn can_equip_if_slot_empty() {
         let head_item = ItemInstance {
             guid: uuid::Uuid::new_v4().to_string(),
-            definition: Arc::new(Mutex::new(ItemDefinition {
+            definition: Arc::new(ItemDefinition {
                 id: 1,
                 classification: ItemClassification::Equippable(ItemEquipSlot::Head),
                 affix_pools: vec![],
                 fixed_affixes: vec![],
                 name: String::from("test item"),
-            })),
+            }),
             affixes: vec![],
+            rarity: ItemInstanceRarityTier::Common,
+            item_level: 1,
         };
 
         let mut equipment = Equipment::new();
@@ -134,26 +132,30 @@ mod tests {
     fn can_equip_if_slot_filled_and_returns_old() {
         let head_item_equipped = ItemInstance {
             guid: uuid::Uuid::new_v4().to_string(),
-            definition: Arc::new(Mutex::new(ItemDefinition {
+            definition: Arc::new(ItemDefinition {
                 id: 1,
                 classification: ItemClassification::Equippable(ItemEquipSlot::Head),
                 affix_pools: vec![],
                 fixed_affixes: vec![],
                 name: String::from("test item"),
-            })),
+            }),
             affixes: vec![],
+            rarity: ItemInstanceRarityTier::Common,
+            item_level: 1,
         };
 
         let head_item_new = ItemInstance {
             guid: uuid::Uuid::new_v4().to_string(),
-            definition: Arc::new(Mutex::new(ItemDefinition {
+            definition: Arc::new(ItemDefinition {
                 id: 2,
                 classification: ItemClassification::Equippable(ItemEquipSlot::Head),
                 affix_pools: vec![],
                 fixed_affixes: vec![],
                 name: String::from("test item"),
-            })),
+            }),
             affixes: vec![],
+            rarity: ItemInstanceRarityTier::Common,
+            item_level: 1,
         };
 
         let mut equipment = Equipment::new();
@@ -163,22 +165,24 @@ mod tests {
 
         assert!(old_item.as_ref().is_ok());
         assert!(old_item.as_ref().unwrap().is_some());
-        assert_eq!(old_item.unwrap().unwrap().definition.lock().unwrap().id, 1); // ensure the old item is returned
-        assert_eq!(equipment.head.unwrap().definition.lock().unwrap().id, 2); // ensure the new item is correctly stored
+        assert_eq!(old_item.unwrap().unwrap().definition.id, 1); // ensure the old item is returned
+        assert_eq!(equipment.head.unwrap().definition.id, 2); // ensure the new item is correctly stored
     }
 
     #[test]
     fn can_only_equip_equipables() {
         let currency = ItemInstance {
             guid: uuid::Uuid::new_v4().to_string(),
-            definition: Arc::new(Mutex::new(ItemDefinition {
+            definition: Arc::new(ItemDefinition {
                 id: 1,
                 classification: ItemClassification::Currency,
                 affix_pools: vec![],
                 fixed_affixes: vec![],
                 name: String::from("test currency"),
-            })),
+            }),
             affixes: vec![],
+            rarity: ItemInstanceRarityTier::Common,
+            item_level: 1,
         };
 
         let mut equipment = Equipment::new();
@@ -1,3 +1,4 @@
 pub mod character;
+pub mod character_stats;
 pub mod drop_source;
 pub mod equipment;
@@ -1,14 +1,13 @@
-use crate::data::{DataDefinition, DataDefinitionDatabase};
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+use crate::{
+    data::{DataDefinitionDatabase, DataMigrations, DefinitionStore},
+    stat::migrate_legacy_stat,
 };
 
-use super::definition::{AffixDefinition, AffixDefinitionId};
+use super::definition::AffixDefinition;
 
 #[derive(Debug)]
 pub struct AffixDefinitionDatabase {
-    affixes: HashMap<AffixDefinitionId, Arc<Mutex<AffixDefinition>>>,
+    affixes: DefinitionStore<AffixDefinition>,
 }
 
 impl AffixDefinitionDatabase {
@@ -17,72 +16,127 @@ impl AffixDefinitionDatabase {
         path.push("..");
         path.push("cypher-core");
         path.push("data");
-        path.push("affix.json");
+        path.push(Self::FILE_NAME);
 
-        Self::load_from(path.to_str().unwrap(), &())
+        Self::load_from(path.to_str().unwrap(), &()).expect("failed to load affix database")
     }
 }
 
 impl DataDefinitionDatabase<AffixDefinition> for AffixDefinitionDatabase {
     type DataDependencies = ();
 
-    fn load_from<S: Into<String>>(path: S, _dependencies: &Self::DataDependencies) -> Self {
-        let affix_file = String::from_utf8(std::fs::read(path.into()).unwrap()).unwrap();
-
-        let definitions: Vec<AffixDefinition> =
-            serde_json::de::from_str(affix_file.as_str()).unwrap();
-
-        let affixes = definitions
-            .into_iter()
-            .map(|affix| (affix.id, Arc::from(Mutex::new(affix))))
-            .collect::<HashMap<_, _>>();
+    const FILE_NAME: &'static str = "affix.json";
 
+    fn from_store(affixes: DefinitionStore<AffixDefinition>) -> Self {
         AffixDefinitionDatabase { affixes }
     }
 
-    fn write_to<S: Into<String>>(&self, path: S) {
-        let definition_clones = self
-            .affixes
-            .values()
-            .map(|def| def.lock().unwrap().to_owned())
-            .collect::<Vec<AffixDefinition>>();
-
-        let serialized = serde_json::ser::to_string(&definition_clones)
-            .expect("failed to serialize affix database");
-
-        std::fs::write(path.into(), serialized).expect("failed to write serialized data to path");
+    fn store(&self) -> &DefinitionStore<AffixDefinition> {
+        &self.affixes
     }
 
-    /// Affixes are entirely self-contained (no references to other data)
-    /// so we only check if there is at least 1 affix and all loaded affixes are valid.
-    fn validate(&self) -> bool {
-        !self.affixes.is_empty()
-            && self
-                .affixes
-                .iter()
-                .all(|(_id, affix_def)| affix_def.lock().unwrap().validate())
+    fn migrations() -> DataMigrations {
+        DataMigrations::default()
+            .with(declare_stat_kinds)
+            .with(stat_names_to_ids)
     }
+}
 
-    fn definition(&self, id: AffixDefinitionId) -> Option<Arc<Mutex<AffixDefinition>>> {
-        self.affixes.get(&id).map(|arc| arc.to_owned())
-    }
+/// Version 2 declares the modifier kind of every stat. Stats were always flat before then.
+fn declare_stat_kinds(definitions: &mut serde_json::Value) -> Result<(), String> {
+    for_each_stat(definitions, |stat| {
+        stat.entry("kind").or_insert("flat".into());
+        Ok(())
+    })
+}
 
-    fn definitions(&self) -> Vec<Arc<Mutex<AffixDefinition>>> {
-        self.affixes.values().map(|def| def.to_owned()).collect()
-    }
+/// Version 3 refers to stats by their ID in the stat registry, rather than by name.
+fn stat_names_to_ids(definitions: &mut serde_json::Value) -> Result<(), String> {
+    for_each_stat(definitions, |stat| match stat.get_mut("stat") {
+        Some(stat) => migrate_legacy_stat(stat),
+        None => Ok(()),
+    })
+}
 
-    fn add_definition(&mut self, definition: AffixDefinition) {
-        self.affixes
-            .insert(definition.id, Arc::new(Mutex::new(definition)));
+/// Calls `migrate` with every stat of every tier of every affix.
+fn for_each_stat(
+    definitions: &mut serde_json::Value,
+    mut migrate: impl FnMut(&mut serde_json::Map<String, serde_json::Value>) -> Result<(), String>,
+) -> Result<(), String> {
+    let definitions = definitions
+        .as_array_mut()
+        .ok_or("definitions are not a list")?;
+
+    for definition in definitions {
+        let Some(tiers) = definition
+            .get_mut("tiers")
+            .and_then(|tiers| tiers.as_object_mut())
+        else {
+            continue;
+        };
+
+        for tier in tiers.values_mut() {
+            let Some(stats) = tier.get_mut("stats").and_then(|stats| stats.as_array_mut()) else {
+                continue;
+            };
+
+            for stat in stats.iter_mut().filter_map(|stat| stat.as_object_mut()) {
+                migrate(stat)?;
+            }
+        }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        data::DataLoadError,
+        stat::{StatModifierKind, HEALTH},
+    };
 
     #[test]
     fn init_affix_database() {
         let _ = AffixDefinitionDatabase::initialize();
     }
+
+    #[test]
+    fn missing_file_is_io_error() {
+        let result = AffixDefinitionDatabase::load_from("does/not/exist/affix.json", &());
+
+        assert!(matches!(result, Err(DataLoadError::Io { .. })));
+    }
+
+    #[test]
+    fn malformed_file_reports_position() {
+        let mut path = std::env::temp_dir();
+        path.push("cypher_malformed_affix.json");
+        std::fs::write(&path, "[\n{\"id\": 1,\n\"placement\": }\n]").unwrap();
+
+        let result = AffixDefinitionDatabase::load_from(path.to_str().unwrap(), &());
+
+        let Err(DataLoadError::Malformed { line, .. }) = result else {
+            panic!("expected malformed data error");
+        };
+        assert_eq!(line, 3);
+    }
+
+    #[test]
+    fn unversioned_stats_migrate_to_flat_registry_stats() {
+        let mut path = std::env::temp_dir();
+        path.push("cypher_unversioned_affix.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":1,"placement":"Prefix","tiers":{"1":{"tier":1,"stats":[{"stat":"Health","value":{"exact":5.0}}]}},"name":"affix.health"}]"#,
+        )
+        .unwrap();
+
+        let affix_db = AffixDefinitionDatabase::load_from(path.to_str().unwrap(), &()).unwrap();
+
+        let affix = affix_db.definition(1).unwrap();
+        assert_eq!(affix.tiers[&1].stats[0].stat, HEALTH);
+        assert_eq!(affix.tiers[&1].stats[0].kind, StatModifierKind::Flat);
+    }
 }
@@ -1,5 +1,13 @@
-use super::placement::AffixPlacement;
-use crate::{data::DataDefinition, stat::Stat};
+use super::{
+    placement::AffixPlacement,
+    tag::{AffixModGroup, AffixTag},
+};
+use crate::{
+    data::DataDefinition,
+    localization::LocalizationKey,
+    stat::{StatId, StatModifierKind},
+    stat_condition::StatCondition,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -15,12 +23,22 @@ pub struct AffixDefinition {
 
     pub tiers: BTreeMap<AffixTierId, AffixDefinitionTier>,
 
-    pub name: String,
+    /// Key of the display name in the string tables.
+    pub name: LocalizationKey,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<AffixTag>,
+
+    /// If set, no other affix of this group can roll on the same item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_group: Option<AffixModGroup>,
 }
 
 impl DataDefinition for AffixDefinition {
     type DefinitionTypeId = AffixDefinitionId;
 
+    const TYPE_NAME: &'static str = "affix";
+
     fn id(&self) -> u64 {
         self.id as u64
     }
@@ -30,6 +48,11 @@ impl DataDefinition for AffixDefinition {
             && self.placement != AffixPlacement::Invalid
             && !self.tiers.is_empty()
             && self.tiers.iter().all(|tier| tier.1.validate())
+            && self.tags.iter().all(|tag| !tag.is_empty())
+            && self
+                .mod_group
+                .as_ref()
+                .is_none_or(|group| !group.is_empty())
     }
 }
 
@@ -66,11 +89,26 @@ pub struct AffixDefinitionTier {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub precision_places: Option<u32>,
+
+    /// Relative chance of rolling this tier over the affix's other eligible tiers. [AffixDefinitionTier::DEFAULT_WEIGHT] if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u64>,
 }
 
 impl AffixDefinitionTier {
+    pub const DEFAULT_WEIGHT: u64 = 100;
+
+    pub fn weight(&self) -> u64 {
+        self.weight.unwrap_or(Self::DEFAULT_WEIGHT)
+    }
+
     pub fn validate(&self) -> bool {
-        self.tier > 0 && !self.stats.is_empty()
+        self.tier > 0
+            && !self.stats.is_empty()
+            && self
+                .stats
+                .iter()
+                .all(|stat| stat.condition.is_none_or(|condition| condition.validate()))
     }
 }
 
@@ -92,13 +130,28 @@ impl std::fmt::Display for AffixDefinitionValue {
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
 pub struct AffixDefinitionStat {
-    pub stat: Stat,
+    pub stat: StatId,
+
+    pub kind: StatModifierKind,
 
     pub value: AffixDefinitionValue,
+
+    /// If set, the rolled modifier only applies while this holds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<StatCondition>,
+
+    /// Key of the text template this stat is shown with, overriding the stat's own template.
+    /// See [crate::stat_text] for the placeholders a template can use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<LocalizationKey>,
 }
 
 impl std::fmt::Display for AffixDefinitionStat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?} [{}]", self.stat, self.value)
+        write!(f, "stat {} {:?} [{}]", self.stat, self.kind, self.value)?;
+        if let Some(condition) = &self.condition {
+            write!(f, " ({condition:?})")?;
+        }
+        Ok(())
     }
 }
@@ -5,10 +5,10 @@ use crate::data::DataDefinitionDatabase;
 use crate::stat::StatList;
 use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 pub struct AffixInstanceVecDeserializer {
-    pub affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
+    pub affix_db: Arc<AffixDefinitionDatabase>,
 }
 
 impl<'de> DeserializeSeed<'de> for AffixInstanceVecDeserializer {
@@ -19,7 +19,7 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceVecDeserializer {
         D: serde::Deserializer<'de>,
     {
         struct AffixInstanceVecVisitor {
-            affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
+            affix_db: Arc<AffixDefinitionDatabase>,
         }
 
         impl<'de> Visitor<'de> for AffixInstanceVecVisitor {
@@ -52,7 +52,7 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceVecDeserializer {
 }
 
 struct AffixInstanceDeserializer {
-    affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
+    affix_db: Arc<AffixDefinitionDatabase>,
 }
 
 impl<'de> DeserializeSeed<'de> for AffixInstanceDeserializer {
@@ -109,7 +109,7 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceDeserializer {
         }
 
         struct AffixInstanceVisitor {
-            affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
+            affix_db: Arc<AffixDefinitionDatabase>,
         }
 
         impl<'de> Visitor<'de> for AffixInstanceVisitor {
@@ -123,7 +123,7 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceDeserializer {
             where
                 V: MapAccess<'de>,
             {
-                let mut maybe_def: Option<Arc<Mutex<AffixDefinition>>> = None;
+                let mut maybe_def: Option<Arc<AffixDefinition>> = None;
                 let mut tier: Option<AffixTierId> = None;
                 let mut stats: Option<StatList> = None;
 
@@ -131,7 +131,10 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceDeserializer {
                     match key {
                         Field::AffixDefId => {
                             let affix_id: AffixDefinitionId = map.next_value()?;
-                            maybe_def = self.affix_db.lock().unwrap().definition(affix_id);
+                            maybe_def =
+                                Some(self.affix_db.definition(affix_id).ok_or_else(|| {
+                                    serde::de::Error::custom(format!("unknown affix {affix_id}"))
+                                })?);
                         }
                         Field::Tier => tier = Some(map.next_value()?),
                         Field::Stats => stats = Some(map.next_value()?),
@@ -139,9 +142,10 @@ impl<'de> DeserializeSeed<'de> for AffixInstanceDeserializer {
                 }
 
                 Ok(AffixInstance {
-                    definition: maybe_def.unwrap(),
-                    tier: tier.unwrap(),
-                    stats: stats.unwrap(),
+                    definition: maybe_def
+                        .ok_or_else(|| serde::de::Error::missing_field("affix_def_id"))?,
+                    tier: tier.ok_or_else(|| serde::de::Error::missing_field("tier"))?,
+                    stats: stats.ok_or_else(|| serde::de::Error::missing_field("stats"))?,
                 })
             }
         }
@@ -1,4 +1,4 @@
-use rand::{seq::IteratorRandom, thread_rng, Rng};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
 
 use crate::{
     data::DataInstanceGenerator,
@@ -6,7 +6,7 @@ use crate::{
 };
 
 use super::{
-    definition::{AffixDefinition, AffixTierId},
+    definition::{AffixDefinition, AffixDefinitionTier, AffixTierId},
     instance::AffixInstance,
 };
 
@@ -17,8 +17,37 @@ pub struct AffixGenerationCriteria {
     /// Maximum tier of [AffixInstance] to generate, if any.
     pub maximum_tier: Option<AffixTierId>,
 
+    /// Minimum tier of [AffixInstance] to generate, if any.
+    pub minimum_tier: Option<AffixTierId>,
+
     /// Item level.
     pub item_level: Option<u8>,
+
+    /// Lets loot sources favour higher tiers. Each eligible tier's weight is multiplied by `1 + tier_bias`
+    /// once for every eligible tier below it, so 0 rolls by weight alone and negative values favour lower tiers.
+    pub tier_bias: f32,
+}
+
+impl AffixGenerator {
+    /// Every tier of `definition` that can roll under `criteria`, lowest first, with the weight it rolls with.
+    pub fn tier_weights<'a>(
+        definition: &'a AffixDefinition,
+        criteria: &AffixGenerationCriteria,
+    ) -> Vec<(&'a AffixDefinitionTier, f64)> {
+        let bias = (1. + criteria.tier_bias as f64).max(0.);
+
+        definition
+            .tiers
+            .iter()
+            .filter(|(id, tier)| {
+                tier.item_level_req.unwrap_or(0) <= criteria.item_level.unwrap_or(0)
+                    && criteria.minimum_tier.is_none_or(|minimum| **id >= minimum)
+                    && criteria.maximum_tier.is_none_or(|maximum| **id <= maximum)
+            })
+            .enumerate()
+            .map(|(rank, (_id, tier))| (tier, tier.weight() as f64 * bias.powi(rank as i32)))
+            .collect()
+    }
 }
 
 fn round_to(num: f32, decimal_places: u32) -> f32 {
@@ -31,42 +60,39 @@ impl DataInstanceGenerator<AffixDefinition, AffixInstance, AffixGenerationCriter
 {
     type DataDependencies = ();
 
-    fn generate(
+    fn generate<R: Rng + ?Sized>(
         &self,
-        definition: std::sync::Arc<std::sync::Mutex<AffixDefinition>>,
+        definition: std::sync::Arc<AffixDefinition>,
         criteria: &AffixGenerationCriteria,
         _databases: &Self::DataDependencies,
+        rng: &mut R,
     ) -> Option<AffixInstance> {
-        let def = definition.lock().unwrap();
+        let tier_weights = Self::tier_weights(&definition, criteria);
+        let distribution =
+            WeightedIndex::new(tier_weights.iter().map(|(_tier, weight)| *weight)).ok()?;
+        let tier = tier_weights[distribution.sample(rng)].0;
 
-        let (_id, tier) = def
-            .tiers
-            .iter()
-            .filter(|(_id, tier)| {
-                tier.item_level_req.unwrap_or(0) <= criteria.item_level.unwrap_or(0)
-            })
-            .choose(&mut rand::thread_rng())?;
+        let mut stat_list = StatList::from(&[]);
+        for stat in &tier.stats {
+            let modifier = StatModifier(
+                stat.stat,
+                round_to(
+                    match stat.value {
+                        super::definition::AffixDefinitionValue::Exact(val) => val,
+                        super::definition::AffixDefinitionValue::Range(lower, upper) => {
+                            rng.gen_range(lower..upper)
+                        }
+                    },
+                    tier.precision_places.unwrap_or(0),
+                ),
+                stat.kind,
+            );
 
-        let stats = tier
-            .stats
-            .iter()
-            .map(|stat| {
-                StatModifier(
-                    stat.stat,
-                    round_to(
-                        match stat.value {
-                            super::definition::AffixDefinitionValue::Exact(val) => val,
-                            super::definition::AffixDefinitionValue::Range(lower, upper) => {
-                                thread_rng().gen_range(lower..upper)
-                            }
-                        },
-                        tier.precision_places.unwrap_or(0),
-                    ),
-                )
-            })
-            .collect::<Vec<StatModifier>>();
-
-        let stat_list = StatList::from(stats.as_slice());
+            match stat.condition {
+                Some(condition) => stat_list.add_conditional_mod(condition, &modifier),
+                None => stat_list.add_mod(&modifier),
+            };
+        }
 
         Some(AffixInstance {
             definition: definition.clone(),
@@ -75,3 +101,110 @@ impl DataInstanceGenerator<AffixDefinition, AffixInstance, AffixGenerationCriter
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        affix::{
+            definition::{AffixDefinitionStat, AffixDefinitionValue},
+            placement::AffixPlacement,
+        },
+        stat::{StatModifierKind, HEALTH},
+    };
+
+    fn definition() -> Arc<AffixDefinition> {
+        let tier = |tier: AffixTierId, weight: u64, item_level_req: Option<u8>| {
+            (
+                tier,
+                AffixDefinitionTier {
+                    tier,
+                    stats: vec![AffixDefinitionStat {
+                        stat: HEALTH,
+                        kind: StatModifierKind::Flat,
+                        value: AffixDefinitionValue::Exact(tier as f32),
+                        condition: None,
+                        template: None,
+                    }],
+                    item_level_req,
+                    precision_places: None,
+                    weight: Some(weight),
+                },
+            )
+        };
+
+        Arc::new(AffixDefinition {
+            id: 1,
+            placement: AffixPlacement::Prefix,
+            tiers: BTreeMap::from([
+                tier(1, 600, None),
+                tier(2, 300, None),
+                tier(3, 100, None),
+                tier(4, 100, Some(50)),
+            ]),
+            name: String::from("affix.health"),
+            tags: vec![],
+            mod_group: None,
+        })
+    }
+
+    /// How often each tier rolls out of 10,000 seeded rolls.
+    fn tier_counts(criteria: &AffixGenerationCriteria) -> BTreeMap<AffixTierId, u32> {
+        let definition = definition();
+        let mut rng = StdRng::seed_from_u64(18);
+
+        let mut counts = BTreeMap::new();
+        for _ in 0..10_000 {
+            let affix = AffixGenerator {}
+                .generate(definition.clone(), criteria, &(), &mut rng)
+                .unwrap();
+            *counts.entry(affix.tier).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn tiers_roll_by_weight() {
+        let counts = tier_counts(&AffixGenerationCriteria::default());
+
+        assert_eq!(counts.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!((5_700..6_300).contains(&counts[&1]), "{counts:?}");
+        assert!((2_700..3_300).contains(&counts[&2]), "{counts:?}");
+        assert!((800..1_200).contains(&counts[&3]), "{counts:?}");
+    }
+
+    #[test]
+    fn minimum_and_maximum_tiers_are_honored() {
+        let counts = tier_counts(&AffixGenerationCriteria {
+            minimum_tier: Some(2),
+            maximum_tier: Some(3),
+            item_level: Some(50),
+            ..Default::default()
+        });
+        assert_eq!(counts.keys().copied().collect::<Vec<_>>(), vec![2, 3]);
+
+        let criteria = AffixGenerationCriteria {
+            minimum_tier: Some(4),
+            ..Default::default()
+        };
+        assert!(AffixGenerator {}
+            .generate(definition(), &criteria, &(), &mut StdRng::seed_from_u64(18))
+            .is_none());
+    }
+
+    #[test]
+    fn tier_bias_favours_higher_tiers() {
+        let unbiased = tier_counts(&AffixGenerationCriteria::default());
+        let biased = tier_counts(&AffixGenerationCriteria {
+            tier_bias: 2.,
+            ..Default::default()
+        });
+
+        assert!(biased[&1] < unbiased[&1]);
+        assert!(biased[&3] > unbiased[&3]);
+    }
+}
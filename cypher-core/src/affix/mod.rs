@@ -4,3 +4,4 @@ pub mod deserializer;
 pub mod generator;
 pub mod instance;
 pub mod placement;
+pub mod tag;
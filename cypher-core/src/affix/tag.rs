@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::affix_pool::member::AffixPoolMember;
+
+/// Describes what an affix does, such as `life`, `speed` or `attack`, so pools and loot sources can pick affix families.
+pub type AffixTag = String;
+
+/// Family of near-duplicate affixes, such as every affix granting flat health. An item rolls at most one affix per group.
+pub type AffixModGroup = String;
+
+/// Which tags affixes must, may not, or are more likely to have when rolled.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AffixTagRules {
+    /// Affixes must have every one of these tags.
+    #[serde(default)]
+    pub required: Vec<AffixTag>,
+
+    /// Affixes with any of these tags never roll.
+    #[serde(default)]
+    pub forbidden: Vec<AffixTag>,
+
+    /// Weights of affixes with any of these tags are multiplied by the tag's factor.
+    #[serde(default)]
+    pub boosted: BTreeMap<AffixTag, u64>,
+}
+
+impl AffixTagRules {
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.forbidden.is_empty() && self.boosted.is_empty()
+    }
+
+    /// Every tag these rules mention.
+    pub fn tags(&self) -> impl Iterator<Item = &AffixTag> {
+        self.required
+            .iter()
+            .chain(&self.forbidden)
+            .chain(self.boosted.keys())
+    }
+
+    /// Whether an affix with `tags` can roll under these rules.
+    pub fn allows(&self, tags: &[AffixTag]) -> bool {
+        self.required.iter().all(|tag| tags.contains(tag))
+            && !self.forbidden.iter().any(|tag| tags.contains(tag))
+    }
+
+    /// Weight `member` rolls with under these rules, or `None` if it can't roll.
+    pub fn weigh(&self, member: &AffixPoolMember) -> Option<u64> {
+        let tags = &member.affix_def.tags;
+        if !self.allows(tags) {
+            return None;
+        }
+
+        Some(
+            self.boosted
+                .iter()
+                .filter(|(tag, _)| tags.contains(tag))
+                .fold(member.weight, |weight, (_, factor)| {
+                    weight.saturating_mul(*factor)
+                }),
+        )
+    }
+}
@@ -1,99 +1,86 @@
-use std::sync::Mutex;
-use std::{collections::HashMap, sync::Arc};
-
-use serde::de::DeserializeSeed;
-
-use crate::data::{DataDefinition, DataDefinitionDatabase};
+use std::sync::Arc;
 
 use crate::affix::database::AffixDefinitionDatabase;
+use crate::data::{DataDefinitionDatabase, DefinitionRegistry, DefinitionStore};
 
 use super::definition::AffixPoolDefinition;
-use super::deserializer::AffixPoolDatabaseDeserializer;
 
 pub type AffixPoolDefinitionId = u32;
 
 pub struct AffixPoolDefinitionDatabase {
-    affix_pools: HashMap<AffixPoolDefinitionId, Arc<Mutex<AffixPoolDefinition>>>,
+    affix_pools: DefinitionStore<AffixPoolDefinition>,
 }
 
 impl DataDefinitionDatabase<AffixPoolDefinition> for AffixPoolDefinitionDatabase {
-    type DataDependencies = Arc<Mutex<AffixDefinitionDatabase>>;
+    type DataDependencies = Arc<AffixDefinitionDatabase>;
 
-    fn load_from<S: Into<String>>(path: S, dependencies: &Self::DataDependencies) -> Self {
-        let affix_file = String::from_utf8(std::fs::read(path.into()).unwrap()).unwrap();
-        let deserializer = AffixPoolDatabaseDeserializer::new(dependencies.clone());
-        let definitions = deserializer
-            .deserialize(&mut serde_json::Deserializer::from_str(affix_file.as_str()))
-            .unwrap();
-
-        let affix_pools = definitions
-            .into_iter()
-            .map(|pool| (pool.id, Arc::new(Mutex::new(pool))))
-            .collect::<HashMap<_, _>>();
+    const FILE_NAME: &'static str = "affix_pool.json";
 
+    fn from_store(affix_pools: DefinitionStore<AffixPoolDefinition>) -> Self {
         AffixPoolDefinitionDatabase { affix_pools }
     }
 
-    fn write_to<S: Into<String>>(&self, path: S) {
-        let definition_clones = self
-            .affix_pools
-            .values()
-            .map(|def| def.lock().unwrap().to_owned())
-            .collect::<Vec<AffixPoolDefinition>>();
-
-        let serialized = serde_json::ser::to_string(&definition_clones)
-            .expect("failed to serialize affix pool database");
-
-        std::fs::write(path.into(), serialized).expect("failed to write serialized data to path");
-    }
-
-    fn validate(&self) -> bool {
-        !self.affix_pools.is_empty()
-            && self
-                .affix_pools
-                .iter()
-                .all(|(_id, pool_def)| pool_def.lock().unwrap().validate())
+    fn store(&self) -> &DefinitionStore<AffixPoolDefinition> {
+        &self.affix_pools
     }
 
-    fn definition(&self, id: AffixPoolDefinitionId) -> Option<Arc<Mutex<AffixPoolDefinition>>> {
-        self.affix_pools.get(&id).map(|arc| arc.to_owned())
-    }
-
-    fn definitions(&self) -> Vec<Arc<Mutex<AffixPoolDefinition>>> {
-        self.affix_pools
-            .values()
-            .map(|def| def.to_owned())
-            .collect()
-    }
-
-    fn add_definition(&mut self, definition: AffixPoolDefinition) {
-        self.affix_pools
-            .insert(definition.id, Arc::new(Mutex::new(definition)));
+    fn registry(affix_db: &Self::DataDependencies) -> DefinitionRegistry {
+        DefinitionRegistry::default().with(affix_db)
     }
 }
 
 impl AffixPoolDefinitionDatabase {
-    pub fn initialize(affix_db: Arc<Mutex<AffixDefinitionDatabase>>) -> Self {
+    pub fn initialize(affix_db: Arc<AffixDefinitionDatabase>) -> Self {
         let mut path = std::env::current_dir().unwrap();
         path.push("..");
         path.push("cypher-core");
         path.push("data");
-        path.push("affix_pool.json");
+        path.push(Self::FILE_NAME);
 
         Self::load_from(path.to_str().unwrap(), &affix_db)
+            .expect("failed to load affix pool database")
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
 
     use super::AffixPoolDefinitionDatabase;
-    use crate::affix::database::AffixDefinitionDatabase;
+    use crate::{
+        affix::database::AffixDefinitionDatabase,
+        data::{DataDefinitionDatabase, DataLoadError},
+    };
 
     #[test]
     fn init_affix_pool_database() {
-        let affix_db = Arc::new(Mutex::new(AffixDefinitionDatabase::initialize()));
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
         let _ = AffixPoolDefinitionDatabase::initialize(affix_db);
     }
+
+    #[test]
+    fn unknown_affix_reports_missing_reference() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_dangling_affix_pool.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":3,"members":[{"affix_id":1,"weight":1},{"affix_id":9999,"weight":1}],"name":"Dangling"}]"#,
+        )
+        .unwrap();
+
+        let result = AffixPoolDefinitionDatabase::load_from(path.to_str().unwrap(), &affix_db);
+
+        let Err(DataLoadError::MissingReference {
+            definition_id,
+            referenced_id,
+            ..
+        }) = result
+        else {
+            panic!("expected missing reference error");
+        };
+        assert_eq!(definition_id, 3);
+        assert_eq!(referenced_id, 9999);
+    }
 }
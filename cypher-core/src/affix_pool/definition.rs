@@ -1,22 +1,29 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::data::DataDefinition;
+use crate::{affix::tag::AffixTagRules, data::DataDefinition, localization::LocalizationKey};
 
 use super::{database::AffixPoolDefinitionId, member::AffixPoolMember};
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AffixPoolDefinition {
     pub id: AffixPoolDefinitionId,
 
     /// All [AffixPoolMember]s that can roll as part of this [AffixPoolDefinition].
     pub members: Vec<AffixPoolMember>,
 
-    pub name: String,
+    /// Key of the display name in the string tables.
+    pub name: LocalizationKey,
+
+    /// Tags members must, may not, or are more likely to have to roll from this pool.
+    #[serde(default, skip_serializing_if = "AffixTagRules::is_empty")]
+    pub tag_rules: AffixTagRules,
 }
 
 impl DataDefinition for AffixPoolDefinition {
     type DefinitionTypeId = AffixPoolDefinitionId;
 
+    const TYPE_NAME: &'static str = "affix pool";
+
     fn id(&self) -> u64 {
         self.id as u64
     }
@@ -33,6 +40,20 @@ impl AffixPoolDefinition {
             id: 0,
             members,
             name: String::from("from_members temp"),
+            tag_rules: AffixTagRules::default(),
         }
     }
+
+    /// Every member that can roll under this pool's [AffixTagRules], with the weight it rolls with.
+    pub fn eligible_members(&self) -> Vec<AffixPoolMember> {
+        self.members
+            .iter()
+            .filter_map(|member| {
+                Some(AffixPoolMember {
+                    affix_def: member.affix_def.clone(),
+                    weight: self.tag_rules.weigh(member)?,
+                })
+            })
+            .collect()
+    }
 }
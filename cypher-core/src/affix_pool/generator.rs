@@ -1,17 +1,15 @@
-use std::{
-    collections::HashSet,
-    sync::{Arc, Mutex},
-};
+use std::{collections::HashSet, sync::Arc};
 
-use rand::{distributions::WeightedIndex, prelude::Distribution};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
 
 use crate::{
     affix::{
         database::AffixDefinitionDatabase,
         definition::{AffixDefinition, AffixDefinitionId},
         placement::AffixPlacement,
+        tag::{AffixModGroup, AffixTagRules},
     },
-    data::{DataDefinitionDatabase, DataInstanceGenerator},
+    data::DataInstanceGenerator,
 };
 
 use super::{definition::AffixPoolDefinition, member::AffixPoolMember};
@@ -29,34 +27,36 @@ pub struct AffixPoolGenerationCriteria {
 
     /// [AffixPlacement] to force, if any. If not Suffix or Prefix, can be either.
     pub placement: Option<AffixPlacement>,
+
+    /// Which mod groups should be excluded, if any, such as those of affixes already on the item.
+    pub disallowed_mod_groups: Option<HashSet<AffixModGroup>>,
+
+    /// Tags affixes must, may not, or are more likely to have, on top of the pool's own [AffixTagRules].
+    pub tag_rules: AffixTagRules,
 }
 
-impl
-    DataInstanceGenerator<
-        AffixPoolDefinition,
-        Arc<Mutex<AffixDefinition>>,
-        AffixPoolGenerationCriteria,
-    > for AffixPoolGenerator
+impl DataInstanceGenerator<AffixPoolDefinition, Arc<AffixDefinition>, AffixPoolGenerationCriteria>
+    for AffixPoolGenerator
 {
-    type DataDependencies = Arc<Mutex<AffixDefinitionDatabase>>;
+    type DataDependencies = Arc<AffixDefinitionDatabase>;
 
-    fn generate(
+    fn generate<R: Rng + ?Sized>(
         &self,
-        definition: std::sync::Arc<std::sync::Mutex<AffixPoolDefinition>>,
+        definition: std::sync::Arc<AffixPoolDefinition>,
         criteria: &AffixPoolGenerationCriteria,
-        databases: &Self::DataDependencies,
-    ) -> Option<Arc<Mutex<AffixDefinition>>> {
-        let definition = definition.lock().unwrap();
+        _databases: &Self::DataDependencies,
+        rng: &mut R,
+    ) -> Option<Arc<AffixDefinition>> {
         let filtered = definition
-            .members
-            .iter()
+            .eligible_members()
+            .into_iter()
             .filter(|member| {
                 criteria.allowed_ids.is_none()
                     || criteria
                         .allowed_ids
                         .as_ref()
                         .unwrap()
-                        .contains(&member.affix_def.lock().unwrap().id)
+                        .contains(&member.affix_def.id)
             })
             .filter(|member| {
                 criteria.disallowed_ids.is_none()
@@ -64,14 +64,28 @@ impl
                         .disallowed_ids
                         .as_ref()
                         .unwrap()
-                        .contains(&member.affix_def.lock().unwrap().id)
+                        .contains(&member.affix_def.id)
             })
             .filter(|member| {
                 criteria.placement.is_none()
-                    || *criteria.placement.as_ref().unwrap()
-                        == member.affix_def.lock().unwrap().placement
+                    || *criteria.placement.as_ref().unwrap() == member.affix_def.placement
+            })
+            .filter(|member| {
+                criteria.disallowed_mod_groups.is_none()
+                    || member.affix_def.mod_group.as_ref().is_none_or(|mod_group| {
+                        !criteria
+                            .disallowed_mod_groups
+                            .as_ref()
+                            .unwrap()
+                            .contains(mod_group)
+                    })
+            })
+            .filter_map(|member| {
+                Some(AffixPoolMember {
+                    weight: criteria.tag_rules.weigh(&member)?,
+                    affix_def: member.affix_def,
+                })
             })
-            .map(|member| member.to_owned())
             .collect::<Vec<AffixPoolMember>>();
 
         let weights = filtered
@@ -80,17 +94,115 @@ impl
             .collect::<Vec<u64>>();
 
         if let Ok(distribution) = WeightedIndex::new(weights.as_slice()) {
-            let mut rng = rand::thread_rng();
-            let affix_id = filtered[distribution.sample(&mut rng)]
-                .affix_def
-                .lock()
-                .unwrap()
-                .id;
-
-            let affix_database = databases;
-            Some(affix_database.lock().unwrap().definition(affix_id).unwrap())
+            Some(Arc::clone(&filtered[distribution.sample(rng)].affix_def))
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        affix::tag::AffixTag,
+        data::{DataDefinitionDatabase, DefinitionStore},
+    };
+
+    fn affix(id: AffixDefinitionId, tags: &[&str], mod_group: &str) -> AffixDefinition {
+        AffixDefinition {
+            id,
+            placement: AffixPlacement::Prefix,
+            tiers: BTreeMap::new(),
+            name: format!("affix.{id}"),
+            tags: tags.iter().map(|tag| AffixTag::from(*tag)).collect(),
+            mod_group: Some(String::from(mod_group)),
+        }
+    }
+
+    /// Every affix that rolls at least once out of 1,000 seeded rolls.
+    fn rolled_ids(
+        pool: &AffixPoolDefinition,
+        criteria: &AffixPoolGenerationCriteria,
+    ) -> HashSet<AffixDefinitionId> {
+        let affix_db = Arc::new(AffixDefinitionDatabase::from_store(DefinitionStore::new(
+            pool.members
+                .iter()
+                .map(|member| member.affix_def.as_ref().clone())
+                .collect(),
+        )));
+        let pool = Arc::new(pool.clone());
+        let mut rng = StdRng::seed_from_u64(19);
+
+        (0..1_000)
+            .filter_map(|_| {
+                AffixPoolGenerator {}.generate(pool.clone(), criteria, &affix_db, &mut rng)
+            })
+            .map(|affix| affix.id)
+            .collect()
+    }
+
+    #[test]
+    fn tag_rules_and_mod_groups_filter_members() {
+        let member = |affix: AffixDefinition| AffixPoolMember {
+            affix_def: Arc::new(affix).into(),
+            weight: 1,
+        };
+        let mut pool = AffixPoolDefinition::with_members(vec![
+            member(affix(1, &["life"], "health")),
+            member(affix(2, &["life", "attack"], "health")),
+            member(affix(3, &["speed"], "move_speed")),
+        ]);
+
+        assert_eq!(
+            rolled_ids(&pool, &AffixPoolGenerationCriteria::default()),
+            HashSet::from([1, 2, 3])
+        );
+
+        pool.tag_rules.forbidden.push(AffixTag::from("attack"));
+        assert_eq!(
+            rolled_ids(&pool, &AffixPoolGenerationCriteria::default()),
+            HashSet::from([1, 3])
+        );
+
+        let criteria = AffixPoolGenerationCriteria {
+            disallowed_mod_groups: Some(HashSet::from([String::from("move_speed")])),
+            ..Default::default()
+        };
+        assert_eq!(rolled_ids(&pool, &criteria), HashSet::from([1]));
+
+        let criteria = AffixPoolGenerationCriteria {
+            tag_rules: AffixTagRules {
+                required: vec![AffixTag::from("speed")],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(rolled_ids(&pool, &criteria), HashSet::from([3]));
+    }
+
+    #[test]
+    fn boosted_tags_roll_more_often() {
+        let rules = AffixTagRules {
+            boosted: BTreeMap::from([(AffixTag::from("life"), 5)]),
+            ..Default::default()
+        };
+        let member = |affix: AffixDefinition| AffixPoolMember {
+            affix_def: Arc::new(affix).into(),
+            weight: 10,
+        };
+
+        assert_eq!(
+            rules.weigh(&member(affix(1, &["life"], "health"))),
+            Some(50)
+        );
+        assert_eq!(
+            rules.weigh(&member(affix(2, &["speed"], "move_speed"))),
+            Some(10)
+        );
+    }
+}
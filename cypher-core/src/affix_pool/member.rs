@@ -1,26 +1,13 @@
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
 
-use serde::{Serialize, Serializer};
+use crate::{affix::definition::AffixDefinition, data::DefRef};
 
-use crate::affix::definition::AffixDefinition;
-
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AffixPoolMember {
-    #[serde(serialize_with = "serialize_affix_definition_member")]
     #[serde(rename = "affix_id")]
     /// What affix will be generated when selected.
-    pub affix_def: Arc<Mutex<AffixDefinition>>,
+    pub affix_def: DefRef<AffixDefinition>,
 
     /// Weight indicates how often this member will be chosen. A higher value = more common.
     pub weight: u64,
 }
-
-fn serialize_affix_definition_member<S>(
-    definition: &Arc<Mutex<AffixDefinition>>,
-    s: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    s.serialize_u64(definition.lock().unwrap().id.into())
-}
@@ -2,5 +2,3 @@ pub mod database;
 pub mod definition;
 pub mod generator;
 pub mod member;
-
-mod deserializer;
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    stat::{
+        StatId, StatList, ADDED_CHAOS_DAMAGE, ADDED_ELEMENTAL_DAMAGE, ADDED_PHYSICAL_DAMAGE, ARMOR,
+        CHAOS_RESISTANCE, ELEMENTAL_RESISTANCE,
+    },
+    stat_registry::database::StatRegistry,
+};
+
+/// Kind of damage a hit deals, each mitigated by different stats.
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+    /// Mitigated by [ARMOR].
+    Physical,
+
+    /// Mitigated by [ELEMENTAL_RESISTANCE].
+    Elemental,
+
+    /// Mitigated by [CHAOS_RESISTANCE].
+    Chaos,
+}
+
+impl DamageType {
+    /// Stat granting flat damage of this type to every hit.
+    pub fn added_damage_stat(&self) -> StatId {
+        match self {
+            DamageType::Physical => ADDED_PHYSICAL_DAMAGE,
+            DamageType::Elemental => ADDED_ELEMENTAL_DAMAGE,
+            DamageType::Chaos => ADDED_CHAOS_DAMAGE,
+        }
+    }
+
+    /// Stat holding the percentage of damage of this type that is resisted.
+    /// Physical damage isn't resisted, and is instead mitigated by [ARMOR].
+    pub fn resistance_stat(&self) -> Option<StatId> {
+        match self {
+            DamageType::Physical => None,
+            DamageType::Elemental => Some(ELEMENTAL_RESISTANCE),
+            DamageType::Chaos => Some(CHAOS_RESISTANCE),
+        }
+    }
+}
+
+/// Damage of one or more [DamageType]s, dealt by a single hit.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Damage {
+    amounts: BTreeMap<DamageType, f32>,
+}
+
+impl Damage {
+    /// Damage of a single type.
+    pub fn of(damage_type: DamageType, amount: f32) -> Damage {
+        let mut damage = Damage::default();
+        damage.add(damage_type, amount);
+        damage
+    }
+
+    /// Adds `amount` to the damage of `damage_type`.
+    pub fn add(&mut self, damage_type: DamageType, amount: f32) {
+        *self.amounts.entry(damage_type).or_default() += amount;
+    }
+
+    /// Damage of `damage_type`, or 0 if this hit doesn't deal any.
+    pub fn amount(&self, damage_type: DamageType) -> f32 {
+        self.amounts.get(&damage_type).copied().unwrap_or_default()
+    }
+
+    /// Every damage type this hit deals, and how much of each.
+    pub fn amounts(&self) -> impl Iterator<Item = (DamageType, f32)> + '_ {
+        self.amounts
+            .iter()
+            .map(|(damage_type, amount)| (*damage_type, *amount))
+    }
+
+    /// Sum of damage across every type, before mitigation.
+    pub fn total(&self) -> f32 {
+        self.amounts.values().sum()
+    }
+
+    /// This damage, plus the added damage of each type granted by the attacker's stats.
+    pub fn with_added_damage(
+        mut self,
+        stat_registry: &StatRegistry,
+        attacker: &StatList,
+    ) -> Damage {
+        for damage_type in DamageType::iter() {
+            let added = stat_registry.resolve(attacker, damage_type.added_damage_stat());
+            if added > 0. {
+                self.add(damage_type, added);
+            }
+        }
+        self
+    }
+}
+
+/// How much damage of a single type was dealt, and how much of it was mitigated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MitigatedDamage {
+    pub damage_type: DamageType,
+
+    /// Damage before mitigation.
+    pub incoming: f32,
+
+    /// Damage prevented by the defender's armor or resistance.
+    pub mitigated: f32,
+}
+
+impl MitigatedDamage {
+    /// Damage left to be dealt after mitigation.
+    pub fn taken(&self) -> f32 {
+        self.incoming - self.mitigated
+    }
+}
+
+/// Result of [mitigate], split per [DamageType].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DamageBreakdown {
+    pub damage: Vec<MitigatedDamage>,
+}
+
+impl DamageBreakdown {
+    /// Damage left to be dealt after mitigation, summed across every type.
+    pub fn total(&self) -> f32 {
+        self.damage.iter().map(MitigatedDamage::taken).sum()
+    }
+}
+
+/// Armor prevents a share of physical damage that shrinks as hits get bigger, so it favours many small hits.
+/// At this many times the hit's damage, armor prevents half of it.
+const ARMOR_PER_DAMAGE_FOR_HALF: f32 = 10.;
+
+/// Most physical damage that armor can ever prevent.
+const MAX_ARMOR_MITIGATION: f32 = 0.9;
+
+/// Turns `damage` into the damage a defender with `defender` stats takes.
+/// Resistances are clamped by their stat definition, so the registry sets the resistance cap.
+pub fn mitigate(
+    damage: &Damage,
+    stat_registry: &StatRegistry,
+    defender: &StatList,
+) -> DamageBreakdown {
+    let mut breakdown = DamageBreakdown::default();
+
+    for (damage_type, incoming) in damage.amounts() {
+        let mitigation = match damage_type.resistance_stat() {
+            Some(resistance_stat) => stat_registry.resolve(defender, resistance_stat) / 100.,
+            None => {
+                let armor = stat_registry.resolve(defender, ARMOR);
+                if armor > 0. {
+                    let mitigation = armor / (armor + ARMOR_PER_DAMAGE_FOR_HALF * incoming);
+                    mitigation.min(MAX_ARMOR_MITIGATION)
+                } else {
+                    0.
+                }
+            }
+        };
+
+        breakdown.damage.push(MitigatedDamage {
+            damage_type,
+            incoming,
+            mitigated: incoming * mitigation,
+        });
+    }
+
+    breakdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{DataDefinitionDatabase, DefinitionStore},
+        stat::{StatModifier, StatModifierKind},
+        stat_registry::definition::{StatDefinition, StatDisplayFormat},
+    };
+
+    fn stat_registry() -> StatRegistry {
+        let stat = |id: StatId, min: Option<f32>, max: Option<f32>| StatDefinition {
+            id,
+            name: format!("stat.{id}"),
+            default_value: 0.,
+            min,
+            max,
+            integer: false,
+            format: StatDisplayFormat::Number,
+        };
+
+        StatRegistry::from_store(DefinitionStore::new(vec![
+            stat(ARMOR, Some(0.), None),
+            stat(ELEMENTAL_RESISTANCE, Some(-100.), Some(75.)),
+            stat(CHAOS_RESISTANCE, Some(-100.), Some(75.)),
+            stat(ADDED_PHYSICAL_DAMAGE, Some(0.), None),
+            stat(ADDED_ELEMENTAL_DAMAGE, Some(0.), None),
+            stat(ADDED_CHAOS_DAMAGE, Some(0.), None),
+        ]))
+    }
+
+    #[test]
+    fn resistances_mitigate_their_type_up_to_the_cap() {
+        let stat_registry = stat_registry();
+        let defender = StatList::from(&[
+            StatModifier(ELEMENTAL_RESISTANCE, 90., StatModifierKind::Flat),
+            StatModifier(CHAOS_RESISTANCE, -50., StatModifierKind::Flat),
+        ]);
+
+        let mut damage = Damage::of(DamageType::Elemental, 100.);
+        damage.add(DamageType::Chaos, 10.);
+
+        let breakdown = mitigate(&damage, &stat_registry, &defender);
+        assert_eq!(breakdown.damage.len(), 2);
+        assert_eq!(breakdown.damage[0].damage_type, DamageType::Elemental);
+        assert_eq!(breakdown.damage[0].taken(), 25.);
+        assert_eq!(breakdown.damage[1].damage_type, DamageType::Chaos);
+        assert_eq!(breakdown.damage[1].taken(), 15.);
+        assert_eq!(breakdown.total(), 40.);
+    }
+
+    #[test]
+    fn armor_mitigates_small_hits_more() {
+        let stat_registry = stat_registry();
+        let defender = StatList::from(&[StatModifier(ARMOR, 100., StatModifierKind::Flat)]);
+
+        let small_hit = mitigate(
+            &Damage::of(DamageType::Physical, 10.),
+            &stat_registry,
+            &defender,
+        );
+        assert_eq!(small_hit.total(), 5.);
+
+        let big_hit = mitigate(
+            &Damage::of(DamageType::Physical, 100.),
+            &stat_registry,
+            &defender,
+        );
+        assert!(big_hit.damage[0].mitigated / 100. < 0.5);
+    }
+
+    #[test]
+    fn attacker_stats_add_damage() {
+        let stat_registry = stat_registry();
+        let attacker =
+            StatList::from(&[StatModifier(ADDED_CHAOS_DAMAGE, 3., StatModifierKind::Flat)]);
+
+        let damage =
+            Damage::of(DamageType::Physical, 1.).with_added_damage(&stat_registry, &attacker);
+        assert_eq!(damage.amount(DamageType::Physical), 1.);
+        assert_eq!(damage.amount(DamageType::Chaos), 3.);
+        assert_eq!(damage.amount(DamageType::Elemental), 0.);
+        assert_eq!(damage.total(), 4.);
+    }
+}
@@ -1,7 +1,24 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
 
-pub trait DataDefinition {
-    type DefinitionTypeId;
+use rand::Rng;
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use thiserror::Error;
+
+pub trait DataDefinition: Clone + Serialize + DeserializeOwned + 'static {
+    type DefinitionTypeId: Into<u64>;
+
+    /// Name of this kind of definition in error messages, such as `affix pool`.
+    const TYPE_NAME: &'static str;
 
     fn id(&self) -> u64;
 
@@ -10,25 +27,140 @@ pub trait DataDefinition {
     fn validate(&self) -> bool;
 }
 
-pub trait DataDefinitionDatabase<DataDefinitionType: DataDefinition> {
+/// An immutable database of definitions loaded from a single data file, shared between systems as an `Arc`.
+/// Implementors only provide storage and their dependencies; loading, writing and lookups are shared.
+/// Changes are made by loading the data again, or through a [DatabaseBuilder].
+pub trait DataDefinitionDatabase<DataDefinitionType: DataDefinition>: Sized {
     type DataDependencies;
 
-    fn load_from<S: Into<String>>(path: S, dependencies: &Self::DataDependencies) -> Self;
+    /// Name of the file this database is stored in, within a game data directory.
+    const FILE_NAME: &'static str;
+
+    /// Creates a database holding the loaded `definitions`.
+    fn from_store(definitions: DefinitionStore<DataDefinitionType>) -> Self;
+
+    fn store(&self) -> &DefinitionStore<DataDefinitionType>;
+
+    /// Databases that this database's [DefRef]s are resolved against.
+    fn registry(_dependencies: &Self::DataDependencies) -> DefinitionRegistry {
+        DefinitionRegistry::default()
+    }
+
+    /// Checks loaded definitions against each other, such as for references between them that form a cycle.
+    /// Runs once every definition is loaded, and fails the load with the returned reason.
+    fn check_definitions(_definitions: &DefinitionStore<DataDefinitionType>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Loads a database from a single data file.
+    fn load_from<S: Into<String>>(
+        path: S,
+        dependencies: &Self::DataDependencies,
+    ) -> Result<Self, DataLoadError> {
+        Self::load_from_layers(&[path.into()], dependencies).map(|(database, _)| database)
+    }
+
+    /// Loads a database from the same data file in several data roots, merged in order by [deserialize_data_layers].
+    /// Also returns which layer each definition came from.
+    fn load_from_layers(
+        paths: &[String],
+        dependencies: &Self::DataDependencies,
+    ) -> Result<(Self, DefinitionLayers), DataLoadError> {
+        let (definitions, definition_layers) =
+            Self::registry(dependencies).scope(|missing_reference| {
+                deserialize_data_layers(
+                    paths,
+                    DefinitionListSeed::<DataDefinitionType>(PhantomData),
+                    &Self::migrations(),
+                    missing_reference,
+                )
+            })?;
+
+        let store = DefinitionStore::new(definitions);
+        Self::check_definitions(&store).map_err(|reason| DataLoadError::InvalidDefinitions {
+            path: Self::FILE_NAME.to_owned(),
+            reason,
+        })?;
+
+        Ok((Self::from_store(store), definition_layers))
+    }
+
+    /// Writes all definitions to `path`, in the latest schema version.
+    fn write_to<S: Into<String>>(&self, path: S) {
+        let serialized =
+            data_file_to_string(&self.store().sorted_definitions(), &Self::migrations())
+                .unwrap_or_else(|err| panic!("failed to serialize {}: {err}", Self::FILE_NAME));
+
+        std::fs::write(path.into(), serialized).expect("failed to write serialized data to path");
+    }
+
+    /// Migrations that upgrade older versions of this database's data file to the latest schema.
+    fn migrations() -> DataMigrations {
+        DataMigrations::default()
+    }
 
-    fn write_to<S: Into<String>>(&self, path: S);
+    /// Loads a database from the binary form written by [DataDefinitionDatabase::to_bytes].
+    fn load_from_bytes(
+        bytes: &[u8],
+        dependencies: &Self::DataDependencies,
+    ) -> Result<Self, DataLoadError> {
+        let definitions = Self::registry(dependencies).scope(|missing_reference| {
+            ciborium::from_reader::<DefinitionList<DataDefinitionType>, _>(bytes)
+                .map(|list| list.0)
+                .map_err(|err| DataLoadError::from_bundle(Self::FILE_NAME, err, missing_reference))
+        })?;
+
+        let store = DefinitionStore::new(definitions);
+        Self::check_definitions(&store).map_err(|reason| DataLoadError::InvalidBundle {
+            path: Self::FILE_NAME.to_owned(),
+            reason,
+        })?;
+
+        Ok(Self::from_store(store))
+    }
+
+    /// Serializes all definitions into a compact binary form, such as for the game data bundle.
+    /// The form is self-describing, so fields left out of data files can be left out of it too.
+    /// Definitions are ordered by ID, so that the same data always produces the same bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        ciborium::into_writer(&self.store().sorted_definitions(), &mut bytes)
+            .expect("failed to serialize definitions");
+        bytes
+    }
 
     /// Returns whether a database has successfully loaded all data.
-    fn validate(&self) -> bool;
+    fn validate(&self) -> bool {
+        !self.store().is_empty()
+            && self
+                .store()
+                .values()
+                .all(|definition| definition.validate())
+    }
+
+    /// IDs that appeared more than once in the loaded file. Only the last definition with each ID is kept.
+    fn duplicate_ids(&self) -> Vec<u64> {
+        self.store().duplicate_ids().to_vec()
+    }
 
     /// Returns a data definition given it's ID.
     fn definition(
         &self,
         id: DataDefinitionType::DefinitionTypeId,
-    ) -> Option<Arc<Mutex<DataDefinitionType>>>;
+    ) -> Option<Arc<DataDefinitionType>> {
+        self.store().get(id.into())
+    }
 
-    fn definitions(&self) -> Vec<Arc<Mutex<DataDefinitionType>>>;
+    fn definitions(&self) -> Vec<Arc<DataDefinitionType>> {
+        self.store().values().cloned().collect()
+    }
 
-    fn add_definition(&mut self, definition: DataDefinitionType);
+    /// Starts editing a copy of this database. Databases can't be changed once built.
+    fn builder(&self) -> DatabaseBuilder<DataDefinitionType> {
+        DatabaseBuilder {
+            store: self.store().clone(),
+        }
+    }
 }
 
 pub trait DataInstanceGenerator<
@@ -39,10 +171,960 @@ pub trait DataInstanceGenerator<
 {
     type DataDependencies;
 
-    fn generate(
+    /// Generates an instance of `definition`.
+    /// All randomness is drawn from `rng`, so the same seeded RNG and definition always produce the same instance.
+    fn generate<R: Rng + ?Sized>(
         &self,
-        definition: Arc<Mutex<DataDefinitionType>>,
+        definition: Arc<DataDefinitionType>,
         criteria: &GeneratorCriteriaType,
         databases: &Self::DataDependencies,
+        rng: &mut R,
     ) -> Option<DataInstanceType>;
 }
+
+/// Reasons a [DataDefinitionDatabase] can fail to load from disk.
+#[derive(Debug, Error)]
+pub enum DataLoadError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("{path} is not valid UTF-8: {source}")]
+    InvalidUtf8 {
+        path: String,
+        source: std::string::FromUtf8Error,
+    },
+
+    #[error("{path}:{line}:{column}: {message}")]
+    Malformed {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("{path}:{line}:{column}: definition {definition_id} references {referenced_type} {referenced_id}, which does not exist")]
+    MissingReference {
+        path: String,
+        line: usize,
+        column: usize,
+        definition_id: u64,
+        referenced_type: &'static str,
+        referenced_id: u64,
+    },
+
+    #[error("{path} has invalid bundle data: {reason}")]
+    InvalidBundle { path: String, reason: String },
+
+    #[error("{path} has invalid definitions: {reason}")]
+    InvalidDefinitions { path: String, reason: String },
+
+    #[error("{path} has schema version {version}, but the newest supported version is {latest}")]
+    UnsupportedSchemaVersion {
+        path: String,
+        version: u32,
+        latest: u32,
+    },
+
+    #[error("{path} failed to migrate from schema version {version}: {reason}")]
+    Migration {
+        path: String,
+        version: u32,
+        reason: String,
+    },
+}
+
+impl DataLoadError {
+    /// Converts a JSON error raised while loading `path` into a [DataLoadError].
+    /// If a deserializer recorded a [MissingReference] while failing, that takes precedence over the JSON error message.
+    pub fn from_json(
+        path: &str,
+        error: serde_json::Error,
+        missing_reference: &MissingReferenceSlot,
+    ) -> DataLoadError {
+        match missing_reference.lock().unwrap().take() {
+            Some(missing) => DataLoadError::MissingReference {
+                path: path.to_owned(),
+                line: error.line(),
+                column: error.column(),
+                definition_id: missing.definition_id,
+                referenced_type: missing.referenced_type,
+                referenced_id: missing.referenced_id,
+            },
+            None => DataLoadError::Malformed {
+                path: path.to_owned(),
+                line: error.line(),
+                column: error.column(),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// Converts a binary error raised while loading `path` from a bundle into a [DataLoadError].
+    /// A recorded [MissingReference] is described instead of the error, as it says which definition is at fault.
+    pub fn from_bundle(
+        path: &str,
+        error: ciborium::de::Error<std::io::Error>,
+        missing_reference: &MissingReferenceSlot,
+    ) -> DataLoadError {
+        let reason = match missing_reference.lock().unwrap().take() {
+            Some(missing) => format!(
+                "definition {} references {} {}, which does not exist",
+                missing.definition_id, missing.referenced_type, missing.referenced_id
+            ),
+            None => error.to_string(),
+        };
+
+        DataLoadError::InvalidBundle {
+            path: path.to_owned(),
+            reason,
+        }
+    }
+}
+
+/// A definition that references another definition which couldn't be found in its database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingReference {
+    pub definition_id: u64,
+    pub referenced_type: &'static str,
+    pub referenced_id: u64,
+}
+
+/// Deserializers can only fail with a string message, so dangling references are recorded here
+/// for the loader to turn into a [DataLoadError::MissingReference].
+pub type MissingReferenceSlot = Arc<Mutex<Option<MissingReference>>>;
+
+/// Every definition in a [DataDefinitionDatabase], indexed by ID.
+#[derive(Clone, Debug)]
+pub struct DefinitionStore<DataDefinitionType> {
+    definitions: HashMap<u64, Arc<DataDefinitionType>>,
+    duplicate_ids: Vec<u64>,
+}
+
+impl<DataDefinitionType: DataDefinition> DefinitionStore<DataDefinitionType> {
+    /// Indexes loaded definitions by ID, recording any IDs that were found more than once.
+    pub fn new(definitions: Vec<DataDefinitionType>) -> Self {
+        let mut store = DefinitionStore {
+            definitions: HashMap::new(),
+            duplicate_ids: vec![],
+        };
+
+        for definition in definitions {
+            let id = definition.id();
+            if store.insert(definition).is_some() {
+                store.duplicate_ids.push(id);
+            }
+        }
+
+        store
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<DataDefinitionType>> {
+        self.definitions.get(&id).cloned()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Arc<DataDefinitionType>> {
+        self.definitions.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    fn insert(&mut self, definition: DataDefinitionType) -> Option<Arc<DataDefinitionType>> {
+        self.definitions
+            .insert(definition.id(), Arc::new(definition))
+    }
+
+    pub fn duplicate_ids(&self) -> &[u64] {
+        &self.duplicate_ids
+    }
+
+    /// Copies of every definition, ordered by ID.
+    pub fn sorted_definitions(&self) -> Vec<DataDefinitionType> {
+        let mut definitions = self
+            .definitions
+            .values()
+            .map(|def| def.as_ref().to_owned())
+            .collect::<Vec<DataDefinitionType>>();
+        definitions.sort_by_key(|def| def.id());
+        definitions
+    }
+}
+
+/// Edits a copy of a database's definitions, then builds a new database from them.
+/// Definitions already shared with game code are left untouched; edited definitions are copied on write.
+#[derive(Clone)]
+pub struct DatabaseBuilder<DataDefinitionType> {
+    store: DefinitionStore<DataDefinitionType>,
+}
+
+impl<DataDefinitionType: DataDefinition> DatabaseBuilder<DataDefinitionType> {
+    pub fn definition(
+        &self,
+        id: DataDefinitionType::DefinitionTypeId,
+    ) -> Option<Arc<DataDefinitionType>> {
+        self.store.get(id.into())
+    }
+
+    pub fn definitions(&self) -> Vec<Arc<DataDefinitionType>> {
+        self.store.values().cloned().collect()
+    }
+
+    pub fn definition_mut(
+        &mut self,
+        id: DataDefinitionType::DefinitionTypeId,
+    ) -> Option<&mut DataDefinitionType> {
+        self.store
+            .definitions
+            .get_mut(&id.into())
+            .map(Arc::make_mut)
+    }
+
+    /// Adds `definition`, replacing any definition with the same ID.
+    pub fn add_definition(&mut self, definition: DataDefinitionType) {
+        self.store.insert(definition);
+    }
+
+    /// Removes a data definition given it's ID. Existing references to the definition remain valid.
+    pub fn remove_definition(
+        &mut self,
+        id: DataDefinitionType::DefinitionTypeId,
+    ) -> Option<Arc<DataDefinitionType>> {
+        self.store.definitions.remove(&id.into())
+    }
+
+    pub fn build<DatabaseType>(self) -> DatabaseType
+    where
+        DatabaseType: DataDefinitionDatabase<DataDefinitionType>,
+    {
+        DatabaseType::from_store(self.store)
+    }
+}
+
+/// Looks up a definition by ID, returning it as a type-erased `Arc<DataDefinitionType>`.
+type DefinitionResolver = Box<dyn Fn(u64) -> Option<Box<dyn Any>>>;
+
+/// Databases that [DefRef]s are resolved against while definitions are being deserialized.
+#[derive(Default)]
+pub struct DefinitionRegistry {
+    resolvers: HashMap<TypeId, DefinitionResolver>,
+}
+
+/// A [DefinitionRegistry] that is resolving references on the current thread.
+struct ActiveRegistry {
+    registry: DefinitionRegistry,
+
+    /// References in the definition currently being read that couldn't be resolved.
+    unresolved: Vec<(&'static str, u64)>,
+
+    missing_reference: MissingReferenceSlot,
+}
+
+thread_local! {
+    static ACTIVE_REGISTRY: RefCell<Option<ActiveRegistry>> = const { RefCell::new(None) };
+}
+
+impl DefinitionRegistry {
+    /// Allows [DefRef]s to reference definitions in `database`.
+    pub fn with<DataDefinitionType, DatabaseType>(mut self, database: &Arc<DatabaseType>) -> Self
+    where
+        DataDefinitionType: DataDefinition,
+        DatabaseType: DataDefinitionDatabase<DataDefinitionType> + 'static,
+    {
+        let database = database.clone();
+        self.resolvers.insert(
+            TypeId::of::<DataDefinitionType>(),
+            Box::new(move |id| {
+                let definition = database.store().get(id)?;
+                Some(Box::new(definition) as Box<dyn Any>)
+            }),
+        );
+        self
+    }
+
+    /// Runs `deserialize` with this registry resolving [DefRef]s on the current thread.
+    /// The first missing reference found by a [DefinitionListSeed] is recorded in the passed slot.
+    fn scope<R>(self, deserialize: impl FnOnce(&MissingReferenceSlot) -> R) -> R {
+        let missing_reference = MissingReferenceSlot::default();
+        let previous = ACTIVE_REGISTRY.with(|active| {
+            active.replace(Some(ActiveRegistry {
+                registry: self,
+                unresolved: vec![],
+                missing_reference: missing_reference.clone(),
+            }))
+        });
+
+        let result = deserialize(&missing_reference);
+
+        ACTIVE_REGISTRY.with(|active| active.replace(previous));
+        result
+    }
+}
+
+/// Reference to a definition in another database.
+/// Stored on disk as the definition's ID, which is resolved against the loading database's [DefinitionRegistry].
+pub struct DefRef<DataDefinitionType>(Option<Arc<DataDefinitionType>>);
+
+impl<DataDefinitionType> DefRef<DataDefinitionType> {
+    pub fn new(definition: Arc<DataDefinitionType>) -> Self {
+        DefRef(Some(definition))
+    }
+}
+
+impl<DataDefinitionType> From<Arc<DataDefinitionType>> for DefRef<DataDefinitionType> {
+    fn from(definition: Arc<DataDefinitionType>) -> Self {
+        DefRef::new(definition)
+    }
+}
+
+impl<DataDefinitionType> Clone for DefRef<DataDefinitionType> {
+    fn clone(&self) -> Self {
+        DefRef(self.0.clone())
+    }
+}
+
+impl<DataDefinitionType: std::fmt::Debug> std::fmt::Debug for DefRef<DataDefinitionType> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<DataDefinitionType> Deref for DefRef<DataDefinitionType> {
+    type Target = Arc<DataDefinitionType>;
+
+    /// Only references in a definition that failed to load are unresolved, so this never panics for loaded data.
+    fn deref(&self) -> &Self::Target {
+        self.0
+            .as_ref()
+            .expect("definition reference was never resolved")
+    }
+}
+
+impl<DataDefinitionType: DataDefinition> Serialize for DefRef<DataDefinitionType> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.id())
+    }
+}
+
+impl<'de, DataDefinitionType: DataDefinition> Deserialize<'de> for DefRef<DataDefinitionType> {
+    /// Unresolved references are left for [DefinitionListSeed] to report once the whole definition is read,
+    /// so that the referencing definition's ID is known.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let id = u64::deserialize(deserializer)?;
+
+        ACTIVE_REGISTRY.with(|active| {
+            let mut active = active.borrow_mut();
+            let Some(active) = active.as_mut() else {
+                return Err(serde::de::Error::custom(format!(
+                    "no registry to resolve {} {id} against",
+                    DataDefinitionType::TYPE_NAME
+                )));
+            };
+
+            let definition = active
+                .registry
+                .resolvers
+                .get(&TypeId::of::<DataDefinitionType>())
+                .and_then(|resolve| resolve(id))
+                .and_then(|definition| definition.downcast().ok())
+                .map(|definition| *definition);
+            if definition.is_none() {
+                active.unresolved.push((DataDefinitionType::TYPE_NAME, id));
+            }
+
+            Ok(DefRef(definition))
+        })
+    }
+}
+
+/// Deserializes a list of definitions, failing at the first definition with a [DefRef] that couldn't be resolved.
+struct DefinitionListSeed<DataDefinitionType>(PhantomData<DataDefinitionType>);
+
+impl<'de, DataDefinitionType: DataDefinition> DeserializeSeed<'de>
+    for DefinitionListSeed<DataDefinitionType>
+{
+    type Value = Vec<DataDefinitionType>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, DataDefinitionType: DataDefinition> Visitor<'de>
+    for DefinitionListSeed<DataDefinitionType>
+{
+    type Value = Vec<DataDefinitionType>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "a list of {} definitions",
+            DataDefinitionType::TYPE_NAME
+        )
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let mut definitions = vec![];
+
+        while let Some(definition) = seq.next_element::<DataDefinitionType>()? {
+            let unresolved = ACTIVE_REGISTRY.with(|active| {
+                let mut active = active.borrow_mut();
+                let active = active.as_mut()?;
+                let unresolved = active.unresolved.first().copied();
+                active.unresolved.clear();
+                unresolved.map(|unresolved| (unresolved, active.missing_reference.clone()))
+            });
+
+            if let Some(((referenced_type, referenced_id), missing_reference)) = unresolved {
+                *missing_reference.lock().unwrap() = Some(MissingReference {
+                    definition_id: definition.id(),
+                    referenced_type,
+                    referenced_id,
+                });
+
+                return Err(serde::de::Error::custom(format!(
+                    "{} {} references unknown {referenced_type} {referenced_id}",
+                    DataDefinitionType::TYPE_NAME,
+                    definition.id()
+                )));
+            }
+
+            definitions.push(definition);
+        }
+
+        Ok(definitions)
+    }
+}
+
+/// A list of definitions deserialized through [DefinitionListSeed], for formats that can't take a seed.
+struct DefinitionList<DataDefinitionType>(Vec<DataDefinitionType>);
+
+impl<'de, DataDefinitionType: DataDefinition> Deserialize<'de>
+    for DefinitionList<DataDefinitionType>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DefinitionListSeed(PhantomData)
+            .deserialize(deserializer)
+            .map(DefinitionList)
+    }
+}
+
+/// Schema version of data files written before files were versioned, which were a bare list of definitions.
+pub const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades the list of definitions in a data file by a single schema version.
+pub type DataMigration = fn(&mut serde_json::Value) -> Result<(), String>;
+
+/// Ordered registry of migrations for a single data file.
+/// The first migration upgrades [UNVERSIONED_SCHEMA_VERSION] to the next version, and so on.
+#[derive(Clone, Default)]
+pub struct DataMigrations {
+    migrations: Vec<DataMigration>,
+}
+
+impl DataMigrations {
+    /// Registers a migration from the current latest version to a new latest version.
+    pub fn with(mut self, migration: DataMigration) -> Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Version that data files are written in.
+    pub fn latest_version(&self) -> u32 {
+        UNVERSIONED_SCHEMA_VERSION + self.migrations.len() as u32
+    }
+
+    /// Upgrades `definitions` from `version` to the latest version, one version at a time.
+    pub fn migrate(
+        &self,
+        definitions: &mut serde_json::Value,
+        version: u32,
+    ) -> Result<(), (u32, String)> {
+        let first = version.saturating_sub(UNVERSIONED_SCHEMA_VERSION) as usize;
+        for (step, migration) in self.migrations.iter().enumerate().skip(first) {
+            migration(definitions)
+                .map_err(|reason| (UNVERSIONED_SCHEMA_VERSION + step as u32, reason))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionedDataFile {
+    schema_version: u32,
+    definitions: serde_json::Value,
+
+    /// IDs removed from earlier data layers.
+    #[serde(default)]
+    removed_ids: Vec<u64>,
+}
+
+#[derive(Serialize)]
+struct VersionedDataFileRef<'a, T> {
+    schema_version: u32,
+    definitions: &'a T,
+}
+
+/// Reads the definition list out of a versioned data file, ignoring its version.
+struct VersionedDataFileSeed<Seed>(Seed);
+
+impl<'de, Seed: DeserializeSeed<'de>> DeserializeSeed<'de> for VersionedDataFileSeed<Seed> {
+    type Value = Seed::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, Seed: DeserializeSeed<'de>> Visitor<'de> for VersionedDataFileSeed<Seed> {
+    type Value = Seed::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a data file with a schema version and definitions")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut seed = Some(self.0);
+        let mut definitions = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key != "definitions" {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            }
+
+            let Some(seed) = seed.take() else {
+                return Err(serde::de::Error::duplicate_field("definitions"));
+            };
+            definitions = Some(map.next_value_seed(seed)?);
+        }
+
+        definitions.ok_or_else(|| serde::de::Error::missing_field("definitions"))
+    }
+}
+
+/// Index of the data root each definition was last added or overridden in, keyed by definition ID.
+pub type DefinitionLayers = HashMap<u64, usize>;
+
+/// A single data file from one data root, upgraded to the latest schema.
+struct DataLayer {
+    contents: String,
+    unversioned: bool,
+    migrated: bool,
+    definitions: serde_json::Value,
+    removed_ids: Vec<u64>,
+}
+
+fn read_data_layer(
+    path: &str,
+    migrations: &DataMigrations,
+    missing_reference: &MissingReferenceSlot,
+) -> Result<DataLayer, DataLoadError> {
+    let contents = read_data_file(path)?;
+    let to_error = |err| DataLoadError::from_json(path, err, missing_reference);
+
+    let document: serde_json::Value = serde_json::from_str(contents.as_str()).map_err(to_error)?;
+    let unversioned = document.is_array();
+    let versioned = if unversioned {
+        VersionedDataFile {
+            schema_version: UNVERSIONED_SCHEMA_VERSION,
+            definitions: document,
+            removed_ids: vec![],
+        }
+    } else {
+        serde_json::from_value(document).map_err(to_error)?
+    };
+
+    let latest = migrations.latest_version();
+    if versioned.schema_version > latest {
+        return Err(DataLoadError::UnsupportedSchemaVersion {
+            path: path.to_owned(),
+            version: versioned.schema_version,
+            latest,
+        });
+    }
+
+    let migrated = versioned.schema_version < latest;
+    let mut definitions = versioned.definitions;
+    migrations
+        .migrate(&mut definitions, versioned.schema_version)
+        .map_err(|(version, reason)| DataLoadError::Migration {
+            path: path.to_owned(),
+            version,
+            reason,
+        })?;
+
+    if !definitions.is_array() {
+        return Err(DataLoadError::Malformed {
+            path: path.to_owned(),
+            line: 0,
+            column: 0,
+            message: String::from("definitions must be a list"),
+        });
+    }
+
+    Ok(DataLayer {
+        contents,
+        unversioned,
+        migrated,
+        definitions,
+        removed_ids: versioned.removed_ids,
+    })
+}
+
+fn definition_id(definition: &serde_json::Value) -> Option<u64> {
+    definition.get("id").and_then(serde_json::Value::as_u64)
+}
+
+/// Overrides fields in `base` with those in `overlay`. Objects are merged field by field; anything else is replaced.
+fn merge_fields(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_fields(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Reads the same data file from every data root in `paths`, in order, and deserializes the merged result with `seed`.
+/// Each later layer can add definitions, override fields of definitions with an existing ID, and remove IDs listed in
+/// its `removed_ids`. Only the first layer's file must exist.
+pub fn deserialize_data_layers<Seed, T>(
+    paths: &[String],
+    seed: Seed,
+    migrations: &DataMigrations,
+    missing_reference: &MissingReferenceSlot,
+) -> Result<(T, DefinitionLayers), DataLoadError>
+where
+    Seed: for<'de> DeserializeSeed<'de, Value = T>,
+{
+    let mut layers = vec![];
+    for (layer_index, path) in paths.iter().enumerate() {
+        if layer_index > 0 && !std::path::Path::new(path).exists() {
+            continue;
+        }
+
+        layers.push((
+            layer_index,
+            path.as_str(),
+            read_data_layer(path, migrations, missing_reference)?,
+        ));
+    }
+
+    // A single file that didn't need migrating is read directly, so errors point at its original line and column
+    if let [(layer_index, path, layer)] = layers.as_slice() {
+        if !layer.migrated {
+            let definition_layers = layer
+                .definitions
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(definition_id)
+                .map(|id| (id, *layer_index))
+                .collect();
+
+            let mut deserializer = serde_json::Deserializer::from_str(layer.contents.as_str());
+            let definitions = if layer.unversioned {
+                seed.deserialize(&mut deserializer)
+            } else {
+                VersionedDataFileSeed(seed).deserialize(&mut deserializer)
+            }
+            .map_err(|err| DataLoadError::from_json(path, err, missing_reference))?;
+
+            return Ok((definitions, definition_layers));
+        }
+    }
+
+    let mut merged: Vec<serde_json::Value> = vec![];
+    let mut definition_layers = DefinitionLayers::new();
+    for (layer_index, _, layer) in &mut layers {
+        let mut positions = merged
+            .iter()
+            .enumerate()
+            .filter_map(|(position, definition)| Some((definition_id(definition)?, position)))
+            .collect::<HashMap<u64, usize>>();
+
+        let definitions = std::mem::take(layer.definitions.as_array_mut().unwrap());
+        for definition in definitions {
+            let Some(id) = definition_id(&definition) else {
+                merged.push(definition);
+                continue;
+            };
+
+            match positions.get(&id) {
+                // Repeating an ID within a single layer is a duplicate, not an override
+                Some(&position) if definition_layers.get(&id) != Some(layer_index) => {
+                    merge_fields(&mut merged[position], definition)
+                }
+                _ => {
+                    positions.insert(id, merged.len());
+                    merged.push(definition);
+                }
+            }
+
+            definition_layers.insert(id, *layer_index);
+        }
+
+        for id in &layer.removed_ids {
+            merged.retain(|definition| definition_id(definition) != Some(*id));
+            definition_layers.remove(id);
+        }
+    }
+
+    let path = layers
+        .iter()
+        .map(|(_, path, _)| *path)
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let definitions = seed
+        .deserialize(serde_json::Value::Array(merged))
+        .map_err(|err| DataLoadError::from_json(&path, err, missing_reference))?;
+
+    Ok((definitions, definition_layers))
+}
+
+/// Reads layered data files of definitions that don't reference other data.
+pub fn deserialize_plain_data_layers<T>(
+    paths: &[String],
+    migrations: &DataMigrations,
+) -> Result<(Vec<T>, DefinitionLayers), DataLoadError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    deserialize_data_layers(
+        paths,
+        PhantomData::<Vec<T>>,
+        migrations,
+        &MissingReferenceSlot::default(),
+    )
+}
+
+/// Serializes definitions as a data file in the latest schema version.
+pub fn data_file_to_string<T: Serialize>(
+    definitions: &T,
+    migrations: &DataMigrations,
+) -> serde_json::Result<String> {
+    serde_json::ser::to_string(&VersionedDataFileRef {
+        schema_version: migrations.latest_version(),
+        definitions,
+    })
+}
+
+/// Reads a data file into a string.
+pub fn read_data_file(path: &str) -> Result<String, DataLoadError> {
+    let bytes = std::fs::read(path).map_err(|source| DataLoadError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+
+    String::from_utf8(bytes).map_err(|source| DataLoadError::InvalidUtf8 {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Checks that a data file is readable, well-formed JSON without resolving any references.
+/// Used for files whose dependencies failed to load, so that every broken file can still be reported.
+pub fn check_data_file(path: &str) -> Result<(), DataLoadError> {
+    let contents = read_data_file(path)?;
+
+    serde_json::from_str::<serde::de::IgnoredAny>(contents.as_str())
+        .map(|_| ())
+        .map_err(|err| DataLoadError::from_json(path, err, &MissingReferenceSlot::default()))
+}
+
+/// Loads a database if all of its dependencies loaded, pushing any failure onto `errors`.
+/// If a dependency is missing, the file is only checked for well-formedness.
+pub fn load_database<DataDefinitionType, DatabaseType>(
+    path: &str,
+    dependencies: Option<DatabaseType::DataDependencies>,
+    errors: &mut Vec<DataLoadError>,
+) -> Option<Arc<DatabaseType>>
+where
+    DataDefinitionType: DataDefinition,
+    DatabaseType: DataDefinitionDatabase<DataDefinitionType>,
+{
+    load_database_layers::<DataDefinitionType, DatabaseType>(
+        &[path.to_owned()],
+        dependencies,
+        errors,
+    )
+    .map(|(database, _)| database)
+}
+
+/// Loads a database from layered data files, as [load_database] does for a single file.
+pub fn load_database_layers<DataDefinitionType, DatabaseType>(
+    paths: &[String],
+    dependencies: Option<DatabaseType::DataDependencies>,
+    errors: &mut Vec<DataLoadError>,
+) -> Option<(Arc<DatabaseType>, DefinitionLayers)>
+where
+    DataDefinitionType: DataDefinition,
+    DatabaseType: DataDefinitionDatabase<DataDefinitionType>,
+{
+    let Some(dependencies) = dependencies else {
+        for (layer_index, path) in paths.iter().enumerate() {
+            if layer_index > 0 && !std::path::Path::new(path).exists() {
+                continue;
+            }
+
+            if let Err(err) = check_data_file(path) {
+                errors.push(err);
+            }
+        }
+
+        return None;
+    };
+
+    match DatabaseType::load_from_layers(paths, &dependencies) {
+        Ok((database, definition_layers)) => Some((Arc::new(database), definition_layers)),
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp_file(file_name: &str, contents: &str) -> String {
+        let mut path = std::env::temp_dir();
+        path.push(file_name);
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    fn read_plain_data_file(
+        path: &str,
+        migrations: &DataMigrations,
+    ) -> Result<Vec<serde_json::Value>, DataLoadError> {
+        deserialize_plain_data_layers(&[path.to_owned()], migrations)
+            .map(|(definitions, _)| definitions)
+    }
+
+    fn rename_field(definitions: &mut serde_json::Value, from: &str, to: &str) {
+        for definition in definitions.as_array_mut().unwrap() {
+            let definition = definition.as_object_mut().unwrap();
+            if let Some(value) = definition.remove(from) {
+                definition.insert(to.to_owned(), value);
+            }
+        }
+    }
+
+    #[test]
+    fn unversioned_files_migrate_step_by_step() {
+        let migrations = DataMigrations::default()
+            .with(|definitions| {
+                rename_field(definitions, "a", "b");
+                Ok(())
+            })
+            .with(|definitions| {
+                rename_field(definitions, "b", "c");
+                Ok(())
+            });
+        assert_eq!(migrations.latest_version(), 3);
+
+        let path = write_temp_file("cypher_unversioned.json", r#"[{"a":1}]"#);
+        let definitions = read_plain_data_file(&path, &migrations).unwrap();
+        assert_eq!(definitions, vec![serde_json::json!({"c": 1})]);
+
+        let path = write_temp_file(
+            "cypher_versioned.json",
+            r#"{"schema_version":2,"definitions":[{"b":1}]}"#,
+        );
+        let definitions = read_plain_data_file(&path, &migrations).unwrap();
+        assert_eq!(definitions, vec![serde_json::json!({"c": 1})]);
+    }
+
+    #[test]
+    fn latest_files_roundtrip_and_newer_files_are_rejected() {
+        let migrations = DataMigrations::default();
+        let definitions = vec![serde_json::json!({"a": 1})];
+
+        let path = write_temp_file(
+            "cypher_latest.json",
+            &data_file_to_string(&definitions, &migrations).unwrap(),
+        );
+        let read = read_plain_data_file(&path, &migrations).unwrap();
+        assert_eq!(read, definitions);
+
+        let path = write_temp_file(
+            "cypher_newer.json",
+            r#"{"schema_version":2,"definitions":[]}"#,
+        );
+        assert!(matches!(
+            read_plain_data_file(&path, &migrations),
+            Err(DataLoadError::UnsupportedSchemaVersion {
+                version: 2,
+                latest: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn later_layers_add_override_and_remove_definitions() {
+        let base = write_temp_file(
+            "cypher_layer_base.json",
+            r#"[{"id":1,"name":"Boots","stats":{"armor":5,"speed":1}},{"id":2,"name":"Gloves"}]"#,
+        );
+        let balance = write_temp_file(
+            "cypher_layer_balance.json",
+            r#"{"schema_version":1,"definitions":[{"id":1,"stats":{"armor":10}},{"id":3,"name":"Helmet"}],"removed_ids":[2]}"#,
+        );
+        let missing = std::env::temp_dir()
+            .join("cypher_layer_missing.json")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let (definitions, definition_layers) = deserialize_plain_data_layers::<serde_json::Value>(
+            &[base, missing, balance],
+            &DataMigrations::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions,
+            vec![
+                serde_json::json!({"id": 1, "name": "Boots", "stats": {"armor": 10, "speed": 1}}),
+                serde_json::json!({"id": 3, "name": "Helmet"}),
+            ]
+        );
+        assert_eq!(definition_layers, DefinitionLayers::from([(1, 2), (3, 2)]));
+    }
+}
@@ -1,4 +1,10 @@
 pub mod affix;
 pub mod affix_pool;
+pub mod damage;
 pub mod data;
+pub mod localization;
 pub mod stat;
+pub mod stat_condition;
+pub mod stat_derivation;
+pub mod stat_registry;
+pub mod stat_text;
@@ -0,0 +1,480 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+    affix::{
+        definition::{AffixDefinitionStat, AffixDefinitionValue},
+        instance::AffixInstance,
+    },
+    data::{read_data_file, DataDefinitionDatabase, DataLoadError, MissingReferenceSlot},
+    stat::{StatId, StatList, StatModifier, StatModifierKind},
+    stat_condition::StatCondition,
+    stat_registry::{database::StatRegistry, definition::StatDisplayFormat},
+    stat_text::{self, StatTextValue, StatTextValues},
+};
+
+/// Key of a string in a [StringTable], such as `item.leather_boots`.
+/// Definitions store keys rather than display text, so that text can be translated.
+pub type LocalizationKey = String;
+
+/// Name of a locale, such as `en`. Each locale's strings are stored in `locale/<locale>.json`.
+pub type Locale = String;
+
+/// Every string for a single locale.
+pub type StringTable = HashMap<LocalizationKey, String>;
+
+/// String tables for every locale, resolving keys in the selected locale, then the fallback locale.
+#[derive(Clone, Debug)]
+pub struct Localization {
+    locale: Locale,
+    tables: HashMap<Locale, StringTable>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self {
+            locale: Locale::from(Self::FALLBACK_LOCALE),
+            tables: HashMap::new(),
+        }
+    }
+}
+
+impl Localization {
+    /// Every key must have a string in this locale, and other locales fall back to it.
+    pub const FALLBACK_LOCALE: &'static str = "en";
+
+    /// Directory within a data root that string tables are stored in.
+    pub const DIRECTORY_NAME: &'static str = "locale";
+
+    /// Loads every string table from each of `data_roots` in order, with later roots overriding earlier ones' strings.
+    /// Only the first root must contain the fallback locale.
+    pub fn load_from_layers(data_roots: &[PathBuf]) -> Result<Localization, DataLoadError> {
+        let mut localization = Localization::default();
+
+        for (layer_index, data_root) in data_roots.iter().enumerate() {
+            let locale_path = data_root.join(Self::DIRECTORY_NAME);
+
+            let mut table_paths = match std::fs::read_dir(&locale_path) {
+                Ok(entries) => entries
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect::<Vec<_>>(),
+                Err(_) if layer_index > 0 => continue,
+                Err(source) => {
+                    return Err(DataLoadError::Io {
+                        path: locale_path.to_str().unwrap().to_owned(),
+                        source,
+                    })
+                }
+            };
+            table_paths.sort();
+
+            for table_path in table_paths {
+                let path = table_path.to_str().unwrap();
+                let locale = table_path.file_stem().unwrap().to_str().unwrap().to_owned();
+
+                let contents = read_data_file(path)?;
+                let table: StringTable =
+                    serde_json::from_str(contents.as_str()).map_err(|err| {
+                        DataLoadError::from_json(path, err, &MissingReferenceSlot::default())
+                    })?;
+
+                localization.tables.entry(locale).or_default().extend(table);
+            }
+        }
+
+        if !localization.tables.contains_key(Self::FALLBACK_LOCALE) {
+            return Err(DataLoadError::Io {
+                path: data_roots[0]
+                    .join(Self::DIRECTORY_NAME)
+                    .join(format!("{}.json", Self::FALLBACK_LOCALE))
+                    .to_str()
+                    .unwrap()
+                    .to_owned(),
+                source: std::io::ErrorKind::NotFound.into(),
+            });
+        }
+
+        Ok(localization)
+    }
+
+    /// Locale that strings are currently resolved in.
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// Selects the locale strings are resolved in. Keys missing from it fall back to [Localization::FALLBACK_LOCALE].
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    /// Every locale with a string table.
+    pub fn locales(&self) -> Vec<&Locale> {
+        let mut locales = self.tables.keys().collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    /// Whether `locale` has a string for `key`, without falling back.
+    pub fn contains(&self, locale: &str, key: &str) -> bool {
+        self.tables
+            .get(locale)
+            .is_some_and(|table| table.contains_key(key))
+    }
+
+    /// Looks up the string for `key` in `locale`, without falling back.
+    pub fn get_in(&self, locale: &str, key: &str) -> Option<&str> {
+        self.tables.get(locale)?.get(key).map(String::as_str)
+    }
+
+    /// Looks up the string for `key`, falling back to [Localization::FALLBACK_LOCALE].
+    pub fn get(&self, key: &str) -> Option<&str> {
+        [self.locale.as_str(), Self::FALLBACK_LOCALE]
+            .into_iter()
+            .find_map(|locale| self.tables.get(locale)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Looks up the string for `key`. Missing keys are shown as the key itself, so they're easy to spot.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get(key).unwrap_or(key)
+    }
+
+    /// Display name of a stat. Stats missing from `stats` are shown by ID, so they're easy to spot.
+    pub fn stat_name(&self, stats: &StatRegistry, stat: StatId) -> String {
+        match stats.definition(stat) {
+            Some(definition) => self.text(&definition.name).to_owned(),
+            None => format!("stat {stat}"),
+        }
+    }
+
+    /// Text template that `kind` modifiers of `stat` are shown with, from the `<stat name>.<kind>` key if there is one,
+    /// such as `stat.move_speed.increased`.
+    pub fn stat_template(
+        &self,
+        stats: &StatRegistry,
+        stat: StatId,
+        kind: StatModifierKind,
+    ) -> &str {
+        stats
+            .definition(stat)
+            .and_then(|definition| {
+                self.get(&format!(
+                    "{}.{}",
+                    definition.name,
+                    stat_text::template_key_suffix(kind)
+                ))
+            })
+            .unwrap_or(stat_text::default_template(kind))
+    }
+
+    /// Display text of a [StatModifier], such as `+5 Move Speed` or `+10% increased Move Speed`.
+    pub fn stat_modifier_text(&self, stats: &StatRegistry, modifier: &StatModifier) -> String {
+        self.stat_text(
+            stats,
+            modifier.0,
+            modifier.2,
+            None,
+            StatTextValue::Rolled(modifier.1, None),
+            None,
+        )
+    }
+
+    /// Display text of a stat rolled from `affix_stat`, or of every value it can roll if `value` is a range.
+    pub fn affix_stat_text(
+        &self,
+        stats: &StatRegistry,
+        affix_stat: &AffixDefinitionStat,
+        value: StatTextValue,
+        precision_places: Option<u32>,
+    ) -> String {
+        let text = self.stat_text(
+            stats,
+            affix_stat.stat,
+            affix_stat.kind,
+            affix_stat.template.as_deref(),
+            value,
+            precision_places,
+        );
+
+        match &affix_stat.condition {
+            Some(condition) => format!("{text} {}", self.condition_text(condition)),
+            None => text,
+        }
+    }
+
+    /// Display text of every stat an affix rolled, one per line, with the ranges they were rolled from if `show_ranges`.
+    pub fn affix_text(
+        &self,
+        stats: &StatRegistry,
+        affix: &AffixInstance,
+        show_ranges: bool,
+    ) -> String {
+        let Some(tier) = affix.definition.tiers.get(&affix.tier) else {
+            return self.stat_list_text(stats, &affix.stats);
+        };
+
+        let mods = affix.stats.mods();
+        let conditional_mods = affix.stats.conditional_mods();
+
+        tier.stats
+            .iter()
+            .filter_map(|affix_stat| {
+                let is_rolled = |modifier: &StatModifier| {
+                    modifier.0 == affix_stat.stat && modifier.2 == affix_stat.kind
+                };
+                let rolled = match affix_stat.condition {
+                    Some(condition) => conditional_mods
+                        .iter()
+                        .find(|(rolled_condition, modifier)| {
+                            *rolled_condition == condition && is_rolled(modifier)
+                        })
+                        .map(|(_, modifier)| modifier.1),
+                    None => mods
+                        .iter()
+                        .find(|modifier| is_rolled(modifier))
+                        .map(|modifier| modifier.1),
+                }?;
+
+                let range = match affix_stat.value {
+                    AffixDefinitionValue::Range(lower, upper) if show_ranges => {
+                        Some((lower, upper))
+                    }
+                    _ => None,
+                };
+
+                Some(self.affix_stat_text(
+                    stats,
+                    affix_stat,
+                    StatTextValue::Rolled(rolled, range),
+                    tier.precision_places,
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the text of a `kind` modifier of `stat`, with the template at `template_key` if it's set and exists.
+    fn stat_text(
+        &self,
+        stats: &StatRegistry,
+        stat: StatId,
+        kind: StatModifierKind,
+        template_key: Option<&str>,
+        value: StatTextValue,
+        precision_places: Option<u32>,
+    ) -> String {
+        let template = template_key
+            .and_then(|key| self.get(key))
+            .unwrap_or_else(|| self.stat_template(stats, stat, kind));
+        let unit = match stats.definition(stat).map(|stat| stat.format) {
+            Some(StatDisplayFormat::Percent) => "%",
+            _ => "",
+        };
+
+        stat_text::render(
+            template,
+            &StatTextValues {
+                stat: &self.stat_name(stats, stat),
+                value,
+                precision_places,
+                unit,
+            },
+        )
+    }
+
+    /// Display text of a [StatCondition], such as `while moving`.
+    pub fn condition_text(&self, condition: &StatCondition) -> String {
+        match condition {
+            StatCondition::Moving => String::from("while moving"),
+            StatCondition::FullHealth => String::from("while at full health"),
+            StatCondition::AgainstTeam(team_id) => format!("against team {team_id}"),
+            StatCondition::AfterKill(seconds) => format!("for {seconds} seconds after a kill"),
+        }
+    }
+
+    /// Display text of every modifier in a [StatList], one per line, followed by its conditional modifiers.
+    pub fn stat_list_text(&self, stats: &StatRegistry, stat_list: &StatList) -> String {
+        let mut lines = stat_list
+            .mods()
+            .iter()
+            .map(|modifier| self.stat_modifier_text(stats, modifier))
+            .collect::<Vec<_>>();
+
+        for (condition, modifier) in stat_list.conditional_mods() {
+            lines.push(format!(
+                "{} {}",
+                self.stat_modifier_text(stats, &modifier),
+                self.condition_text(&condition)
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use crate::{
+        affix::{
+            definition::{AffixDefinition, AffixDefinitionTier},
+            placement::AffixPlacement,
+        },
+        data::DefinitionStore,
+        stat::{HEALTH, MOVE_SPEED},
+        stat_registry::definition::StatDefinition,
+    };
+
+    fn write_table(data_root: &std::path::Path, locale: &str, contents: &str) {
+        let locale_path = data_root.join(Localization::DIRECTORY_NAME);
+        std::fs::create_dir_all(&locale_path).unwrap();
+        std::fs::write(locale_path.join(format!("{locale}.json")), contents).unwrap();
+    }
+
+    #[test]
+    fn keys_fall_back_to_fallback_locale() {
+        let mut base = std::env::temp_dir();
+        base.push("cypher_localization_base");
+        write_table(
+            &base,
+            "en",
+            r#"{"item.boots":"Boots","item.gloves":"Gloves","stat.health":"Health"}"#,
+        );
+        write_table(&base, "fr", r#"{"item.boots":"Bottes"}"#);
+
+        let mut overlay = std::env::temp_dir();
+        overlay.push("cypher_localization_overlay");
+        write_table(&overlay, "en", r#"{"item.gloves":"Gauntlets"}"#);
+
+        let mut localization = Localization::load_from_layers(&[base, overlay]).unwrap();
+        assert_eq!(localization.text("item.gloves"), "Gauntlets");
+
+        localization.set_locale(Locale::from("fr"));
+        assert_eq!(localization.text("item.boots"), "Bottes");
+        assert_eq!(localization.text("item.gloves"), "Gauntlets");
+        assert_eq!(localization.text("item.missing"), "item.missing");
+
+        const FIRE_RESISTANCE: StatId = 100;
+        let stats = StatRegistry::from_store(DefinitionStore::new(vec![
+            StatDefinition {
+                id: HEALTH,
+                name: String::from("stat.health"),
+                default_value: 0.,
+                min: None,
+                max: None,
+                integer: true,
+                format: StatDisplayFormat::Number,
+            },
+            StatDefinition {
+                id: FIRE_RESISTANCE,
+                name: String::from("stat.fire_resistance"),
+                default_value: 0.,
+                min: None,
+                max: Some(75.),
+                integer: false,
+                format: StatDisplayFormat::Percent,
+            },
+        ]));
+        assert_eq!(
+            localization
+                .stat_modifier_text(&stats, &StatModifier(HEALTH, 5., StatModifierKind::Flat)),
+            "+5 Health"
+        );
+        assert_eq!(
+            localization.stat_modifier_text(
+                &stats,
+                &StatModifier(HEALTH, 10., StatModifierKind::Increased)
+            ),
+            "+10% increased Health"
+        );
+        assert_eq!(
+            localization.stat_modifier_text(
+                &stats,
+                &StatModifier(FIRE_RESISTANCE, 10., StatModifierKind::Flat)
+            ),
+            "+10% stat.fire_resistance"
+        );
+        assert_eq!(
+            localization.stat_modifier_text(&stats, &StatModifier(7, 1., StatModifierKind::Flat)),
+            "+1 stat 7"
+        );
+    }
+
+    #[test]
+    fn affix_text_uses_templates_and_ranges() {
+        let mut data_root = std::env::temp_dir();
+        data_root.push("cypher_localization_templates");
+        write_table(
+            &data_root,
+            "en",
+            r#"{"stat.move_speed":"Move Speed","stat.move_speed.increased":"{+value}% faster{range}","affix.sprint":"Sprinting: {value}"}"#,
+        );
+        let localization = Localization::load_from_layers(&[data_root]).unwrap();
+
+        let stats = StatRegistry::from_store(DefinitionStore::new(vec![StatDefinition {
+            id: MOVE_SPEED,
+            name: String::from("stat.move_speed"),
+            default_value: 100.,
+            min: None,
+            max: None,
+            integer: false,
+            format: StatDisplayFormat::Number,
+        }]));
+
+        let affix_stat = |kind: StatModifierKind, template: Option<&str>| AffixDefinitionStat {
+            stat: MOVE_SPEED,
+            kind,
+            value: AffixDefinitionValue::Range(10., 30.),
+            condition: None,
+            template: template.map(LocalizationKey::from),
+        };
+        let definition = AffixDefinition {
+            id: 1,
+            placement: AffixPlacement::Prefix,
+            tiers: BTreeMap::from([(
+                1,
+                AffixDefinitionTier {
+                    tier: 1,
+                    stats: vec![
+                        affix_stat(StatModifierKind::Increased, None),
+                        affix_stat(StatModifierKind::Flat, Some("affix.sprint")),
+                    ],
+                    item_level_req: None,
+                    precision_places: Some(1),
+                    weight: None,
+                },
+            )]),
+            name: String::from("affix.swift"),
+            tags: vec![],
+            mod_group: None,
+        };
+        let affix = AffixInstance {
+            definition: Arc::new(definition),
+            tier: 1,
+            stats: StatList::from(&[
+                StatModifier(MOVE_SPEED, 12., StatModifierKind::Increased),
+                StatModifier(MOVE_SPEED, 20., StatModifierKind::Flat),
+            ]),
+        };
+
+        assert_eq!(
+            localization.affix_text(&stats, &affix, false),
+            "+12.0% faster\nSprinting: 20.0"
+        );
+        assert_eq!(
+            localization.affix_text(&stats, &affix, true),
+            "+12.0% faster (10.0-30.0)\nSprinting: 20.0"
+        );
+        assert_eq!(
+            localization.affix_stat_text(
+                &stats,
+                &affix.definition.tiers[&1].stats[0],
+                StatTextValue::Range(10., 30.),
+                None
+            ),
+            "+(10-30)% faster"
+        );
+    }
+}
@@ -1,45 +1,159 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::BTreeMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
 use strum_macros::EnumIter;
 
-// Stats are any numeric value a player can possess.
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Hash, PartialEq, Eq, Serialize)]
-pub enum Stat {
-    Resolve,
-    Finesse,
-    Complexity,
-    MoveSpeed,
-    Health,
-    Energy,
+use crate::stat_condition::{ConditionState, StatCondition};
+
+/// ID of a [StatDefinition](crate::stat_registry::definition::StatDefinition).
+/// Stats are any numeric value a player can possess, and are defined in the [StatRegistry](crate::stat_registry::database::StatRegistry).
+pub type StatId = u32;
+
+// Stats read by the game's code, so every stat registry must define them. Validation reports any that are missing.
+
+/// Most hit points a character can have.
+pub const HEALTH: StatId = 5;
+
+/// Speed characters move at, as a percentage of their base speed.
+pub const MOVE_SPEED: StatId = 4;
+
+/// Reduces physical damage taken, by less against bigger hits.
+pub const ARMOR: StatId = 7;
+
+/// Percentage of elemental damage taken that is resisted.
+pub const ELEMENTAL_RESISTANCE: StatId = 8;
+
+/// Percentage of chaos damage taken that is resisted.
+pub const CHAOS_RESISTANCE: StatId = 9;
+
+/// Physical damage added to every hit.
+pub const ADDED_PHYSICAL_DAMAGE: StatId = 10;
+
+/// Elemental damage added to every hit.
+pub const ADDED_ELEMENTAL_DAMAGE: StatId = 11;
+
+/// Chaos damage added to every hit.
+pub const ADDED_CHAOS_DAMAGE: StatId = 12;
+
+/// Percentage by which rarer items are more likely to drop from kills.
+pub const MAGIC_FIND: StatId = 13;
+
+/// Percentage more items that drop from kills.
+pub const ITEM_QUANTITY: StatId = 14;
+
+/// Stats were a fixed enum before they were defined in data, so older data files refer to them by name.
+/// Replaces such a name with the ID of the stat that now defines it.
+pub(crate) fn migrate_legacy_stat(stat: &mut serde_json::Value) -> Result<(), String> {
+    let Some(name) = stat.as_str() else {
+        return Ok(());
+    };
+
+    let id: StatId = match name {
+        "Resolve" => 1,
+        "Finesse" => 2,
+        "Complexity" => 3,
+        "MoveSpeed" => MOVE_SPEED,
+        "Health" => HEALTH,
+        "Energy" => 6,
+        _ => return Err(format!("unknown stat {name}")),
+    };
+    *stat = id.into();
+
+    Ok(())
 }
 
-#[derive(Clone, PartialEq)]
-pub struct StatModifier(pub Stat, pub f32);
+/// How a [StatModifier] combines with the other modifiers of the same stat.
+/// A stat resolves as `(base + flat) * (1 + increased%) * (1 + more%)`, unless it is overridden.
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StatModifierKind {
+    /// Added to the stat's base value.
+    Flat,
 
+    /// Percentage that is summed with every other increased modifier before scaling the stat.
+    Increased,
+
+    /// Percentage that scales the stat separately from every other modifier.
+    More,
+
+    /// Replaces the resolved value. The last override added wins.
+    Override,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatModifier(pub StatId, pub f32, pub StatModifierKind);
+
+/// Shows the stat by ID, as there is no registry to name it with, so is only meant for logs.
+/// Players see modifiers through [Localization::stat_modifier_text](crate::localization::Localization::stat_modifier_text).
 impl Display for StatModifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{:?} {}{}",
-            self.0,
-            if self.1 >= 0. { "+" } else { "" },
-            self.1
-        )
+        let sign = if self.1 >= 0. { "+" } else { "" };
+        match self.2 {
+            StatModifierKind::Flat => write!(f, "stat {} {}{}", self.0, sign, self.1),
+            StatModifierKind::Increased => {
+                write!(f, "stat {} {}{}% increased", self.0, sign, self.1)
+            }
+            StatModifierKind::More => write!(f, "stat {} {}{}% more", self.0, sign, self.1),
+            StatModifierKind::Override => write!(f, "stat {} = {}", self.0, self.1),
+        }
+    }
+}
+
+/// Every modifier of a single stat, combined per [StatModifierKind].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct StatLayers {
+    flat: f32,
+
+    increased: f32,
+
+    /// Combined percentage of every more modifier, as they multiply with each other.
+    more: f32,
+
+    overridden: Option<f32>,
+}
+
+impl StatLayers {
+    fn add(&mut self, kind: StatModifierKind, value: f32) {
+        match kind {
+            StatModifierKind::Flat => self.flat += value,
+            StatModifierKind::Increased => self.increased += value,
+            StatModifierKind::More => {
+                self.more = ((1. + self.more / 100.) * (1. + value / 100.) - 1.) * 100.
+            }
+            StatModifierKind::Override => self.overridden = Some(value),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.flat == 0. && self.increased == 0. && self.more == 0. && self.overridden.is_none()
+    }
+
+    fn resolve(&self, base: f32) -> f32 {
+        self.overridden
+            .unwrap_or((base + self.flat) * (1. + self.increased / 100.) * (1. + self.more / 100.))
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StatList {
-    modifiers: HashMap<Stat, f32>,
+    /// Ordered so that a [StatList] always serializes the same way.
+    modifiers: BTreeMap<StatId, StatLayers>,
+
+    /// Modifiers that only apply while their [StatCondition] holds, grouped per condition.
+    /// Left out of [StatList::mods] and [StatList::resolve] until [StatList::applying] checks them.
+    #[serde(default)]
+    conditional: Vec<(StatCondition, StatList)>,
 }
 
 impl StatList {
     /// Creates a [StatList] from a slice of [StatModifier]s.
     pub fn from(mods: &[StatModifier]) -> StatList {
         let mut stat_list = StatList {
-            modifiers: HashMap::new(),
+            modifiers: BTreeMap::new(),
+            conditional: vec![],
         };
 
         for modifier in mods {
@@ -49,61 +163,134 @@ impl StatList {
         stat_list
     }
 
-    /// Adds another [StatList] to this one.
+    /// Adds another [StatList] to this one, including its conditional modifiers.
     pub fn add_list(&mut self, list: &StatList) -> &mut StatList {
         for stat_mod in list.mods().iter() {
             self.add_mod(stat_mod);
         }
 
+        for (condition, conditional_list) in &list.conditional {
+            for stat_mod in conditional_list.mods().iter() {
+                self.add_conditional_mod(*condition, stat_mod);
+            }
+        }
+
         self
     }
 
-    /// Adds a [StatModifier] to this [StatList].
-    fn add_mod(&mut self, modifier: &StatModifier) -> &mut StatList {
-        match self.modifiers.get_mut(&modifier.0) {
-            Some(val) => {
-                *val += modifier.1;
-
-                // If the stat would be 0 after modifying, remove it from the list
-                if *val == 0. {
-                    self.modifiers.remove(&modifier.0);
-                }
-            }
-            None => {
-                self.modifiers.insert(modifier.0, modifier.1);
+    /// Adds a [StatModifier] that only applies while `condition` holds.
+    pub fn add_conditional_mod(
+        &mut self,
+        condition: StatCondition,
+        modifier: &StatModifier,
+    ) -> &mut StatList {
+        match self
+            .conditional
+            .iter_mut()
+            .find(|(existing, _)| *existing == condition)
+        {
+            Some((_, conditional_list)) => {
+                conditional_list.add_mod(modifier);
             }
-        };
+            None => self
+                .conditional
+                .push((condition, StatList::from(std::slice::from_ref(modifier)))),
+        }
 
         self
     }
 
-    /// Retrieves all [StatModifier]s provided by the [StatList].
-    pub fn mods(&self) -> Vec<StatModifier> {
-        self.modifiers
+    /// Retrieves all conditional [StatModifier]s, combined per condition, stat and [StatModifierKind].
+    pub fn conditional_mods(&self) -> Vec<(StatCondition, StatModifier)> {
+        self.conditional
             .iter()
-            .map(|modifier| StatModifier(*modifier.0, *modifier.1))
+            .flat_map(|(condition, conditional_list)| {
+                conditional_list
+                    .mods()
+                    .into_iter()
+                    .map(|modifier| (*condition, modifier))
+            })
             .collect()
     }
 
-    /// Gets the modifier value of a [Stat] in a [StatList], if it exists.
-    pub fn get_stat(&self, stat: &Stat) -> Option<&f32> {
-        self.modifiers.get(stat)
+    /// This [StatList] without conditions, keeping only the conditional modifiers whose condition holds in `state`.
+    pub fn applying(&self, state: &ConditionState) -> StatList {
+        let mut stat_list = StatList {
+            modifiers: self.modifiers.clone(),
+            conditional: vec![],
+        };
+
+        for (condition, conditional_list) in &self.conditional {
+            if condition.holds(state) {
+                stat_list.add_list(conditional_list);
+            }
+        }
+
+        stat_list
+    }
+
+    /// Adds a [StatModifier] to this [StatList].
+    pub fn add_mod(&mut self, modifier: &StatModifier) -> &mut StatList {
+        let layers = self.modifiers.entry(modifier.0).or_default();
+        layers.add(modifier.2, modifier.1);
+
+        // If the stat would have no modifiers left, remove it from the list
+        if layers.is_empty() {
+            self.modifiers.remove(&modifier.0);
+        }
+
+        self
+    }
+
+    /// Retrieves all [StatModifier]s provided by the [StatList], combined per stat and [StatModifierKind].
+    pub fn mods(&self) -> Vec<StatModifier> {
+        let mut mods = vec![];
+
+        for (stat, layers) in &self.modifiers {
+            for (kind, value) in [
+                (StatModifierKind::Flat, layers.flat),
+                (StatModifierKind::Increased, layers.increased),
+                (StatModifierKind::More, layers.more),
+            ] {
+                if value != 0. {
+                    mods.push(StatModifier(*stat, value, kind));
+                }
+            }
+
+            if let Some(value) = layers.overridden {
+                mods.push(StatModifier(*stat, value, StatModifierKind::Override));
+            }
+        }
+
+        mods
+    }
+
+    /// Gets the resolved value of a stat with no base value, if the [StatList] modifies it.
+    pub fn get_stat(&self, stat: StatId) -> Option<f32> {
+        self.modifiers.get(&stat).map(|layers| layers.resolve(0.))
+    }
+
+    /// Resolves the value of a stat, applying every modifier to `base`.
+    /// Use [StatRegistry::resolve](crate::stat_registry::database::StatRegistry::resolve) to also apply the stat's default value and clamps.
+    pub fn resolve(&self, stat: StatId, base: f32) -> f32 {
+        self.modifiers
+            .get(&stat)
+            .map_or(base, |layers| layers.resolve(base))
     }
 }
 
+/// Shows every modifier as [StatModifier]'s [Display] does, so is only meant for logs too.
 impl Display for StatList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut buffer = String::new();
-        for stat in &self.modifiers {
-            buffer += format!(
-                "{}{} {:?}",
-                if stat.1 < &0. { "-" } else { "+" },
-                stat.1,
-                stat.0
-            )
-            .as_str();
+        let mut mods = self
+            .mods()
+            .iter()
+            .map(|modifier| modifier.to_string())
+            .collect::<Vec<_>>();
+        for (condition, modifier) in self.conditional_mods() {
+            mods.push(format!("{modifier} ({condition:?})"));
         }
-        write!(f, "{}", buffer.as_str())
+        write!(f, "{}", mods.join(", "))
     }
 }
 
@@ -111,10 +298,14 @@ impl Display for StatList {
 mod tests {
     use super::*;
 
+    const RESOLVE: StatId = 1;
+    const FINESSE: StatId = 2;
+    const COMPLEXITY: StatId = 3;
+
     #[test]
     fn add_different_stat_modifiers() {
-        let resolve_plus_one = StatModifier(Stat::Resolve, 1.);
-        let finesse_plus_two = StatModifier(Stat::Finesse, 2.);
+        let resolve_plus_one = StatModifier(RESOLVE, 1., StatModifierKind::Flat);
+        let finesse_plus_two = StatModifier(FINESSE, 2., StatModifierKind::Flat);
 
         let stat_list = StatList::from(&[resolve_plus_one.clone(), finesse_plus_two.clone()]);
         let mods = stat_list.mods();
@@ -126,21 +317,21 @@ mod tests {
 
     #[test]
     fn add_same_stat_modifiers() {
-        let resolve_plus_one = StatModifier(Stat::Resolve, 1.);
-        let resolve_plus_two = StatModifier(Stat::Resolve, 2.);
+        let resolve_plus_one = StatModifier(RESOLVE, 1., StatModifierKind::Flat);
+        let resolve_plus_two = StatModifier(RESOLVE, 2., StatModifierKind::Flat);
 
         let stat_list = StatList::from(&[resolve_plus_one.clone(), resolve_plus_two.clone()]);
         let mods = stat_list.mods();
 
         assert_eq!(mods.len(), 1);
-        let resolve_plus_three = StatModifier(Stat::Resolve, 3.);
+        let resolve_plus_three = StatModifier(RESOLVE, 3., StatModifierKind::Flat);
         assert!(mods.contains(&resolve_plus_three));
     }
 
     #[test]
     fn zero_stat_modifiers_omitted() {
-        let resolve_plus_one = StatModifier(Stat::Resolve, 1.);
-        let resolve_minus_one = StatModifier(Stat::Resolve, -1.);
+        let resolve_plus_one = StatModifier(RESOLVE, 1., StatModifierKind::Flat);
+        let resolve_minus_one = StatModifier(RESOLVE, -1., StatModifierKind::Flat);
 
         let stat_list = StatList::from(&[resolve_plus_one.clone(), resolve_minus_one.clone()]);
         let mods = stat_list.mods();
@@ -149,47 +340,101 @@ mod tests {
 
     #[test]
     fn add_stat_lists_different_modifiers() {
-        let mut stat_list_1 = StatList::from(&[StatModifier(Stat::Resolve, 1.)]);
-        let stat_list_2 = StatList::from(&[StatModifier(Stat::Finesse, 1.)]);
+        let mut stat_list_1 = StatList::from(&[StatModifier(RESOLVE, 1., StatModifierKind::Flat)]);
+        let stat_list_2 = StatList::from(&[StatModifier(FINESSE, 1., StatModifierKind::Flat)]);
 
         let new_list = stat_list_1.add_list(&stat_list_2);
 
         assert_eq!(new_list.mods().len(), 2);
-        let resolve_plus_one = StatModifier(Stat::Resolve, 1.);
-        let finesse_plus_one = StatModifier(Stat::Finesse, 1.);
+        let resolve_plus_one = StatModifier(RESOLVE, 1., StatModifierKind::Flat);
+        let finesse_plus_one = StatModifier(FINESSE, 1., StatModifierKind::Flat);
         assert!(new_list.mods().contains(&resolve_plus_one));
         assert!(new_list.mods().contains(&finesse_plus_one));
     }
 
     #[test]
     fn add_stat_lists_same_modifiers() {
-        let mut stat_list_1 = StatList::from(&[StatModifier(Stat::Resolve, 1.)]);
-        let stat_list_2 = StatList::from(&[StatModifier(Stat::Resolve, 1.)]);
+        let mut stat_list_1 = StatList::from(&[StatModifier(RESOLVE, 1., StatModifierKind::Flat)]);
+        let stat_list_2 = StatList::from(&[StatModifier(RESOLVE, 1., StatModifierKind::Flat)]);
 
         let new_list = stat_list_1.add_list(&stat_list_2);
 
         assert_eq!(new_list.mods().len(), 1);
-        let resolve_plus_two = StatModifier(Stat::Resolve, 2.);
+        let resolve_plus_two = StatModifier(RESOLVE, 2., StatModifierKind::Flat);
         assert!(new_list.mods().contains(&resolve_plus_two));
     }
 
     #[test]
     fn get_stat_in_stat_list() {
         let stat_list = StatList::from(&[
-            StatModifier(Stat::Resolve, 1.),
-            StatModifier(Stat::Finesse, 2.),
+            StatModifier(RESOLVE, 1., StatModifierKind::Flat),
+            StatModifier(FINESSE, 2., StatModifierKind::Flat),
         ]);
 
-        let resolve_stat = stat_list.get_stat(&Stat::Resolve);
-        let finesse_stat = stat_list.get_stat(&Stat::Finesse);
-        let complexity_stat = stat_list.get_stat(&Stat::Complexity);
+        let resolve_stat = stat_list.get_stat(RESOLVE);
+        let finesse_stat = stat_list.get_stat(FINESSE);
+        let complexity_stat = stat_list.get_stat(COMPLEXITY);
 
         assert!(resolve_stat.is_some());
-        assert_eq!(*resolve_stat.unwrap(), 1.);
+        assert_eq!(resolve_stat.unwrap(), 1.);
 
         assert!(finesse_stat.is_some());
-        assert_eq!(*finesse_stat.unwrap(), 2.);
+        assert_eq!(finesse_stat.unwrap(), 2.);
 
         assert!(complexity_stat.is_none());
     }
+
+    #[test]
+    fn resolve_applies_layers_in_order() {
+        let stat_list = StatList::from(&[
+            StatModifier(MOVE_SPEED, 20., StatModifierKind::Flat),
+            StatModifier(MOVE_SPEED, 10., StatModifierKind::Increased),
+            StatModifier(MOVE_SPEED, 40., StatModifierKind::Increased),
+            StatModifier(MOVE_SPEED, 20., StatModifierKind::More),
+            StatModifier(MOVE_SPEED, 50., StatModifierKind::More),
+        ]);
+
+        // (100 + 20) * (1 + 0.5) * 1.2 * 1.5
+        let move_speed = stat_list.resolve(MOVE_SPEED, 100.);
+        assert!((move_speed - 324.).abs() < 0.001);
+
+        assert_eq!(stat_list.resolve(HEALTH, 10.), 10.);
+    }
+
+    #[test]
+    fn override_replaces_resolved_value() {
+        let mut stat_list = StatList::from(&[
+            StatModifier(MOVE_SPEED, 20., StatModifierKind::Flat),
+            StatModifier(MOVE_SPEED, 50., StatModifierKind::More),
+        ]);
+        stat_list.add_list(&StatList::from(&[StatModifier(
+            MOVE_SPEED,
+            0.,
+            StatModifierKind::Override,
+        )]));
+
+        assert_eq!(stat_list.resolve(MOVE_SPEED, 100.), 0.);
+        assert!(stat_list.mods().contains(&StatModifier(
+            MOVE_SPEED,
+            0.,
+            StatModifierKind::Override
+        )));
+    }
+
+    #[test]
+    fn conditional_mods_only_apply_while_condition_holds() {
+        let mut stat_list =
+            StatList::from(&[StatModifier(MOVE_SPEED, 10., StatModifierKind::Flat)]);
+        stat_list.add_conditional_mod(
+            StatCondition::Moving,
+            &StatModifier(MOVE_SPEED, 50., StatModifierKind::Increased),
+        );
+        assert_eq!(stat_list.resolve(MOVE_SPEED, 100.), 110.);
+
+        let mut state = ConditionState::default();
+        assert_eq!(stat_list.applying(&state).resolve(MOVE_SPEED, 100.), 110.);
+
+        state.moving = true;
+        assert_eq!(stat_list.applying(&state).resolve(MOVE_SPEED, 100.), 165.);
+    }
 }
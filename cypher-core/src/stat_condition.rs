@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Condition that a conditional stat modifier only applies while it holds.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatCondition {
+    /// While the character moved since the last tick.
+    Moving,
+
+    /// While the character's health is at its maximum.
+    FullHealth,
+
+    /// Against targets on the given team.
+    AgainstTeam(u16),
+
+    /// For the given number of seconds after the character kills something.
+    AfterKill(f32),
+}
+
+impl StatCondition {
+    pub fn validate(&self) -> bool {
+        match self {
+            StatCondition::AfterKill(seconds) => *seconds > 0.,
+            _ => true,
+        }
+    }
+
+    /// Whether this condition holds for a character in `state`.
+    pub fn holds(&self, state: &ConditionState) -> bool {
+        match self {
+            StatCondition::Moving => state.moving,
+            StatCondition::FullHealth => state.full_health,
+            StatCondition::AgainstTeam(team_id) => state.target_team == Some(*team_id),
+            StatCondition::AfterKill(seconds) => state
+                .seconds_since_kill
+                .is_some_and(|seconds_since_kill| seconds_since_kill <= *seconds),
+        }
+    }
+}
+
+/// Current state of a character, that [StatCondition]s are checked against.
+/// Updated every tick by the world's systems.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConditionState {
+    pub moving: bool,
+
+    pub full_health: bool,
+
+    /// Team of whoever the character's stats are being resolved against, if anyone.
+    pub target_team: Option<u16>,
+
+    /// `None` if the character hasn't killed anything yet.
+    pub seconds_since_kill: Option<f32>,
+}
+
+impl ConditionState {
+    /// Advances timed conditions by `delta_seconds`.
+    pub fn tick(&mut self, delta_seconds: f32) {
+        if let Some(seconds_since_kill) = &mut self.seconds_since_kill {
+            *seconds_since_kill += delta_seconds;
+        }
+    }
+
+    pub fn record_kill(&mut self) {
+        self.seconds_since_kill = Some(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn after_kill_expires() {
+        let condition = StatCondition::AfterKill(4.);
+        let mut state = ConditionState::default();
+        assert!(!condition.holds(&state));
+
+        state.record_kill();
+        state.tick(3.);
+        assert!(condition.holds(&state));
+
+        state.tick(2.);
+        assert!(!condition.holds(&state));
+    }
+
+    #[test]
+    fn against_team_only_holds_for_that_team() {
+        let condition = StatCondition::AgainstTeam(2);
+        let mut state = ConditionState::default();
+        assert!(!condition.holds(&state));
+
+        state.target_team = Some(1);
+        assert!(!condition.holds(&state));
+
+        state.target_team = Some(2);
+        assert!(condition.holds(&state));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    affix::definition::AffixDefinitionId,
+    stat::{StatId, StatList, StatModifier},
+};
+
+use super::definition::StatDerivationId;
+
+/// Where a [StatModifier] in a [StatBreakdown] came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatSource {
+    /// A character's own stats.
+    Base,
+
+    /// A rolled affix on equipment.
+    Affix(AffixDefinitionId),
+
+    /// Granted by another stat, through a [StatDerivationDefinition](super::definition::StatDerivationDefinition).
+    Derivation(StatDerivationId),
+}
+
+/// Every [StatModifier] that makes up a set of stats, along with where each one came from.
+#[derive(Clone, Debug, Default)]
+pub struct StatBreakdown {
+    contributions: Vec<(StatSource, StatModifier)>,
+}
+
+impl StatBreakdown {
+    /// Adds every modifier in `stats` as coming from `source`.
+    pub fn add(&mut self, source: StatSource, stats: &StatList) {
+        for modifier in stats.mods() {
+            self.contributions.push((source, modifier));
+        }
+    }
+
+    /// Every modifier of `stat`, in the order they were added.
+    pub fn contributions(&self, stat: StatId) -> Vec<&(StatSource, StatModifier)> {
+        self.contributions
+            .iter()
+            .filter(|(_, modifier)| modifier.0 == stat)
+            .collect()
+    }
+
+    /// Combines every contribution into a single [StatList].
+    pub fn stats(&self) -> StatList {
+        let mods = self
+            .contributions
+            .iter()
+            .map(|(_, modifier)| modifier.clone())
+            .collect::<Vec<_>>();
+
+        StatList::from(&mods)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    data::{DataDefinitionDatabase, DataMigrations, DefinitionStore},
+    stat::{migrate_legacy_stat, StatList, StatModifier},
+};
+
+use super::{
+    breakdown::{StatBreakdown, StatSource},
+    definition::StatDerivationDefinition,
+};
+
+pub struct StatDerivationDatabase {
+    derivations: DefinitionStore<StatDerivationDefinition>,
+}
+
+impl DataDefinitionDatabase<StatDerivationDefinition> for StatDerivationDatabase {
+    type DataDependencies = ();
+
+    const FILE_NAME: &'static str = "stat_derivation.json";
+
+    fn from_store(derivations: DefinitionStore<StatDerivationDefinition>) -> Self {
+        StatDerivationDatabase { derivations }
+    }
+
+    fn store(&self) -> &DefinitionStore<StatDerivationDefinition> {
+        &self.derivations
+    }
+
+    fn migrations() -> DataMigrations {
+        DataMigrations::default().with(stat_names_to_ids)
+    }
+}
+
+/// Version 2 refers to stats by their ID in the stat registry, rather than by name.
+fn stat_names_to_ids(definitions: &mut serde_json::Value) -> Result<(), String> {
+    let definitions = definitions
+        .as_array_mut()
+        .ok_or("definitions are not a list")?;
+
+    for definition in definitions {
+        for field in ["source", "target"] {
+            if let Some(stat) = definition.get_mut(field) {
+                migrate_legacy_stat(stat)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl StatDerivationDatabase {
+    pub fn initialize() -> Self {
+        let mut path = std::env::current_dir().unwrap();
+        path.push("..");
+        path.push("cypher-core");
+        path.push("data");
+        path.push(Self::FILE_NAME);
+
+        Self::load_from(path.to_str().unwrap(), &())
+            .expect("failed to load stat derivation database")
+    }
+
+    /// Adds the stats granted by every derivation to `breakdown`, based on the stats already in it.
+    /// Derived stats don't grant further stats, so the order derivations are defined in doesn't matter.
+    pub fn derive(&self, breakdown: &mut StatBreakdown) {
+        let stats = breakdown.stats();
+
+        let mut derivations = self.definitions();
+        derivations.sort_by_key(|def| def.id);
+
+        for derivation in derivations {
+            let Some(points) = stats.get_stat(derivation.source) else {
+                continue;
+            };
+
+            breakdown.add(
+                StatSource::Derivation(derivation.id),
+                &StatList::from(&[StatModifier(
+                    derivation.target,
+                    points * derivation.per_point,
+                    derivation.kind,
+                )]),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stat::{StatId, StatModifierKind, HEALTH};
+
+    const RESOLVE: StatId = 1;
+    const ENERGY: StatId = 6;
+
+    #[test]
+    fn init_stat_derivation_database() {
+        let _ = StatDerivationDatabase::initialize();
+    }
+
+    #[test]
+    fn derived_stats_record_their_source() {
+        let derivation_db = StatDerivationDatabase::from_store(DefinitionStore::new(vec![
+            StatDerivationDefinition {
+                id: 1,
+                source: RESOLVE,
+                target: HEALTH,
+                kind: StatModifierKind::Flat,
+                per_point: 2.,
+            },
+            StatDerivationDefinition {
+                id: 2,
+                source: HEALTH,
+                target: ENERGY,
+                kind: StatModifierKind::Flat,
+                per_point: 1.,
+            },
+        ]));
+
+        let mut breakdown = StatBreakdown::default();
+        breakdown.add(
+            StatSource::Base,
+            &StatList::from(&[
+                StatModifier(RESOLVE, 5., StatModifierKind::Flat),
+                StatModifier(HEALTH, 10., StatModifierKind::Flat),
+            ]),
+        );
+        derivation_db.derive(&mut breakdown);
+
+        let stats = breakdown.stats();
+        assert_eq!(stats.get_stat(HEALTH), Some(20.));
+        // Health derived from Resolve doesn't also grant Energy
+        assert_eq!(stats.get_stat(ENERGY), Some(10.));
+
+        let health = breakdown.contributions(HEALTH);
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[1].0, StatSource::Derivation(1));
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::DataDefinition,
+    stat::{StatId, StatModifierKind},
+};
+
+pub type StatDerivationId = u32;
+
+/// Grants a stat for every point of another stat, such as 2 Health per point of Resolve.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatDerivationDefinition {
+    /// Opaque ID.
+    pub id: StatDerivationId,
+
+    /// Stat whose resolved value is read.
+    pub source: StatId,
+
+    /// Stat that is granted.
+    pub target: StatId,
+
+    /// How the granted stat combines with the target's other modifiers.
+    pub kind: StatModifierKind,
+
+    /// Amount of the target stat granted per point of the source stat.
+    pub per_point: f32,
+}
+
+impl DataDefinition for StatDerivationDefinition {
+    type DefinitionTypeId = StatDerivationId;
+
+    const TYPE_NAME: &'static str = "stat derivation";
+
+    fn id(&self) -> u64 {
+        self.id as u64
+    }
+
+    fn validate(&self) -> bool {
+        self.id > 0 && self.source != self.target && self.per_point.is_finite()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod breakdown;
+pub mod database;
+pub mod definition;
@@ -0,0 +1,82 @@
+use crate::{
+    data::{DataDefinitionDatabase, DefinitionStore},
+    stat::{StatId, StatList},
+};
+
+use super::definition::StatDefinition;
+
+/// Every stat in the game, as defined in data.
+pub struct StatRegistry {
+    stats: DefinitionStore<StatDefinition>,
+}
+
+impl DataDefinitionDatabase<StatDefinition> for StatRegistry {
+    type DataDependencies = ();
+
+    const FILE_NAME: &'static str = "stat.json";
+
+    fn from_store(stats: DefinitionStore<StatDefinition>) -> Self {
+        StatRegistry { stats }
+    }
+
+    fn store(&self) -> &DefinitionStore<StatDefinition> {
+        &self.stats
+    }
+}
+
+impl StatRegistry {
+    pub fn initialize() -> Self {
+        let mut path = std::env::current_dir().unwrap();
+        path.push("..");
+        path.push("cypher-core");
+        path.push("data");
+        path.push(Self::FILE_NAME);
+
+        Self::load_from(path.to_str().unwrap(), &()).expect("failed to load stat registry")
+    }
+
+    /// Resolves the final value of `stat`, applying every modifier in `stat_list` to the stat's default value
+    /// before clamping it. Stats missing from the registry resolve as if they had no default or clamps.
+    pub fn resolve(&self, stat_list: &StatList, stat: StatId) -> f32 {
+        match self.definition(stat) {
+            Some(definition) => definition.clamp(stat_list.resolve(stat, definition.default_value)),
+            None => stat_list.resolve(stat, 0.),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stat::{StatModifier, StatModifierKind, MOVE_SPEED},
+        stat_registry::definition::StatDisplayFormat,
+    };
+
+    #[test]
+    fn init_stat_registry() {
+        let _ = StatRegistry::initialize();
+    }
+
+    #[test]
+    fn resolve_applies_default_and_clamps() {
+        let registry = StatRegistry::from_store(DefinitionStore::new(vec![StatDefinition {
+            id: MOVE_SPEED,
+            name: String::from("stat.move_speed"),
+            default_value: 100.,
+            min: Some(50.),
+            max: Some(250.),
+            integer: true,
+            format: StatDisplayFormat::Number,
+        }]));
+
+        let stat_list = StatList::from(&[StatModifier(MOVE_SPEED, 10.5, StatModifierKind::Flat)]);
+        assert_eq!(registry.resolve(&stat_list, MOVE_SPEED), 110.);
+
+        let stat_list = StatList::from(&[StatModifier(MOVE_SPEED, -90., StatModifierKind::Flat)]);
+        assert_eq!(registry.resolve(&stat_list, MOVE_SPEED), 50.);
+
+        let stat_list = StatList::from(&[StatModifier(MOVE_SPEED, 200., StatModifierKind::More)]);
+        assert_eq!(registry.resolve(&stat_list, MOVE_SPEED), 250.);
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{data::DataDefinition, localization::LocalizationKey, stat::StatId};
+
+/// How a stat's values are displayed.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatDisplayFormat {
+    /// Plain number, such as `+5 Health`.
+    Number,
+
+    /// Percentage, such as `+5% Fire Resistance`.
+    Percent,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatDefinition {
+    /// Opaque ID.
+    pub id: StatId,
+
+    /// Key of the display name in the string tables.
+    pub name: LocalizationKey,
+
+    /// Value of the stat before any modifiers are applied.
+    pub default_value: f32,
+
+    /// Lowest value the stat resolves to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f32>,
+
+    /// Highest value the stat resolves to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f32>,
+
+    /// Whether resolved values are rounded down to whole numbers.
+    pub integer: bool,
+
+    pub format: StatDisplayFormat,
+}
+
+impl DataDefinition for StatDefinition {
+    type DefinitionTypeId = StatId;
+
+    const TYPE_NAME: &'static str = "stat";
+
+    fn id(&self) -> u64 {
+        self.id as u64
+    }
+
+    fn validate(&self) -> bool {
+        let min = self.min.unwrap_or(f32::MIN);
+        let max = self.max.unwrap_or(f32::MAX);
+
+        self.id > 0 && min <= max && (min..=max).contains(&self.default_value)
+    }
+}
+
+impl StatDefinition {
+    /// Applies this stat's clamps and rounding to a resolved value.
+    pub fn clamp(&self, value: f32) -> f32 {
+        let value = if self.integer { value.floor() } else { value };
+
+        value
+            .max(self.min.unwrap_or(f32::MIN))
+            .min(self.max.unwrap_or(f32::MAX))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::stat::StatModifierKind;
+
+/// Value shown by a stat text template's `{value}` placeholder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatTextValue {
+    /// A rolled value, and the range it was rolled from if it should be shown.
+    Rolled(f32, Option<(f32, f32)>),
+
+    /// Every value a stat can roll, for text shown before anything is rolled, such as in the data editor.
+    Range(f32, f32),
+}
+
+/// Everything a stat text template's placeholders are filled in from.
+pub struct StatTextValues<'a> {
+    /// Display name of the stat.
+    pub stat: &'a str,
+
+    pub value: StatTextValue,
+
+    /// Decimal places values are shown with. Values are shown as precisely as needed if unset.
+    pub precision_places: Option<u32>,
+
+    /// Unit of the stat's values, such as `%` for percentage stats.
+    pub unit: &'a str,
+}
+
+/// Placeholders a stat text template can use:
+/// - `{value}`: the value, signed only when negative
+/// - `{+value}`: the value, always signed
+/// - `{range}`: the range the value was rolled from, such as ` (10-30)`, or nothing if there isn't one to show
+/// - `{stat}`: the stat's display name
+/// - `{unit}`: the stat's unit, such as `%`
+pub const PLACEHOLDERS: [&str; 5] = ["value", "+value", "range", "stat", "unit"];
+
+/// Template used for a stat that doesn't have its own for `kind`.
+pub fn default_template(kind: StatModifierKind) -> &'static str {
+    match kind {
+        StatModifierKind::Flat => "{+value}{unit} {stat}{range}",
+        StatModifierKind::Increased => "{+value}% increased {stat}{range}",
+        StatModifierKind::More => "{+value}% more {stat}{range}",
+        StatModifierKind::Override => "{stat} is {value}{unit}{range}",
+    }
+}
+
+/// Suffix of the localization key of a stat's template for `kind`, such as `stat.move_speed.increased`.
+pub fn template_key_suffix(kind: StatModifierKind) -> &'static str {
+    match kind {
+        StatModifierKind::Flat => "flat",
+        StatModifierKind::Increased => "increased",
+        StatModifierKind::More => "more",
+        StatModifierKind::Override => "override",
+    }
+}
+
+/// Formats `value` with `precision_places` decimal places, or as precisely as needed if unset.
+pub fn format_number(value: f32, precision_places: Option<u32>) -> String {
+    match precision_places {
+        Some(places) => format!("{:.*}", places as usize, value),
+        None => format!("{value}"),
+    }
+}
+
+/// Fills in every placeholder of `template`. Unknown placeholders are left as they are, so they're easy to spot.
+pub fn render(template: &str, values: &StatTextValues) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+
+        let Some(length) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let placeholder = &rest[start + 1..start + length];
+        match placeholder_text(placeholder, values) {
+            Some(value) => text.push_str(&value),
+            None => text.push_str(&rest[start..=start + length]),
+        }
+
+        rest = &rest[start + length + 1..];
+    }
+    text.push_str(rest);
+
+    text
+}
+
+/// Every placeholder in `template` that isn't one of [PLACEHOLDERS].
+pub fn unknown_placeholders(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(placeholder, _)| placeholder))
+        .filter(|placeholder| !PLACEHOLDERS.contains(placeholder))
+        .collect()
+}
+
+fn placeholder_text(placeholder: &str, values: &StatTextValues) -> Option<String> {
+    let number = |value: f32| format_number(value, values.precision_places);
+    let signed = |value: f32| {
+        let sign = if value >= 0. { "+" } else { "" };
+        format!("{sign}{}", number(value))
+    };
+
+    Some(match (placeholder, values.value) {
+        ("value", StatTextValue::Rolled(value, _)) => number(value),
+        ("+value", StatTextValue::Rolled(value, _)) => signed(value),
+        ("value", StatTextValue::Range(lower, upper)) => {
+            format!("({}-{})", number(lower), number(upper))
+        }
+        ("+value", StatTextValue::Range(lower, upper)) => {
+            let sign = if lower >= 0. { "+" } else { "" };
+            format!("{sign}({}-{})", number(lower), number(upper))
+        }
+        ("range", StatTextValue::Rolled(_, Some((lower, upper)))) => {
+            format!(" ({}-{})", number(lower), number(upper))
+        }
+        ("range", _) => String::new(),
+        ("stat", _) => values.stat.to_owned(),
+        ("unit", _) => values.unit.to_owned(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(value: StatTextValue, precision_places: Option<u32>) -> StatTextValues<'static> {
+        StatTextValues {
+            stat: "Movement Speed",
+            value,
+            precision_places,
+            unit: "",
+        }
+    }
+
+    #[test]
+    fn renders_signs_ranges_and_precision() {
+        let template = "{+value}% increased {stat}{range}";
+
+        assert_eq!(
+            render(template, &values(StatTextValue::Rolled(12.5, None), None)),
+            "+12.5% increased Movement Speed"
+        );
+        assert_eq!(
+            render(
+                template,
+                &values(StatTextValue::Rolled(12., Some((10., 30.))), Some(1))
+            ),
+            "+12.0% increased Movement Speed (10.0-30.0)"
+        );
+        assert_eq!(
+            render(template, &values(StatTextValue::Range(10., 30.), Some(0))),
+            "+(10-30)% increased Movement Speed"
+        );
+        assert_eq!(
+            render(
+                "{value} {stat} {missing}",
+                &values(StatTextValue::Rolled(-5., None), None)
+            ),
+            "-5 Movement Speed {missing}"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholders_are_found() {
+        assert!(unknown_placeholders(default_template(StatModifierKind::Flat)).is_empty());
+        assert_eq!(
+            unknown_placeholders("{+value} {stat} {vlaue}"),
+            vec!["vlaue"]
+        );
+    }
+}
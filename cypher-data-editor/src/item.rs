@@ -1,27 +1,23 @@
 use crate::DataEditorApp;
 use cypher_core::affix::definition::AffixDefinition;
 use cypher_core::affix::placement::AffixPlacement;
+use cypher_core::affix::tag::AffixTagRules;
 use cypher_core::affix_pool::definition::AffixPoolDefinition;
-use cypher_core::data::DataDefinitionDatabase;
 use cypher_item::item::classification::{ItemClassification, ItemEquipSlot};
 use cypher_item::item::definition::ItemDefinition;
 use egui_extras::{Column, TableBuilder};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
     egui::TopBottomPanel::top("item_menu_bar").show(ctx, |ui| {
         if ui.button("Add").clicked() {
             app.invalidate_selections();
 
-            let mut item_db = app.item_db.lock().unwrap();
-            let items = item_db.definitions();
-            let next_id = items.iter().fold(0, |acc, next| {
-                if acc > next.lock().unwrap().id {
-                    acc
-                } else {
-                    next.lock().unwrap().id
-                }
-            }) + 1;
+            let items = app.item_db.definitions();
+            let next_id = items
+                .iter()
+                .fold(0, |acc, next| if acc > next.id { acc } else { next.id })
+                + 1;
             let new_affix = ItemDefinition {
                 id: next_id,
                 classification: ItemClassification::Invalid,
@@ -30,7 +26,7 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                 name: String::new(),
             };
 
-            item_db.add_definition(new_affix);
+            app.item_db.add_definition(new_affix);
         }
     });
 
@@ -45,21 +41,16 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                 ui.separator();
 
                 egui::ScrollArea::vertical().show(ui, |ui| {
-                    let item_db = app.item_db.lock().unwrap();
-                    let mut items = item_db.definitions();
-                    let mut item = items
-                        .iter_mut()
-                        .find(|def| {
-                            def.lock().unwrap().id as u64 == app.selected_definition_id.unwrap()
-                        })
-                        .unwrap()
-                        .lock()
+                    let item = app
+                        .item_db
+                        .definition_mut(app.selected_definition_id.unwrap())
                         .unwrap();
 
                     ui.label(format!("Id: {}", item.id));
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut item.name);
+                        ui.label(app.localization.text(&item.name));
                     });
 
                     ui.horizontal(|ui| {
@@ -120,12 +111,15 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                                 let next_id = item.fixed_affixes.len() as u32;
                                 item.fixed_affixes.insert(
                                     next_id as usize,
-                                    Arc::new(Mutex::new(AffixDefinition {
+                                    Arc::new(AffixDefinition {
                                         id: next_id,
                                         placement: AffixPlacement::Invalid,
                                         tiers: Default::default(),
                                         name: String::default(),
-                                    })),
+                                        tags: vec![],
+                                        mod_group: None,
+                                    })
+                                    .into(),
                                 );
                             }
                         });
@@ -136,27 +130,28 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                         for def in &item.fixed_affixes {
                             ui.horizontal(|ui| {
                                 let (mut affix_id, affix_name) = {
-                                    let affix_def = def.lock().unwrap();
+                                    let affix_def = def;
 
-                                    (affix_def.id, affix_def.name.clone())
+                                    (
+                                        affix_def.id,
+                                        app.localization.text(&affix_def.name).to_owned(),
+                                    )
                                 };
 
                                 egui::ComboBox::from_id_source(format!("ItemFixedAffix{:?}", def))
                                     .selected_text(format!("{}", affix_name))
                                     .show_ui(ui, |ui| {
                                         let selected_affix_id = &mut affix_id;
-                                        let mut affix_definitions =
-                                            app.affix_db.lock().unwrap().definitions();
-                                        affix_definitions.retain(|def| {
-                                            def.lock().unwrap().id != *selected_affix_id
-                                        });
+                                        let mut affix_definitions = app.affix_db.definitions();
+                                        affix_definitions
+                                            .retain(|def| def.id != *selected_affix_id);
 
                                         for affix in affix_definitions {
-                                            let affix_def = affix.lock().unwrap();
+                                            let affix_def = affix;
                                             ui.selectable_value(
                                                 selected_affix_id,
                                                 affix_def.id,
-                                                affix_def.name.as_str(),
+                                                app.localization.text(&affix_def.name),
                                             );
                                         }
                                     });
@@ -165,7 +160,7 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                                     remove_id = Some(item.fixed_affixes.len() as u32 - 1);
                                 }
 
-                                let old_id = def.lock().unwrap().id;
+                                let old_id = def.id;
                                 if old_id != affix_id {
                                     replace_ids = Some((old_id, affix_id));
                                 }
@@ -173,18 +168,17 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                         }
 
                         if let Some(id) = remove_id {
-                            item.fixed_affixes
-                                .retain(|affix| affix.lock().unwrap().id != id);
+                            item.fixed_affixes.retain(|affix| affix.id != id);
                         }
 
                         if let Some((old_id, new_id)) = replace_ids {
                             let item_idx = item
                                 .fixed_affixes
-                                .binary_search_by(|probe| probe.lock().unwrap().id.cmp(&old_id))
+                                .binary_search_by(|probe| probe.id.cmp(&old_id))
                                 .unwrap();
                             item.fixed_affixes.remove(item_idx);
-                            let new_def = app.affix_db.lock().unwrap().definition(new_id).unwrap();
-                            item.fixed_affixes.push(new_def);
+                            let new_def = app.affix_db.definition(new_id).unwrap();
+                            item.fixed_affixes.push(new_def.into());
                         }
                     }
 
@@ -197,11 +191,13 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                                 let next_id = item.affix_pools.len() as u32;
                                 item.affix_pools.insert(
                                     next_id as usize,
-                                    Arc::new(Mutex::new(AffixPoolDefinition {
+                                    Arc::new(AffixPoolDefinition {
                                         id: next_id,
                                         members: vec![],
                                         name: String::default(),
-                                    })),
+                                        tag_rules: AffixTagRules::default(),
+                                    })
+                                    .into(),
                                 );
                             }
                         });
@@ -212,9 +208,12 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                         for def in &item.affix_pools {
                             ui.horizontal(|ui| {
                                 let (mut affix_pool_id, affix_pool_name) = {
-                                    let affix_pool = def.lock().unwrap();
+                                    let affix_pool = def;
 
-                                    (affix_pool.id, affix_pool.name.clone())
+                                    (
+                                        affix_pool.id,
+                                        app.localization.text(&affix_pool.name).to_owned(),
+                                    )
                                 };
 
                                 egui::ComboBox::from_id_source(format!("ItemAffixPools{:?}", def))
@@ -222,17 +221,16 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                                     .show_ui(ui, |ui| {
                                         let selected_pool_id = &mut affix_pool_id;
                                         let mut affix_pool_definitions =
-                                            app.affix_pool_db.lock().unwrap().definitions();
-                                        affix_pool_definitions.retain(|def| {
-                                            def.lock().unwrap().id != *selected_pool_id
-                                        });
+                                            app.affix_pool_db.definitions();
+                                        affix_pool_definitions
+                                            .retain(|def| def.id != *selected_pool_id);
 
                                         for pool in affix_pool_definitions {
-                                            let pool_def = pool.lock().unwrap();
+                                            let pool_def = pool;
                                             ui.selectable_value(
                                                 selected_pool_id,
                                                 pool_def.id,
-                                                pool_def.name.as_str(),
+                                                app.localization.text(&pool_def.name),
                                             );
                                         }
                                     });
@@ -241,7 +239,7 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                                     remove_id = Some(item.affix_pools.len() as u32 - 1);
                                 }
 
-                                let old_id = def.lock().unwrap().id;
+                                let old_id = def.id;
                                 if old_id != affix_pool_id {
                                     replace_ids = Some((old_id, affix_pool_id));
                                 }
@@ -249,23 +247,17 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                         }
 
                         if let Some(id) = remove_id {
-                            item.affix_pools
-                                .retain(|pool| pool.lock().unwrap().id != id);
+                            item.affix_pools.retain(|pool| pool.id != id);
                         }
 
                         if let Some((old_id, new_id)) = replace_ids {
                             let item_idx = item
                                 .affix_pools
-                                .binary_search_by(|probe| probe.lock().unwrap().id.cmp(&old_id))
+                                .binary_search_by(|probe| probe.id.cmp(&old_id))
                                 .unwrap();
                             item.affix_pools.remove(item_idx);
-                            let new_def = app
-                                .affix_pool_db
-                                .lock()
-                                .unwrap()
-                                .definition(new_id)
-                                .unwrap();
-                            item.affix_pools.push(new_def);
+                            let new_def = app.affix_pool_db.definition(new_id).unwrap();
+                            item.affix_pools.push(new_def.into());
                         }
                     }
                 });
@@ -296,22 +288,19 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                 });
             })
             .body(|body| {
-                let mut items = {
-                    let item_db = app.item_db.lock().unwrap();
-                    item_db.definitions()
-                };
+                let mut items = app.item_db.definitions();
 
                 // ZJ-TODO: other sorting/filtering methods? would be nice to sort/filter other columns, not just ID
-                items.sort_by(|a, b| a.lock().unwrap().id.cmp(&b.lock().unwrap().id));
+                items.sort_by_key(|definition| definition.id);
 
                 body.rows(30., items.len(), |mut row| {
-                    let item = items[row.index()].lock().unwrap();
+                    let item = &items[row.index()];
 
                     if let Some(selected_definition_id) = app.selected_definition_id {
                         row.set_selected(selected_definition_id == item.id as u64);
                     }
 
-                    app.populate_row_for_definition(&*item, &mut row);
+                    app.populate_row_for_definition(&**item, &mut row);
                 });
             });
     });
@@ -1,23 +1,21 @@
 use crate::table_display::TableDisplay;
 use crate::DataEditorApp;
-use cypher_core::data::DataDefinitionDatabase;
-use cypher_item::item::classification::ItemClassification;
-use cypher_item::item::definition::ItemDefinition;
+use cypher_core::localization::Localization;
 use cypher_item::loot_pool::definition::LootPoolDefinition;
-use cypher_item::loot_pool::member::LootPoolMember;
+use cypher_item::loot_pool::member::{LootPoolEntry, LootPoolMember};
 use egui_extras::{Column, TableBuilder};
-use std::sync::{Arc, Mutex};
 
 impl TableDisplay for LootPoolDefinition {
     fn header_row_values() -> Vec<&'static str> {
-        vec!["Id", "Name", "Members"]
+        vec!["Id", "Name", "Members", "Rolls"]
     }
 
-    fn data_row_values(&self) -> Vec<String> {
+    fn data_row_values(&self, _localization: &Localization) -> Vec<String> {
         vec![
             self.id.to_string(),
             self.name.clone(),
             self.members.len().to_string(),
+            self.rolls().to_string(),
         ]
     }
 }
@@ -27,22 +25,19 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
         if ui.button("Add").clicked() {
             app.invalidate_selections();
 
-            let mut loot_pool_db = app.loot_pool_db.lock().unwrap();
-            let loot_pools = loot_pool_db.definitions();
-            let next_id = loot_pools.iter().fold(0, |acc, next| {
-                if acc > next.lock().unwrap().id {
-                    acc
-                } else {
-                    next.lock().unwrap().id
-                }
-            }) + 1;
+            let loot_pools = app.loot_pool_db.definitions();
+            let next_id = loot_pools
+                .iter()
+                .fold(0, |acc, next| if acc > next.id { acc } else { next.id })
+                + 1;
             let new_loot_pool = LootPoolDefinition {
                 id: next_id,
                 name: String::new(),
                 members: vec![],
+                rolls: None,
             };
 
-            loot_pool_db.add_definition(new_loot_pool);
+            app.loot_pool_db.add_definition(new_loot_pool);
         }
     });
 
@@ -56,131 +51,134 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                 }
                 ui.separator();
 
+                let selected_id = app.selected_definition_id.unwrap() as u32;
+
+                // Pools that can be nested in the selected pool, listed before it's borrowed for editing
+                let mut pool_names = app
+                    .loot_pool_db
+                    .definitions()
+                    .iter()
+                    .filter(|def| def.id != selected_id)
+                    .map(|def| (def.id, def.name.clone()))
+                    .collect::<Vec<_>>();
+                pool_names.sort_by_key(|(id, _)| *id);
+
+                let mut item_defs = app.item_db.definitions();
+                item_defs.sort_by_key(|def| def.id);
+
                 egui::ScrollArea::vertical().show(ui, |ui| {
-                    let loot_pool_db = app.loot_pool_db.lock().unwrap();
-                    let mut loot_pools = loot_pool_db.definitions();
-                    let mut loot_pool = loot_pools
-                        .iter_mut()
-                        .find(|def| {
-                            def.lock().unwrap().id as u64 == app.selected_definition_id.unwrap()
-                        })
-                        .unwrap()
-                        .lock()
-                        .unwrap();
+                    let loot_pool = app.loot_pool_db.definition_mut(selected_id).unwrap();
 
                     ui.label(format!("Id: {}", loot_pool.id));
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut loot_pool.name);
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("Rolls");
+                        let mut rolls = loot_pool.rolls();
+                        ui.add(egui::DragValue::new(&mut rolls).clamp_range(0..=100));
+                        loot_pool.rolls = (rolls != 1).then_some(rolls);
+                    });
 
                     ui.separator();
 
-                    for member in &mut loot_pool.members {
-                        let member_def = member.item_def.lock().unwrap();
-                        ui.horizontal(|ui| {
-                            ui.label(format!("{}", member_def.name));
-                            ui.horizontal(|ui| {
-                                ui.label("Weight");
-                                ui.add(egui::DragValue::new(&mut member.weight));
-                            });
-                        });
-                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Members");
+                        if let Some(item_def) = item_defs.first() {
+                            if ui.button("Add Item").clicked() {
+                                loot_pool.members.push(LootPoolMember::new(
+                                    LootPoolEntry::Item(item_def.clone().into()),
+                                    1,
+                                ));
+                            }
+                        }
+                        if let Some((pool_id, _)) = pool_names.first() {
+                            if ui.button("Add Pool").clicked() {
+                                loot_pool
+                                    .members
+                                    .push(LootPoolMember::new(LootPoolEntry::Pool(*pool_id), 1));
+                            }
+                        }
+                        if ui.button("Add Nothing").clicked() {
+                            loot_pool
+                                .members
+                                .push(LootPoolMember::new(LootPoolEntry::Nothing, 1));
+                        }
+                    });
 
-                    ui.separator();
+                    let mut remove_index: Option<usize> = None;
 
-                    {
+                    for (index, member) in loot_pool.members.iter_mut().enumerate() {
                         ui.horizontal(|ui| {
-                            ui.label("Members");
-                            if ui.button("Add").clicked() {
-                                let next_id = loot_pool.members.len() as u32;
-                                loot_pool.members.insert(
-                                    next_id as usize,
-                                    LootPoolMember {
-                                        item_def: Arc::new(Mutex::new(ItemDefinition {
-                                            id: 0,
-                                            classification: ItemClassification::Invalid,
-                                            affix_pools: vec![],
-                                            fixed_affixes: vec![],
-                                            name: "".to_string(),
-                                        })),
-                                        weight: 0,
-                                    },
-                                );
-                            }
-                        });
-
-                        let mut remove_id: Option<u64> = None;
-                        let mut replace_ids: Option<(u64, u64)> = None;
-
-                        for member in &loot_pool.members {
-                            let (mut member_id, member_name) = {
-                                let member_mutex_guard = member.item_def.lock().unwrap();
-                                (member_mutex_guard.id, member_mutex_guard.name.clone())
-                            };
-
-                            ui.horizontal(|ui| {
-                                egui::ComboBox::from_id_source(format!(
-                                    "LootPoolMember{:?}",
-                                    member_id
-                                ))
-                                .selected_text(format!("{}", member_name))
-                                .show_ui(ui, |ui| {
-                                    let selected_member_id = &mut member_id;
-                                    let mut item_defs = app.item_db.lock().unwrap().definitions();
-                                    item_defs.retain(|def| {
-                                        let item_id = def.lock().unwrap().id;
-                                        let is_member_already =
-                                            loot_pool.members.iter().any(|member| {
-                                                member.item_def.lock().unwrap().id == item_id
-                                            });
-                                        let is_selected_item =
-                                            item_id as u64 == *selected_member_id as u64;
-
-                                        !(is_member_already || is_selected_item)
+                            match &mut member.entry {
+                                LootPoolEntry::Item(item_def) => {
+                                    let mut item_id = item_def.id;
+                                    egui::ComboBox::from_id_source(format!(
+                                        "LootPoolMemberItem{index}"
+                                    ))
+                                    .selected_text(app.localization.text(&item_def.name))
+                                    .show_ui(ui, |ui| {
+                                        for def in &item_defs {
+                                            ui.selectable_value(
+                                                &mut item_id,
+                                                def.id,
+                                                app.localization.text(&def.name),
+                                            );
+                                        }
                                     });
 
-                                    for item in item_defs {
-                                        let item_def = item.lock().unwrap();
-                                        ui.selectable_value(
-                                            selected_member_id,
-                                            item_def.id,
-                                            item_def.name.as_str(),
-                                        );
+                                    if item_id != item_def.id {
+                                        *item_def = app.item_db.definition(item_id).unwrap().into();
                                     }
-                                });
-
-                                if ui.button("Remove").clicked() {
-                                    remove_id = Some(loot_pool.members.len() as u64 - 1);
                                 }
-
-                                let old_id = member.item_def.lock().unwrap().id;
-                                if old_id != member_id {
-                                    replace_ids = Some((old_id, member_id));
+                                LootPoolEntry::Pool(pool_id) => {
+                                    let pool_name = pool_names
+                                        .iter()
+                                        .find(|(id, _)| id == pool_id)
+                                        .map_or(String::from("Missing pool"), |(_, name)| {
+                                            name.clone()
+                                        });
+                                    egui::ComboBox::from_id_source(format!(
+                                        "LootPoolMemberPool{index}"
+                                    ))
+                                    .selected_text(format!("Pool: {pool_name}"))
+                                    .show_ui(ui, |ui| {
+                                        for (id, name) in &pool_names {
+                                            ui.selectable_value(pool_id, *id, name);
+                                        }
+                                    });
                                 }
-                            });
-                        }
+                                LootPoolEntry::Nothing => {
+                                    ui.label("Nothing");
+                                }
+                            }
 
-                        if let Some(id) = remove_id {
-                            loot_pool
-                                .members
-                                .retain(|member| member.item_def.lock().unwrap().id != id);
-                        }
+                            ui.checkbox(&mut member.guaranteed, "Guaranteed");
+                            if !member.guaranteed {
+                                ui.label("Weight");
+                                ui.add(egui::DragValue::new(&mut member.weight));
+                            }
 
-                        if let Some((old_id, new_id)) = replace_ids {
-                            let item_idx = loot_pool
-                                .members
-                                .binary_search_by(|probe| {
-                                    probe.item_def.lock().unwrap().id.cmp(&old_id)
-                                })
-                                .unwrap();
-                            loot_pool.members.remove(item_idx);
-                            let new_def = app.item_db.lock().unwrap().definition(new_id).unwrap();
-                            loot_pool.members.push(LootPoolMember {
-                                item_def: new_def,
-                                weight: 1,
-                            });
-                        }
+                            let mut min_count = *member.count_range().start();
+                            let mut max_count = *member.count_range().end();
+                            ui.label("Count");
+                            ui.add(egui::DragValue::new(&mut min_count));
+                            ui.label("to");
+                            ui.add(
+                                egui::DragValue::new(&mut max_count).clamp_range(min_count..=100),
+                            );
+                            member.min_count = (min_count != 1).then_some(min_count);
+                            member.max_count = (max_count != 1).then_some(max_count);
+
+                            if ui.button("Remove").clicked() {
+                                remove_index = Some(index);
+                            }
+                        });
+                    }
+
+                    if let Some(index) = remove_index {
+                        loot_pool.members.remove(index);
                     }
                 });
             });
@@ -193,6 +191,7 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
             .column(Column::initial(60.0).at_least(40.0))
             .column(Column::initial(60.0).at_least(40.0))
             .column(Column::initial(60.0).at_least(40.0))
+            .column(Column::initial(60.0).at_least(40.0))
             .column(Column::remainder().at_least(60.0))
             .resizable(true)
             .header(20.0, |mut header| {
@@ -203,22 +202,19 @@ pub fn draw(ctx: &egui::Context, app: &mut DataEditorApp) {
                 }
             })
             .body(|body| {
-                let mut loot_pools = {
-                    let loot_pool_db = app.loot_pool_db.lock().unwrap();
-                    loot_pool_db.definitions()
-                };
+                let mut loot_pools = app.loot_pool_db.definitions();
 
                 // ZJ-TODO: other sorting/filtering methods? would be nice to sort/filter other columns, not just ID
-                loot_pools.sort_by(|a, b| a.lock().unwrap().id.cmp(&b.lock().unwrap().id));
+                loot_pools.sort_by_key(|definition| definition.id);
 
                 body.rows(30., loot_pools.len(), |mut row| {
-                    let loot_pool = loot_pools[row.index()].lock().unwrap();
+                    let loot_pool = &loot_pools[row.index()];
 
                     if let Some(selected_definition_id) = app.selected_definition_id {
                         row.set_selected(selected_definition_id == loot_pool.id as u64);
                     }
 
-                    app.populate_row_for_definition(&*loot_pool, &mut row);
+                    app.populate_row_for_definition(&**loot_pool, &mut row);
                 });
             });
     });
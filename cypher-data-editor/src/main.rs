@@ -4,24 +4,31 @@ mod table_display;
 
 use std::collections::BTreeMap;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use cypher_core::affix::database::AffixDefinitionDatabase;
 use cypher_core::affix::definition::{
     AffixDefinition, AffixDefinitionStat, AffixDefinitionTier, AffixDefinitionValue,
 };
 use cypher_core::affix::placement::AffixPlacement;
+use cypher_core::affix::tag::{AffixTag, AffixTagRules};
 use cypher_core::affix_pool::database::AffixPoolDefinitionDatabase;
 use cypher_core::affix_pool::definition::AffixPoolDefinition;
 use cypher_core::affix_pool::member::AffixPoolMember;
-use cypher_core::data::{DataDefinition, DataDefinitionDatabase};
-use cypher_core::stat::Stat;
+use cypher_core::data::{
+    load_database, DataDefinition, DataDefinitionDatabase, DataLoadError, DatabaseBuilder,
+};
+use cypher_core::localization::Localization;
+use cypher_core::stat::StatModifierKind;
+use cypher_core::stat_registry::database::StatRegistry;
+use cypher_core::stat_text::StatTextValue;
 use cypher_item::item::classification::{ItemClassification, ItemEquipSlot};
 use cypher_item::item::database::ItemDefinitionDatabase;
 
 use cypher_item::item::definition::ItemDefinition;
 use cypher_item::loot_pool::database::LootPoolDefinitionDatabase;
 use cypher_item::loot_pool::definition::LootPoolDefinition;
+use cypher_item::validation::{validate_databases, validate_localization, ValidationReport};
 use eframe::egui;
 use egui::{Color32, Ui, WidgetText};
 use egui_extras::{Column, TableBuilder};
@@ -33,7 +40,7 @@ fn get_affix_db_path() -> PathBuf {
     let mut path = std::env::current_dir().unwrap();
     path.push("cypher-core");
     path.push("data");
-    path.push("affix.json");
+    path.push(AffixDefinitionDatabase::FILE_NAME);
     path
 }
 
@@ -41,7 +48,7 @@ fn get_affix_pool_db_path() -> PathBuf {
     let mut path = std::env::current_dir().unwrap();
     path.push("cypher-core");
     path.push("data");
-    path.push("affix_pool.json");
+    path.push(AffixPoolDefinitionDatabase::FILE_NAME);
     path
 }
 
@@ -49,7 +56,23 @@ fn get_item_db_path() -> PathBuf {
     let mut path = std::env::current_dir().unwrap();
     path.push("cypher-item");
     path.push("data");
-    path.push("item.json");
+    path.push(ItemDefinitionDatabase::FILE_NAME);
+    path
+}
+
+fn get_stat_registry_path() -> PathBuf {
+    let mut path = std::env::current_dir().unwrap();
+    path.push("cypher-core");
+    path.push("data");
+    path.push(StatRegistry::FILE_NAME);
+    path
+}
+
+/// String tables are stored alongside the core data files.
+fn get_localization_data_path() -> PathBuf {
+    let mut path = std::env::current_dir().unwrap();
+    path.push("cypher-core");
+    path.push("data");
     path
 }
 
@@ -57,7 +80,7 @@ fn get_loot_pool_db_path() -> PathBuf {
     let mut path = std::env::current_dir().unwrap();
     path.push("cypher-item");
     path.push("data");
-    path.push("loot_pool.json");
+    path.push(LootPoolDefinitionDatabase::FILE_NAME);
     path
 }
 
@@ -77,50 +100,101 @@ impl Default for SelectedEditor {
 }
 
 struct DataEditorApp {
-    affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
-    affix_pool_db: Arc<Mutex<AffixPoolDefinitionDatabase>>,
-    item_db: Arc<Mutex<ItemDefinitionDatabase>>,
-    loot_pool_db: Arc<Mutex<LootPoolDefinitionDatabase>>,
+    /// Working copies of each database, only written back to disk when saved.
+    affix_db: DatabaseBuilder<AffixDefinition>,
+    affix_pool_db: DatabaseBuilder<AffixPoolDefinition>,
+    item_db: DatabaseBuilder<ItemDefinition>,
+    loot_pool_db: DatabaseBuilder<LootPoolDefinition>,
+
+    /// Stats that affixes can modify. Not editable here, so it isn't written back on save.
+    stat_registry: Arc<StatRegistry>,
+
+    /// Names are edited as localization keys, but displayed through this.
+    localization: Localization,
 
     selected_editor: SelectedEditor,
     selected_definition_id: Option<u64>,
+
+    /// Result of the last validation run, shown until closed.
+    validation_report: Option<ValidationReport>,
 }
 
 impl DataEditorApp {
-    fn new() -> DataEditorApp {
+    fn new() -> Result<DataEditorApp, Vec<DataLoadError>> {
+        let mut errors = vec![];
+
         let affix_db_path = get_affix_db_path();
-        let affix_db = Arc::new(Mutex::new(AffixDefinitionDatabase::load_from(
+        let affix_db = load_database::<_, AffixDefinitionDatabase>(
             affix_db_path.to_str().unwrap(),
-            &(),
-        )));
+            Some(()),
+            &mut errors,
+        );
 
         let affix_pool_db_path = get_affix_pool_db_path();
-        let affix_pool_db = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::load_from(
+        let affix_pool_db = load_database::<_, AffixPoolDefinitionDatabase>(
             affix_pool_db_path.to_str().unwrap(),
-            &affix_db.clone(),
-        )));
+            affix_db.clone(),
+            &mut errors,
+        );
 
         let item_db_path = get_item_db_path();
-        let item_db = Arc::new(Mutex::new(ItemDefinitionDatabase::load_from(
+        let item_db = load_database::<_, ItemDefinitionDatabase>(
             item_db_path.to_str().unwrap(),
-            &(affix_db.clone(), affix_pool_db.clone()),
-        )));
+            affix_db.clone().zip(affix_pool_db.clone()),
+            &mut errors,
+        );
 
         let loot_pool_db_path = get_loot_pool_db_path();
-        let loot_pool_db = Arc::new(Mutex::new(LootPoolDefinitionDatabase::load_from(
+        let loot_pool_db = load_database::<_, LootPoolDefinitionDatabase>(
             loot_pool_db_path.to_str().unwrap(),
-            &item_db.clone(),
-        )));
-
-        DataEditorApp {
+            item_db.clone(),
+            &mut errors,
+        );
+
+        let stat_registry_path = get_stat_registry_path();
+        let stat_registry = load_database::<_, StatRegistry>(
+            stat_registry_path.to_str().unwrap(),
+            Some(()),
+            &mut errors,
+        );
+
+        let localization = Localization::load_from_layers(&[get_localization_data_path()])
+            .map_err(|err| errors.push(err))
+            .ok();
+
+        let (
+            Some(affix_db),
+            Some(affix_pool_db),
+            Some(item_db),
+            Some(loot_pool_db),
+            Some(stat_registry),
+            Some(localization),
+        ) = (
             affix_db,
             affix_pool_db,
             item_db,
             loot_pool_db,
+            stat_registry,
+            localization,
+        )
+        else {
+            return Err(errors);
+        };
+
+        Ok(DataEditorApp {
+            affix_db: affix_db.builder(),
+            affix_pool_db: affix_pool_db.builder(),
+            item_db: item_db.builder(),
+            loot_pool_db: loot_pool_db.builder(),
+
+            stat_registry,
+            localization,
 
             selected_editor: SelectedEditor::NoEditor,
             selected_definition_id: None,
-        }
+
+            validation_report: None,
+        })
     }
 
     /// Loads data files from the repository.
@@ -128,26 +202,33 @@ impl DataEditorApp {
         println!("Loading data files");
     }
 
-    /// Writes data files back to the repository.
+    /// Writes data files back to the repository, upgrading any older files to the latest schema version.
     fn write_data(&mut self) {
         println!("Writing data files");
 
-        self.affix_db
-            .lock()
-            .unwrap()
-            .write_to(get_affix_db_path().to_str().unwrap());
-        self.affix_pool_db
-            .lock()
-            .unwrap()
-            .write_to(get_affix_pool_db_path().to_str().unwrap());
-        self.item_db
-            .lock()
-            .unwrap()
-            .write_to(get_item_db_path().to_str().unwrap());
-        self.loot_pool_db
-            .lock()
-            .unwrap()
-            .write_to(get_loot_pool_db_path().to_str().unwrap());
+        let (affix_db, affix_pool_db, item_db, loot_pool_db) = self.build_databases();
+
+        affix_db.write_to(get_affix_db_path().to_str().unwrap());
+        affix_pool_db.write_to(get_affix_pool_db_path().to_str().unwrap());
+        item_db.write_to(get_item_db_path().to_str().unwrap());
+        loot_pool_db.write_to(get_loot_pool_db_path().to_str().unwrap());
+    }
+
+    /// Builds databases from the current working copies.
+    fn build_databases(
+        &self,
+    ) -> (
+        AffixDefinitionDatabase,
+        AffixPoolDefinitionDatabase,
+        ItemDefinitionDatabase,
+        LootPoolDefinitionDatabase,
+    ) {
+        (
+            self.affix_db.clone().build(),
+            self.affix_pool_db.clone().build(),
+            self.item_db.clone().build(),
+            self.loot_pool_db.clone().build(),
+        )
     }
 
     fn draw_file_menu_options(&mut self, ui: &mut Ui) {
@@ -160,13 +241,63 @@ impl DataEditorApp {
             self.write_data();
             ui.close_menu();
         };
+
+        if ui.button("Validate").clicked() {
+            self.validate_data();
+            ui.close_menu();
+        };
+    }
+
+    /// Checks references between all databases and string tables, storing the report to be shown.
+    fn validate_data(&mut self) {
+        let (affix_db, affix_pool_db, item_db, loot_pool_db) = self.build_databases();
+
+        let mut report = validate_databases(&affix_db, &affix_pool_db, &item_db, &loot_pool_db);
+        report.extend(validate_localization(
+            &self.localization,
+            &affix_db,
+            &affix_pool_db,
+            &item_db,
+            &self.stat_registry,
+        ));
+
+        self.validation_report = Some(report);
+    }
+
+    fn draw_validation_report(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.validation_report else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Validation")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if report.errors.is_empty() && report.warnings.is_empty() {
+                    ui.label("No issues found.");
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for error in &report.errors {
+                        ui.label(WidgetText::from(error.to_string()).color(Color32::RED));
+                    }
+
+                    for warning in &report.warnings {
+                        ui.label(WidgetText::from(warning.to_string()).color(Color32::YELLOW));
+                    }
+                });
+            });
+
+        if !open {
+            self.validation_report = None;
+        }
     }
 
     fn populate_row_for_definition<T>(&mut self, def: &T, row: &mut egui_extras::TableRow)
     where
         T: TableDisplay + DataDefinition,
     {
-        for text_value in def.data_row_values() {
+        for text_value in def.data_row_values(&self.localization) {
             let (_, resp) = row.col(|ui| {
                 if def.validate() {
                     let label = egui::Label::new(text_value).selectable(false);
@@ -189,23 +320,21 @@ impl DataEditorApp {
             if ui.button("Add").clicked() {
                 self.invalidate_selections();
 
-                let mut affix_db = self.affix_db.lock().unwrap();
-                let affixes = affix_db.definitions();
-                let next_id = affixes.iter().fold(0, |acc, next| {
-                    if acc > next.lock().unwrap().id {
-                        acc
-                    } else {
-                        next.lock().unwrap().id
-                    }
-                }) + 1;
+                let affixes = self.affix_db.definitions();
+                let next_id = affixes
+                    .iter()
+                    .fold(0, |acc, next| if acc > next.id { acc } else { next.id })
+                    + 1;
                 let new_affix = AffixDefinition {
                     id: next_id,
                     placement: AffixPlacement::Invalid,
                     tiers: BTreeMap::new(),
                     name: String::new(),
+                    tags: vec![],
+                    mod_group: None,
                 };
 
-                affix_db.add_definition(new_affix);
+                self.affix_db.add_definition(new_affix);
             }
         });
 
@@ -219,23 +348,25 @@ impl DataEditorApp {
                     }
                     ui.separator();
 
+                    let mut stat_ids = self
+                        .stat_registry
+                        .definitions()
+                        .iter()
+                        .map(|stat| stat.id)
+                        .collect::<Vec<_>>();
+                    stat_ids.sort();
+
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        let affix_db = self.affix_db.lock().unwrap();
-                        let mut affixes = affix_db.definitions();
-                        let mut affix = affixes
-                            .iter_mut()
-                            .find(|def| {
-                                def.lock().unwrap().id as u64
-                                    == self.selected_definition_id.unwrap()
-                            })
-                            .unwrap()
-                            .lock()
+                        let affix = self
+                            .affix_db
+                            .definition_mut(self.selected_definition_id.unwrap() as u32)
                             .unwrap();
 
                         ui.label(format!("Id: {}", affix.id));
                         ui.horizontal(|ui| {
                             ui.label("Name");
                             ui.text_edit_singleline(&mut affix.name);
+                            ui.label(self.localization.text(&affix.name));
                         });
 
                         ui.horizontal(|ui| {
@@ -258,6 +389,20 @@ impl DataEditorApp {
                                 });
                         });
 
+                        draw_tags_editor(ui, "Tags", &mut affix.tags);
+
+                        ui.horizontal(|ui| {
+                            ui.label("Mod Group");
+                            let mut mod_group = affix.mod_group.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut mod_group).changed() {
+                                affix.mod_group = if mod_group.is_empty() {
+                                    None
+                                } else {
+                                    Some(mod_group)
+                                };
+                            }
+                        });
+
                         ui.separator();
                         ui.label("Tiers");
 
@@ -271,6 +416,7 @@ impl DataEditorApp {
                                         stats: Vec::new(),
                                         item_level_req: None,
                                         precision_places: None,
+                                        weight: None,
                                     },
                                 );
                             }
@@ -286,9 +432,13 @@ impl DataEditorApp {
                                 // TODO: extract to draw_affix_definition_tier
                                 ui.horizontal(|ui| {
                                     if ui.button("Add Stat").clicked() {
+                                        let first_stat = stat_ids.first().copied().unwrap_or(1);
                                         tier_def.stats.push(AffixDefinitionStat {
-                                            stat: Stat::Resolve, // TODO: invalid?
+                                            stat: first_stat, // TODO: invalid?
+                                            kind: StatModifierKind::Flat,
                                             value: AffixDefinitionValue::Range(0., 0.),
+                                            condition: None,
+                                            template: None,
                                         });
                                     }
 
@@ -302,13 +452,33 @@ impl DataEditorApp {
                                         "Stat_{:?}_{}",
                                         selected_stat, stat.value
                                     ))
-                                    .selected_text(format!("{:?}", selected_stat))
+                                    .selected_text(
+                                        self.localization
+                                            .stat_name(&self.stat_registry, *selected_stat),
+                                    )
                                     .show_ui(ui, |ui| {
-                                        for stat_variant in Stat::iter() {
+                                        for stat_id in &stat_ids {
                                             ui.selectable_value(
                                                 selected_stat,
-                                                stat_variant,
-                                                format!("{:?}", stat_variant),
+                                                *stat_id,
+                                                self.localization
+                                                    .stat_name(&self.stat_registry, *stat_id),
+                                            );
+                                        }
+                                    });
+
+                                    let selected_kind = &mut stat.kind;
+                                    egui::ComboBox::from_id_source(format!(
+                                        "StatKind_{:?}_{}",
+                                        stat.stat, stat.value
+                                    ))
+                                    .selected_text(format!("{:?}", selected_kind))
+                                    .show_ui(ui, |ui| {
+                                        for kind_variant in StatModifierKind::iter() {
+                                            ui.selectable_value(
+                                                selected_kind,
+                                                kind_variant,
+                                                format!("{:?}", kind_variant),
                                             );
                                         }
                                     });
@@ -354,6 +524,21 @@ impl DataEditorApp {
                                             }
                                         };
                                     });
+
+                                    let preview_value = match stat.value {
+                                        AffixDefinitionValue::Exact(val) => {
+                                            StatTextValue::Rolled(val, None)
+                                        }
+                                        AffixDefinitionValue::Range(lower, upper) => {
+                                            StatTextValue::Range(lower, upper)
+                                        }
+                                    };
+                                    ui.label(self.localization.affix_stat_text(
+                                        &self.stat_registry,
+                                        stat,
+                                        preview_value,
+                                        tier_def.precision_places,
+                                    ));
                                 }
                                 ui.horizontal(|ui| {
                                     let mut enabled = tier_def.item_level_req.is_some();
@@ -372,6 +557,19 @@ impl DataEditorApp {
                                         tier_def.item_level_req = None;
                                     }
                                 });
+                                ui.horizontal(|ui| {
+                                    let mut enabled = tier_def.weight.is_some();
+                                    ui.checkbox(&mut enabled, "Weight");
+                                    if enabled {
+                                        let mut val = tier_def
+                                            .weight
+                                            .unwrap_or(AffixDefinitionTier::DEFAULT_WEIGHT);
+                                        ui.add(egui::Slider::new(&mut val, 0..=1000));
+                                        tier_def.weight = Some(val);
+                                    } else if tier_def.weight.is_some() {
+                                        tier_def.weight = None;
+                                    }
+                                });
                                 ui.horizontal(|ui| {
                                     let mut enabled = tier_def.precision_places.is_some();
                                     ui.checkbox(&mut enabled, "Float Precision");
@@ -399,6 +597,8 @@ impl DataEditorApp {
                 .column(Column::initial(60.0).at_least(40.0))
                 .column(Column::initial(60.0).at_least(40.0))
                 .column(Column::initial(60.0).at_least(40.0))
+                .column(Column::initial(60.0).at_least(40.0))
+                .column(Column::initial(100.0).at_least(40.0))
                 .column(Column::remainder().at_least(60.0))
                 .resizable(true)
                 .header(20.0, |mut header| {
@@ -414,24 +614,27 @@ impl DataEditorApp {
                     header.col(|ui| {
                         ui.heading("Tiers");
                     });
+                    header.col(|ui| {
+                        ui.heading("Mod Group");
+                    });
+                    header.col(|ui| {
+                        ui.heading("Tags");
+                    });
                 })
                 .body(|body| {
-                    let mut affixes = {
-                        let affix_db = self.affix_db.lock().unwrap();
-                        affix_db.definitions()
-                    };
+                    let mut affixes = self.affix_db.definitions();
 
                     // ZJ-TODO: other sorting/filtering methods? would be nice to sort/filter other columns, not just ID
-                    affixes.sort_by(|a, b| a.lock().unwrap().id.cmp(&b.lock().unwrap().id));
+                    affixes.sort_by_key(|definition| definition.id);
 
                     body.rows(30., affixes.len(), |mut row| {
-                        let affix = affixes[row.index()].lock().unwrap();
+                        let affix = &affixes[row.index()];
 
                         if let Some(selected_definition_id) = self.selected_definition_id {
                             row.set_selected(selected_definition_id == affix.id as u64);
                         }
 
-                        self.populate_row_for_definition(&*affix, &mut row);
+                        self.populate_row_for_definition(&**affix, &mut row);
                     });
                 });
         });
@@ -442,16 +645,16 @@ impl DataEditorApp {
             if ui.button("Add").clicked() {
                 self.invalidate_selections();
 
-                let mut affix_pool_db = self.affix_pool_db.lock().unwrap();
-                let affix_pools = affix_pool_db.definitions();
-                let next_id = affix_pools.last().unwrap().lock().unwrap().id + 1;
+                let affix_pools = self.affix_pool_db.definitions();
+                let next_id = affix_pools.iter().map(|def| def.id).max().unwrap_or(0) + 1;
                 let new_affix_pool = AffixPoolDefinition {
                     id: next_id,
                     members: vec![],
                     name: String::default(),
+                    tag_rules: AffixTagRules::default(),
                 };
 
-                affix_pool_db.add_definition(new_affix_pool);
+                self.affix_pool_db.add_definition(new_affix_pool);
             }
         });
 
@@ -468,15 +671,9 @@ impl DataEditorApp {
                             }
                             if ui.button("Delete").clicked() {
                                 {
-                                    // ZJ-TODO: deletions
-                                    let affix_pool_db = self.affix_pool_db.lock().unwrap();
-                                    let mut affix_pools = affix_pool_db.definitions();
-                                    if let Ok(idx) = affix_pools.binary_search_by(|def| {
-                                        (def.lock().unwrap().id as u64)
-                                            .cmp(&self.selected_definition_id.unwrap())
-                                    }) {
-                                        affix_pools.remove(idx);
-                                    }
+                                    self.affix_pool_db.remove_definition(
+                                        self.selected_definition_id.unwrap() as u32,
+                                    );
 
                                     should_close_sidebar = true;
                                 }
@@ -493,45 +690,48 @@ impl DataEditorApp {
                     ui.separator();
 
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        let affix_pool_db = self.affix_pool_db.lock().unwrap();
-                        let affix_pools = affix_pool_db.definitions();
-                        let mut affix_pool = affix_pools
-                            .iter()
-                            .find(|def| {
-                                def.lock().unwrap().id as u64
-                                    == self.selected_definition_id.unwrap()
-                            })
-                            .unwrap()
-                            .lock()
+                        let affix_pool = self
+                            .affix_pool_db
+                            .definition_mut(self.selected_definition_id.unwrap() as u32)
                             .unwrap();
 
                         ui.label(format!("Id: {}", affix_pool.id));
                         ui.horizontal(|ui| {
                             ui.label("Name");
                             ui.text_edit_singleline(&mut affix_pool.name);
+                            ui.label(self.localization.text(&affix_pool.name));
                         });
 
+                        ui.separator();
+                        ui.label("Tag Rules");
+                        draw_tags_editor(ui, "Required", &mut affix_pool.tag_rules.required);
+                        draw_tags_editor(ui, "Forbidden", &mut affix_pool.tag_rules.forbidden);
+                        for (tag, factor) in &mut affix_pool.tag_rules.boosted {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Boost {tag}"));
+                                ui.add(egui::Slider::new(factor, 1..=10));
+                            });
+                        }
+
                         ui.separator();
                         ui.label("Members");
                         if ui.button("Add").clicked() {
                             let definition = AffixPoolMember {
                                 affix_def: self
                                     .affix_db
-                                    .lock()
-                                    .unwrap()
                                     .definitions()
                                     .first()
                                     .unwrap()
-                                    .to_owned(),
+                                    .to_owned()
+                                    .into(),
                                 weight: 0,
                             };
                             affix_pool.members.push(definition);
                         }
 
                         for member in &mut affix_pool.members {
-                            let member_def = member.affix_def.lock().unwrap();
                             ui.horizontal(|ui| {
-                                ui.label(format!("{}", member_def.name));
+                                ui.label(self.localization.text(&member.affix_def.name));
                                 ui.horizontal(|ui| {
                                     ui.label("Weight");
                                     ui.add(egui::DragValue::new(&mut member.weight));
@@ -563,19 +763,17 @@ impl DataEditorApp {
                     });
                 })
                 .body(|body| {
-                    let affix_pools = {
-                        let affix_pool_db = self.affix_pool_db.lock().unwrap();
-                        affix_pool_db.definitions()
-                    };
+                    let mut affix_pools = self.affix_pool_db.definitions();
+                    affix_pools.sort_by_key(|definition| definition.id);
 
                     body.rows(30., affix_pools.len(), |mut row| {
-                        let affix_pool = affix_pools[row.index()].lock().unwrap();
+                        let affix_pool = &affix_pools[row.index()];
 
                         if let Some(selected_definition_id) = self.selected_definition_id {
                             row.set_selected(selected_definition_id == affix_pool.id as u64);
                         }
 
-                        self.populate_row_for_definition(&*affix_pool, &mut row)
+                        self.populate_row_for_definition(&**affix_pool, &mut row)
                     });
                 });
         });
@@ -633,12 +831,38 @@ impl eframe::App for DataEditorApp {
             SelectedEditor::LootPool => self.draw_loot_pool_editor(ctx, ui),
             _ => {}
         });
+
+        self.draw_validation_report(ctx);
     }
 }
 
+/// Edits a list of tags as comma-separated text.
+fn draw_tags_editor(ui: &mut Ui, label: &str, tags: &mut Vec<AffixTag>) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut text = tags.join(", ");
+        if ui.text_edit_singleline(&mut text).changed() {
+            *tags = if text.trim().is_empty() {
+                vec![]
+            } else {
+                text.split(',').map(|tag| tag.trim().to_owned()).collect()
+            };
+        }
+    });
+}
+
 fn main() {
     let options = eframe::NativeOptions::default();
-    let app = DataEditorApp::new();
+    let app = match DataEditorApp::new() {
+        Ok(app) => app,
+        Err(errors) => {
+            eprintln!("Failed to load game data:");
+            for error in errors {
+                eprintln!("\t{error}");
+            }
+            std::process::exit(1);
+        }
+    };
     app.load_data();
 
     eframe::run_native("Cypher Data Editor", options, Box::new(|_cc| Box::new(app)));
@@ -1,5 +1,6 @@
 use cypher_core::affix::definition::AffixDefinition;
 use cypher_core::affix_pool::definition::AffixPoolDefinition;
+use cypher_core::localization::Localization;
 use cypher_item::item::definition::ItemDefinition;
 
 pub trait TableDisplay {
@@ -9,20 +10,23 @@ pub trait TableDisplay {
 
     /// What values from this type should be displayed in the data row?
     /// eg, if header row was ID, Name: 1, "Test Item"
-    fn data_row_values(&self) -> Vec<String>;
+    /// Names are shown through `localization`, rather than as their keys.
+    fn data_row_values(&self, localization: &Localization) -> Vec<String>;
 }
 
 impl TableDisplay for AffixDefinition {
     fn header_row_values() -> Vec<&'static str> {
-        vec!["Id", "Name", "Placement", "Tiers"]
+        vec!["Id", "Name", "Placement", "Tiers", "Mod Group", "Tags"]
     }
 
-    fn data_row_values(&self) -> Vec<String> {
+    fn data_row_values(&self, localization: &Localization) -> Vec<String> {
         vec![
             self.id.to_string(),
-            self.name.clone(),
+            localization.text(&self.name).to_owned(),
             self.placement.to_string(),
             self.tiers.len().to_string(),
+            self.mod_group.clone().unwrap_or_default(),
+            self.tags.join(", "),
         ]
     }
 }
@@ -32,10 +36,10 @@ impl TableDisplay for AffixPoolDefinition {
         vec!["Id", "Name", "Members"]
     }
 
-    fn data_row_values(&self) -> Vec<String> {
+    fn data_row_values(&self, localization: &Localization) -> Vec<String> {
         vec![
             self.id.to_string(),
-            self.name.clone(),
+            localization.text(&self.name).to_owned(),
             self.members.len().to_string(),
         ]
     }
@@ -46,10 +50,10 @@ impl TableDisplay for ItemDefinition {
         vec!["Id", "Name", "Classification", "Pools"]
     }
 
-    fn data_row_values(&self) -> Vec<String> {
+    fn data_row_values(&self, localization: &Localization) -> Vec<String> {
         vec![
             self.id.to_string(),
-            self.name.clone(),
+            localization.text(&self.name).to_owned(),
             self.classification.to_string(),
             self.affix_pools.len().to_string(),
         ]
@@ -1,71 +1,512 @@
 use std::{
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use bevy::prelude::Resource;
 use cypher_core::{
-    affix::database::AffixDefinitionDatabase, affix_pool::database::AffixPoolDefinitionDatabase,
-    data::DataDefinitionDatabase,
+    affix::database::AffixDefinitionDatabase,
+    affix_pool::database::AffixPoolDefinitionDatabase,
+    data::{load_database_layers, DataDefinitionDatabase, DataLoadError, DefinitionLayers},
+    localization::Localization,
+    stat_derivation::database::StatDerivationDatabase,
+    stat_registry::database::StatRegistry,
 };
 use cypher_item::{
-    item::database::ItemDefinitionDatabase, loot_pool::database::LootPoolDefinitionDatabase,
+    bundle::GameDataBundle,
+    enemy::database::EnemyDefinitionDatabase,
+    item::database::ItemDefinitionDatabase,
+    loot_pool::database::LootPoolDefinitionDatabase,
+    validation::{
+        validate_databases, validate_enemies, validate_localization, validate_stats,
+        ValidationReport,
+    },
 };
+use thiserror::Error;
 
 #[derive(Resource)]
 pub struct DataManager {
-    pub affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
-    pub affix_pool_db: Arc<Mutex<AffixPoolDefinitionDatabase>>,
-    pub item_db: Arc<Mutex<ItemDefinitionDatabase>>,
-    pub loot_pool_db: Arc<Mutex<LootPoolDefinitionDatabase>>,
+    pub affix_db: Arc<AffixDefinitionDatabase>,
+    pub affix_pool_db: Arc<AffixPoolDefinitionDatabase>,
+    pub item_db: Arc<ItemDefinitionDatabase>,
+    pub loot_pool_db: Arc<LootPoolDefinitionDatabase>,
+
+    /// Enemies that can be spawned, and the loot pools they drop from.
+    pub enemy_db: Arc<EnemyDefinitionDatabase>,
+
+    /// Stats that attributes grant, such as Health per point of Resolve.
+    pub stat_derivation_db: Arc<StatDerivationDatabase>,
+
+    /// Every stat, with the defaults and bounds their values are resolved with.
+    pub stat_registry: Arc<StatRegistry>,
+
+    /// String tables that definition names and stat text are displayed through.
+    pub localization: Localization,
+
+    /// Directories all databases were loaded from, and are reloaded from, in layer order.
+    /// The first is the base game data, and each later directory overlays those before it.
+    pub data_roots: Vec<PathBuf>,
+
+    /// Index into [DataManager::data_roots] that each definition came from, keyed by data file name.
+    /// Empty when loaded from a [GameDataBundle].
+    pub definition_layers: HashMap<&'static str, DefinitionLayers>,
+}
+
+/// Reasons a [DataManager::reload] can be rejected. The previously loaded data is kept in either case.
+#[derive(Debug, Error)]
+pub enum DataReloadError {
+    #[error("failed to load: {}", .0.iter().map(|err| err.to_string()).collect::<Vec<_>>().join("; "))]
+    Load(Vec<DataLoadError>),
+
+    #[error("failed validation:\n{0}")]
+    Validation(ValidationReport),
 }
 
 impl DataManager {
-    pub fn new(game_data_path: PathBuf) -> Self {
-        let mut affix_db_path = game_data_path.clone();
-        affix_db_path.push("affix.json");
-        let affix_db = Arc::new(Mutex::new(AffixDefinitionDatabase::load_from(
-            affix_db_path.to_str().unwrap(),
-            &(),
-        )));
-
-        let mut affix_pool_db_path = game_data_path.clone();
-        affix_pool_db_path.push("affix_pool.json");
-        let affix_pool_db = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::load_from(
-            affix_pool_db_path.to_str().unwrap(),
-            &affix_db,
-        )));
-
-        let mut item_db_path = game_data_path.clone();
-        item_db_path.push("item.json");
-        let item_db = Arc::new(Mutex::new(ItemDefinitionDatabase::load_from(
-            item_db_path.to_str().unwrap(),
-            &(affix_db.clone(), affix_pool_db.clone()),
-        )));
-
-        let mut loot_pool_db_path = game_data_path;
-        loot_pool_db_path.push("loot_pool.json");
-        let loot_pool_db = Arc::new(Mutex::new(LootPoolDefinitionDatabase::load_from(
-            loot_pool_db_path.to_str().unwrap(),
-            &item_db,
-        )));
-
-        DataManager {
+    /// Loads all game data from `game_data_path`.
+    /// On failure, returns every error found rather than stopping at the first broken file.
+    pub fn new(game_data_path: PathBuf) -> Result<Self, Vec<DataLoadError>> {
+        DataManager::with_layers(vec![game_data_path])
+    }
+
+    /// Loads all game data from each of `data_roots` in order, such as base game data, then balance overrides,
+    /// then mods. Later roots only need to contain the data files they change.
+    pub fn with_layers(data_roots: Vec<PathBuf>) -> Result<Self, Vec<DataLoadError>> {
+        let mut errors = vec![];
+        let mut definition_layers = HashMap::new();
+
+        let layer_paths = |file_name: &str| {
+            data_roots
+                .iter()
+                .map(|root| root.join(file_name).to_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let affix_db = load_database_layers::<_, AffixDefinitionDatabase>(
+            &layer_paths(AffixDefinitionDatabase::FILE_NAME),
+            Some(()),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(AffixDefinitionDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let affix_pool_db = load_database_layers::<_, AffixPoolDefinitionDatabase>(
+            &layer_paths(AffixPoolDefinitionDatabase::FILE_NAME),
+            affix_db.clone(),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(AffixPoolDefinitionDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let item_db = load_database_layers::<_, ItemDefinitionDatabase>(
+            &layer_paths(ItemDefinitionDatabase::FILE_NAME),
+            affix_db.clone().zip(affix_pool_db.clone()),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(ItemDefinitionDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let loot_pool_db = load_database_layers::<_, LootPoolDefinitionDatabase>(
+            &layer_paths(LootPoolDefinitionDatabase::FILE_NAME),
+            item_db.clone(),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(LootPoolDefinitionDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let enemy_db = load_database_layers::<_, EnemyDefinitionDatabase>(
+            &layer_paths(EnemyDefinitionDatabase::FILE_NAME),
+            loot_pool_db.clone(),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(EnemyDefinitionDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let stat_derivation_db = load_database_layers::<_, StatDerivationDatabase>(
+            &layer_paths(StatDerivationDatabase::FILE_NAME),
+            Some(()),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(StatDerivationDatabase::FILE_NAME, layers);
+            db
+        });
+
+        let stat_registry = load_database_layers::<_, StatRegistry>(
+            &layer_paths(StatRegistry::FILE_NAME),
+            Some(()),
+            &mut errors,
+        )
+        .map(|(db, layers)| {
+            definition_layers.insert(StatRegistry::FILE_NAME, layers);
+            db
+        });
+
+        let localization = Localization::load_from_layers(&data_roots)
+            .map_err(|err| errors.push(err))
+            .ok();
+
+        match (
             affix_db,
             affix_pool_db,
             item_db,
             loot_pool_db,
+            enemy_db,
+            stat_derivation_db,
+            stat_registry,
+            localization,
+        ) {
+            (
+                Some(affix_db),
+                Some(affix_pool_db),
+                Some(item_db),
+                Some(loot_pool_db),
+                Some(enemy_db),
+                Some(stat_derivation_db),
+                Some(stat_registry),
+                Some(localization),
+            ) if errors.is_empty() => Ok(DataManager {
+                affix_db,
+                affix_pool_db,
+                item_db,
+                loot_pool_db,
+                enemy_db,
+                stat_derivation_db,
+                stat_registry,
+                localization,
+                data_roots,
+                definition_layers,
+            }),
+            _ => Err(errors),
         }
     }
-}
 
-impl Default for DataManager {
-    fn default() -> DataManager {
+    /// Loads all game data from the [GameDataBundle] in `game_data_path`.
+    /// The bundle was validated when it was built, so it isn't validated again.
+    pub fn from_bundle(game_data_path: PathBuf) -> Result<Self, DataLoadError> {
+        let mut bundle_path = game_data_path.clone();
+        bundle_path.push(GameDataBundle::FILE_NAME);
+
+        let bundle = GameDataBundle::read_from(bundle_path.to_str().unwrap())?;
+        let (
+            affix_db,
+            affix_pool_db,
+            item_db,
+            loot_pool_db,
+            enemy_db,
+            stat_derivation_db,
+            stat_registry,
+        ) = bundle.databases()?;
+
+        // String tables aren't bundled, so they can be swapped without rebuilding
+        let localization = Localization::load_from_layers(std::slice::from_ref(&game_data_path))?;
+
+        Ok(DataManager {
+            affix_db,
+            affix_pool_db,
+            item_db,
+            loot_pool_db,
+            enemy_db,
+            stat_derivation_db,
+            stat_registry,
+            localization,
+            data_roots: vec![game_data_path],
+            definition_layers: HashMap::new(),
+        })
+    }
+
+    /// Loads all game data from `game_data_path`, preferring the [GameDataBundle] if one was built
+    /// and none of the data files next to it have changed since.
+    pub fn load(game_data_path: PathBuf) -> Result<Self, Vec<DataLoadError>> {
+        let mut bundle_path = game_data_path.clone();
+        bundle_path.push(GameDataBundle::FILE_NAME);
+
+        if bundle_path.exists() {
+            if GameDataBundle::is_current(&game_data_path) {
+                return DataManager::from_bundle(game_data_path).map_err(|err| vec![err]);
+            }
+
+            println!(
+                "Game data files changed since {} was built, loading them instead",
+                GameDataBundle::FILE_NAME
+            );
+        }
+
+        DataManager::new(game_data_path)
+    }
+
+    /// Hash of the loaded data in its bundled form. Matches [GameDataBundle::content_hash] for unmodified data.
+    pub fn content_hash(&self) -> u64 {
+        GameDataBundle::from_databases(
+            &self.affix_db,
+            &self.affix_pool_db,
+            &self.item_db,
+            &self.loot_pool_db,
+            &self.enemy_db,
+            &self.stat_derivation_db,
+            &self.stat_registry,
+        )
+        .content_hash
+    }
+
+    /// Data root that the definition `id` in `file_name` was last added or overridden in.
+    pub fn definition_origin(&self, file_name: &str, id: u64) -> Option<&Path> {
+        let layer = *self.definition_layers.get(file_name)?.get(&id)?;
+        Some(self.data_roots[layer].as_path())
+    }
+
+    /// Checks references between all loaded databases, and that every referenced string exists.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = validate_databases(
+            &self.affix_db,
+            &self.affix_pool_db,
+            &self.item_db,
+            &self.loot_pool_db,
+        );
+        report.extend(validate_localization(
+            &self.localization,
+            &self.affix_db,
+            &self.affix_pool_db,
+            &self.item_db,
+            &self.stat_registry,
+        ));
+        report.extend(validate_stats(
+            &self.stat_registry,
+            &self.affix_db,
+            &self.stat_derivation_db,
+        ));
+        report.extend(validate_enemies(
+            &self.enemy_db,
+            &self.stat_registry,
+            &self.localization,
+        ));
+
+        report
+    }
+
+    /// Reloads all databases from [DataManager::data_roots].
+    /// New data is only applied if every file loads and passes validation. Databases are swapped for the new
+    /// snapshots, so existing references (such as those held by dropped or equipped items) keep the data they
+    /// were created with, and systems see the new data the next time they read from the [DataManager].
+    pub fn reload(&mut self) -> Result<ValidationReport, DataReloadError> {
+        let staged =
+            DataManager::with_layers(self.data_roots.clone()).map_err(DataReloadError::Load)?;
+
+        let report = staged.validate();
+        if !report.is_valid() {
+            return Err(DataReloadError::Validation(report));
+        }
+
+        self.affix_db = staged.affix_db;
+        self.affix_pool_db = staged.affix_pool_db;
+        self.item_db = staged.item_db;
+        self.loot_pool_db = staged.loot_pool_db;
+        self.enemy_db = staged.enemy_db;
+        self.stat_derivation_db = staged.stat_derivation_db;
+        self.stat_registry = staged.stat_registry;
+        self.definition_layers = staged.definition_layers;
+
+        let locale = self.localization.locale().clone();
+        self.localization = staged.localization;
+        self.localization.set_locale(locale);
+
+        Ok(report)
+    }
+
+    /// Where game data is copied to by the cypher-game build script.
+    pub fn default_game_data_path() -> PathBuf {
         let mut base_path = std::env::current_dir().unwrap();
         base_path.push("cypher-game");
         base_path.push("assets");
         base_path.push("game_data");
+        base_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies the repository's data files into a fresh directory, so tests can modify them.
+    fn copy_game_data(dir_name: &str) -> PathBuf {
+        let mut game_data_path = std::env::temp_dir();
+        game_data_path.push(dir_name);
+        std::fs::create_dir_all(&game_data_path).unwrap();
+
+        for (crate_name, file_name) in [
+            ("cypher-core", AffixDefinitionDatabase::FILE_NAME),
+            ("cypher-core", AffixPoolDefinitionDatabase::FILE_NAME),
+            ("cypher-item", ItemDefinitionDatabase::FILE_NAME),
+            ("cypher-item", LootPoolDefinitionDatabase::FILE_NAME),
+            ("cypher-item", EnemyDefinitionDatabase::FILE_NAME),
+            ("cypher-core", StatDerivationDatabase::FILE_NAME),
+            ("cypher-core", StatRegistry::FILE_NAME),
+        ] {
+            let mut from_path = std::env::current_dir().unwrap();
+            from_path.push("..");
+            from_path.push(crate_name);
+            from_path.push("data");
+            from_path.push(file_name);
+
+            std::fs::copy(from_path, game_data_path.join(file_name)).unwrap();
+        }
+
+        let locale_path = game_data_path.join(Localization::DIRECTORY_NAME);
+        std::fs::create_dir_all(&locale_path).unwrap();
+
+        let mut from_path = std::env::current_dir().unwrap();
+        from_path.push("..");
+        from_path.push("cypher-core");
+        from_path.push("data");
+        from_path.push(Localization::DIRECTORY_NAME);
+        for entry in std::fs::read_dir(from_path).unwrap() {
+            let entry = entry.unwrap();
+            std::fs::copy(entry.path(), locale_path.join(entry.file_name())).unwrap();
+        }
+
+        game_data_path
+    }
+
+    #[test]
+    fn reload_swaps_in_new_snapshots() {
+        let game_data_path = copy_game_data("cypher_reload_snapshot");
+        let mut data_manager = DataManager::new(game_data_path.clone()).unwrap();
+
+        let previous_item = data_manager.item_db.definition(1).unwrap();
+
+        for path in [
+            game_data_path.join(ItemDefinitionDatabase::FILE_NAME),
+            game_data_path
+                .join(Localization::DIRECTORY_NAME)
+                .join("en.json"),
+        ] {
+            let file = std::fs::read_to_string(&path).unwrap();
+            std::fs::write(
+                &path,
+                file.replace("item.leather_boots", "item.reloaded_boots")
+                    .replace("Leather Boots", "Reloaded Boots"),
+            )
+            .unwrap();
+        }
+
+        data_manager.reload().unwrap();
+
+        assert_eq!(previous_item.name, "item.leather_boots");
+        assert_eq!(
+            data_manager.item_db.definition(1).unwrap().name,
+            "item.reloaded_boots"
+        );
+        assert_eq!(
+            data_manager.localization.text("item.reloaded_boots"),
+            "Reloaded Boots"
+        );
+    }
+
+    #[test]
+    fn failed_reload_keeps_previous_data() {
+        let game_data_path = copy_game_data("cypher_reload_rejected");
+        let mut data_manager = DataManager::new(game_data_path.clone()).unwrap();
+
+        std::fs::write(
+            game_data_path.join(LootPoolDefinitionDatabase::FILE_NAME),
+            r#"[{"id":1,"name":"Broken","members":[{"item_id":9999,"weight":1}]}]"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            data_manager.reload(),
+            Err(DataReloadError::Load(_))
+        ));
+
+        let loot_pool = data_manager.loot_pool_db.definition(1).unwrap();
+        assert_eq!(loot_pool.name, "Generic Pool");
+    }
+
+    #[test]
+    fn overlay_layers_override_and_record_origin() {
+        let game_data_path = copy_game_data("cypher_layer_base");
+
+        let mut overlay_path = std::env::temp_dir();
+        overlay_path.push("cypher_layer_overlay");
+        std::fs::create_dir_all(&overlay_path).unwrap();
+        std::fs::write(
+            overlay_path.join(ItemDefinitionDatabase::FILE_NAME),
+            r#"{"schema_version":1,"definitions":[{"id":1,"name":"item.balanced_boots"}]}"#,
+        )
+        .unwrap();
+
+        let overlay_locale_path = overlay_path.join(Localization::DIRECTORY_NAME);
+        std::fs::create_dir_all(&overlay_locale_path).unwrap();
+        std::fs::write(
+            overlay_locale_path.join("en.json"),
+            r#"{"item.balanced_boots":"Balanced Boots"}"#,
+        )
+        .unwrap();
+
+        let data_manager =
+            DataManager::with_layers(vec![game_data_path.clone(), overlay_path.clone()]).unwrap();
+        assert!(data_manager.validate().is_valid());
+
+        let item = data_manager.item_db.definition(1).unwrap();
+        assert_eq!(data_manager.localization.text(&item.name), "Balanced Boots");
+        assert!(!item.affix_pools.is_empty());
+
+        assert_eq!(
+            data_manager.definition_origin(ItemDefinitionDatabase::FILE_NAME, 1),
+            Some(overlay_path.as_path())
+        );
+        assert_eq!(
+            data_manager.definition_origin(ItemDefinitionDatabase::FILE_NAME, 2),
+            Some(game_data_path.as_path())
+        );
+    }
+
+    #[test]
+    fn load_skips_stale_bundle() {
+        let game_data_path = copy_game_data("cypher_stale_bundle");
+        let data_manager = DataManager::new(game_data_path.clone()).unwrap();
+        GameDataBundle::from_databases(
+            &data_manager.affix_db,
+            &data_manager.affix_pool_db,
+            &data_manager.item_db,
+            &data_manager.loot_pool_db,
+            &data_manager.enemy_db,
+            &data_manager.stat_derivation_db,
+            &data_manager.stat_registry,
+        )
+        .write_to(
+            game_data_path
+                .join(GameDataBundle::FILE_NAME)
+                .to_str()
+                .unwrap(),
+        );
+
+        // Only data loaded from files records which layer each definition came from
+        let loaded = DataManager::load(game_data_path.clone()).unwrap();
+        assert!(loaded.definition_layers.is_empty());
+
+        let item_path = game_data_path.join(ItemDefinitionDatabase::FILE_NAME);
+        let bundle_modified = std::fs::metadata(game_data_path.join(GameDataBundle::FILE_NAME))
+            .and_then(|metadata| metadata.modified())
+            .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&item_path)
+            .and_then(|file| file.set_modified(bundle_modified + std::time::Duration::from_secs(1)))
+            .unwrap();
 
-        DataManager::new(base_path)
+        let loaded = DataManager::load(game_data_path).unwrap();
+        assert!(!loaded.definition_layers.is_empty());
     }
 }
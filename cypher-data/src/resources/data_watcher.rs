@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::{
+    prelude::{Event, Resource},
+    time::{Timer, TimerMode},
+};
+use cypher_core::{
+    affix::database::AffixDefinitionDatabase, affix_pool::database::AffixPoolDefinitionDatabase,
+    data::DataDefinitionDatabase, localization::Localization,
+    stat_derivation::database::StatDerivationDatabase, stat_registry::database::StatRegistry,
+};
+use cypher_item::{
+    enemy::database::EnemyDefinitionDatabase, item::database::ItemDefinitionDatabase,
+    loot_pool::database::LootPoolDefinitionDatabase,
+};
+
+/// Sent after game data has been reloaded from disk and applied.
+#[derive(Event)]
+pub struct DataReloaded;
+
+/// Polls every data root for changes to any data file.
+#[derive(Resource)]
+pub struct DataWatcher {
+    pub timer: Timer,
+
+    last_modified: HashMap<PathBuf, SystemTime>,
+
+    /// Whether the first poll, which only records modification times, has happened.
+    polled: bool,
+}
+
+impl Default for DataWatcher {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            last_modified: HashMap::new(),
+            polled: false,
+        }
+    }
+}
+
+impl DataWatcher {
+    const FILE_NAMES: [&'static str; 7] = [
+        AffixDefinitionDatabase::FILE_NAME,
+        AffixPoolDefinitionDatabase::FILE_NAME,
+        ItemDefinitionDatabase::FILE_NAME,
+        LootPoolDefinitionDatabase::FILE_NAME,
+        EnemyDefinitionDatabase::FILE_NAME,
+        StatRegistry::FILE_NAME,
+        StatDerivationDatabase::FILE_NAME,
+    ];
+
+    /// Returns whether any data file or string table in `data_roots` was modified since the last poll.
+    /// The first poll only records modification times. After that, files that appear count as modified too, such as a
+    /// new overlay file.
+    pub fn poll(&mut self, data_roots: &[PathBuf]) -> bool {
+        let mut changed = false;
+
+        for data_root in data_roots {
+            for file_name in Self::FILE_NAMES {
+                changed |= self.poll_file(&data_root.join(file_name));
+            }
+
+            let Ok(entries) = std::fs::read_dir(data_root.join(Localization::DIRECTORY_NAME))
+            else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                changed |= self.poll_file(&entry.path());
+            }
+        }
+
+        self.polled = true;
+        changed
+    }
+
+    fn poll_file(&mut self, path: &Path) -> bool {
+        let Ok(modified) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+            return false;
+        };
+
+        match self.last_modified.insert(path.to_owned(), modified) {
+            Some(last_modified) => last_modified != modified,
+            None => self.polled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_created_after_first_poll_are_changes() {
+        let mut data_root = std::env::temp_dir();
+        data_root.push("cypher_watch_new_file");
+        let _ = std::fs::remove_dir_all(&data_root);
+        std::fs::create_dir_all(&data_root).unwrap();
+        std::fs::write(data_root.join(ItemDefinitionDatabase::FILE_NAME), "[]").unwrap();
+
+        let mut watcher = DataWatcher::default();
+        let data_roots = vec![data_root.clone()];
+        assert!(!watcher.poll(&data_roots));
+        assert!(!watcher.poll(&data_roots));
+
+        std::fs::write(data_root.join(LootPoolDefinitionDatabase::FILE_NAME), "[]").unwrap();
+        assert!(watcher.poll(&data_roots));
+        assert!(!watcher.poll(&data_roots));
+    }
+}
@@ -1 +1,2 @@
 pub mod data_manager;
+pub mod data_watcher;
@@ -0,0 +1,39 @@
+use bevy::{
+    prelude::{EventWriter, Res, ResMut},
+    time::Time,
+};
+
+use crate::resources::{
+    data_manager::DataManager,
+    data_watcher::{DataReloaded, DataWatcher},
+};
+
+/// Reloads game data when any data file changes on disk.
+/// Takes the [DataManager] mutably so that no other system can observe a partially applied reload.
+pub fn reload_changed_game_data(
+    time: Res<Time>,
+    mut watcher: ResMut<DataWatcher>,
+    mut data_manager: ResMut<DataManager>,
+    mut data_reloaded: EventWriter<DataReloaded>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if !watcher.poll(&data_manager.data_roots) {
+        return;
+    }
+
+    println!("Game data changed on disk, reloading");
+
+    match data_manager.reload() {
+        Ok(report) => {
+            for warning in &report.warnings {
+                println!("Game data warning: {warning}");
+            }
+
+            data_reloaded.send(DataReloaded);
+        }
+        Err(err) => println!("Rejected game data reload, keeping previous data: {err}"),
+    }
+}
@@ -0,0 +1,11 @@
+use bevy::app::{App, Update};
+
+use crate::resources::data_watcher::{DataReloaded, DataWatcher};
+
+mod hot_reload;
+
+pub fn register_systems(app: &mut App) {
+    app.add_event::<DataReloaded>()
+        .init_resource::<DataWatcher>()
+        .add_systems(Update, hot_reload::reload_changed_game_data);
+}
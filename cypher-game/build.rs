@@ -1,86 +1,145 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
+
+use cypher_core::{
+    affix::database::AffixDefinitionDatabase, affix_pool::database::AffixPoolDefinitionDatabase,
+    data::DataDefinitionDatabase, localization::Localization,
+    stat_derivation::database::StatDerivationDatabase, stat_registry::database::StatRegistry,
+};
+use cypher_item::{
+    bundle::GameDataBundle,
+    enemy::database::EnemyDefinitionDatabase,
+    item::database::ItemDefinitionDatabase,
+    loot_pool::database::LootPoolDefinitionDatabase,
+    validation::{validate_databases, validate_enemies, validate_localization, validate_stats},
+};
 
 fn main() {
-    {
-        let mut dir_path = std::env::current_dir().unwrap();
-        dir_path.push("assets");
-        dir_path.push("game_data");
-        let _ = std::fs::create_dir(dir_path);
-    }
-
-    {
-        let mut from_path = std::env::current_dir().unwrap();
-        from_path.push("..");
-        from_path.push("cypher-core");
-        from_path.push("data");
-
+    let mut game_data_path = std::env::current_dir().unwrap();
+    game_data_path.push("assets");
+    game_data_path.push("game_data");
+    let _ = std::fs::create_dir(&game_data_path);
+
+    for (crate_name, file_name) in [
+        ("cypher-core", AffixDefinitionDatabase::FILE_NAME),
+        ("cypher-core", AffixPoolDefinitionDatabase::FILE_NAME),
+        ("cypher-item", ItemDefinitionDatabase::FILE_NAME),
+        ("cypher-item", LootPoolDefinitionDatabase::FILE_NAME),
+        ("cypher-item", EnemyDefinitionDatabase::FILE_NAME),
+        ("cypher-core", StatRegistry::FILE_NAME),
+        ("cypher-core", StatDerivationDatabase::FILE_NAME),
+    ] {
+        let from_path = data_path(crate_name);
         println!("cargo:rerun-if-changed={}", from_path.to_str().unwrap());
 
-        from_path.push("affix.json");
-
-        let mut to_path = std::env::current_dir().unwrap();
-        to_path.push("assets");
-        to_path.push("game_data");
-        to_path.push("affix.json");
-
-        copy_data(from_path, to_path);
+        copy_data(from_path.join(file_name), game_data_path.join(file_name));
     }
 
-    {
-        let mut from_path = std::env::current_dir().unwrap();
-        from_path.push("..");
-        from_path.push("cypher-core");
-        from_path.push("data");
-
-        println!("cargo:rerun-if-changed={}", from_path.to_str().unwrap());
-
-        from_path.push("affix_pool.json");
+    let locale_from_path = data_path("cypher-core").join(Localization::DIRECTORY_NAME);
+    println!(
+        "cargo:rerun-if-changed={}",
+        locale_from_path.to_str().unwrap()
+    );
 
-        let mut to_path = std::env::current_dir().unwrap();
-        to_path.push("assets");
-        to_path.push("game_data");
-        to_path.push("affix_pool.json");
+    let locale_to_path = game_data_path.join(Localization::DIRECTORY_NAME);
+    let _ = std::fs::create_dir(&locale_to_path);
 
-        copy_data(from_path, to_path);
+    for entry in std::fs::read_dir(locale_from_path).unwrap() {
+        let entry = entry.unwrap();
+        copy_data(entry.path(), locale_to_path.join(entry.file_name()));
     }
 
-    {
-        let mut from_path = std::env::current_dir().unwrap();
-        from_path.push("..");
-        from_path.push("cypher-item");
-        from_path.push("data");
-
-        println!("cargo:rerun-if-changed={}", from_path.to_str().unwrap());
+    write_bundle(game_data_path);
+}
 
-        from_path.push("item.json");
+/// Directory the data files owned by `crate_name` are kept in.
+fn data_path(crate_name: &str) -> PathBuf {
+    let mut path = std::env::current_dir().unwrap();
+    path.push("..");
+    path.push(crate_name);
+    path.push("data");
+    path
+}
 
-        let mut to_path = std::env::current_dir().unwrap();
-        to_path.push("assets");
-        to_path.push("game_data");
-        to_path.push("item.json");
+fn copy_data(from: PathBuf, to: PathBuf) {
+    std::fs::copy(from, to).unwrap();
+}
 
-        copy_data(from_path, to_path);
+/// Loads the copied data, refusing to build if it's broken, and bundles it for faster loading at startup.
+fn write_bundle(dir_path: PathBuf) {
+    let path_of = |file_name: &str| {
+        let mut path = dir_path.clone();
+        path.push(file_name);
+        path.to_str().unwrap().to_owned()
+    };
+
+    let affix_db = Arc::new(
+        AffixDefinitionDatabase::load_from(path_of(AffixDefinitionDatabase::FILE_NAME), &())
+            .unwrap_or_else(|err| panic!("{err}")),
+    );
+    let affix_pool_db = Arc::new(
+        AffixPoolDefinitionDatabase::load_from(
+            path_of(AffixPoolDefinitionDatabase::FILE_NAME),
+            &affix_db,
+        )
+        .unwrap_or_else(|err| panic!("{err}")),
+    );
+    let item_db = Arc::new(
+        ItemDefinitionDatabase::load_from(
+            path_of(ItemDefinitionDatabase::FILE_NAME),
+            &(affix_db.clone(), affix_pool_db.clone()),
+        )
+        .unwrap_or_else(|err| panic!("{err}")),
+    );
+    let loot_pool_db = Arc::new(
+        LootPoolDefinitionDatabase::load_from(
+            path_of(LootPoolDefinitionDatabase::FILE_NAME),
+            &item_db,
+        )
+        .unwrap_or_else(|err| panic!("{err}")),
+    );
+    let enemy_db = EnemyDefinitionDatabase::load_from(
+        path_of(EnemyDefinitionDatabase::FILE_NAME),
+        &loot_pool_db,
+    )
+    .unwrap_or_else(|err| panic!("{err}"));
+    let stat_derivation_db =
+        StatDerivationDatabase::load_from(path_of(StatDerivationDatabase::FILE_NAME), &())
+            .unwrap_or_else(|err| panic!("{err}"));
+    let stat_registry = StatRegistry::load_from(path_of(StatRegistry::FILE_NAME), &())
+        .unwrap_or_else(|err| panic!("{err}"));
+
+    let localization = Localization::load_from_layers(std::slice::from_ref(&dir_path))
+        .unwrap_or_else(|err| panic!("{err}"));
+
+    let mut report = validate_databases(&affix_db, &affix_pool_db, &item_db, &loot_pool_db);
+    report.extend(validate_localization(
+        &localization,
+        &affix_db,
+        &affix_pool_db,
+        &item_db,
+        &stat_registry,
+    ));
+    report.extend(validate_stats(
+        &stat_registry,
+        &affix_db,
+        &stat_derivation_db,
+    ));
+    report.extend(validate_enemies(&enemy_db, &stat_registry, &localization));
+    for warning in &report.warnings {
+        println!("cargo:warning=game data: {warning}");
     }
-
-    {
-        let mut from_path = std::env::current_dir().unwrap();
-        from_path.push("..");
-        from_path.push("cypher-item");
-        from_path.push("data");
-
-        println!("cargo:rerun-if-changed={}", from_path.to_str().unwrap());
-
-        from_path.push("loot_pool.json");
-
-        let mut to_path = std::env::current_dir().unwrap();
-        to_path.push("assets");
-        to_path.push("game_data");
-        to_path.push("loot_pool.json");
-
-        copy_data(from_path, to_path);
+    if !report.is_valid() {
+        panic!("game data failed validation:\n{report}");
     }
-}
 
-fn copy_data(from: PathBuf, to: PathBuf) {
-    std::fs::copy(from, to).unwrap();
+    GameDataBundle::from_databases(
+        &affix_db,
+        &affix_pool_db,
+        &item_db,
+        &loot_pool_db,
+        &enemy_db,
+        &stat_derivation_db,
+        &stat_registry,
+    )
+    .write_to(path_of(GameDataBundle::FILE_NAME));
 }
@@ -43,14 +43,61 @@ pub enum SimulationMode {
 pub fn start(mode: SimulationMode) {
     let mut app = App::new();
 
-    if let Ok(game_data_path) = std::env::var("GAME_DATA_PATH") {
+    let game_data_path = if let Ok(game_data_path) = std::env::var("GAME_DATA_PATH") {
         println!("Initializing with game data path {}", game_data_path);
-        let data_manager = DataManager::new(game_data_path.into());
-        app.insert_resource(data_manager);
+        game_data_path.into()
     } else {
         println!("Initializing with default game data path.");
-        app.init_resource::<DataManager>();
-    }
+        DataManager::default_game_data_path()
+    };
+
+    // Overlays, such as balance experiments or mods, are applied on top of the base game data in order
+    let data_manager = match std::env::var_os("GAME_DATA_LAYERS") {
+        Some(layers) => {
+            let mut data_roots = vec![game_data_path];
+            data_roots.extend(std::env::split_paths(&layers));
+            println!("Initializing with game data layers {data_roots:?}");
+            DataManager::with_layers(data_roots)
+        }
+        None => DataManager::load(game_data_path),
+    };
+
+    let data_hash = match data_manager {
+        Ok(mut data_manager) => {
+            if let Ok(locale) = std::env::var("GAME_LOCALE") {
+                println!("Using locale {locale}");
+                data_manager.localization.set_locale(locale);
+            }
+
+            let data_hash = data_manager.content_hash();
+            println!("Game data hash: {data_hash:016x}");
+
+            let report = data_manager.validate();
+            for warning in &report.warnings {
+                println!("Game data warning: {warning}");
+            }
+
+            if !report.is_valid() {
+                eprintln!("Game data failed validation:");
+                for error in &report.errors {
+                    eprintln!("\t{error}");
+                }
+                std::process::exit(1);
+            }
+
+            app.insert_resource(data_manager);
+            cypher_data::systems::register_systems(&mut app);
+
+            data_hash
+        }
+        Err(errors) => {
+            eprintln!("Failed to load game data:");
+            for error in errors {
+                eprintln!("\t{error}");
+            }
+            std::process::exit(1);
+        }
+    };
 
     let socket_bind_override = match std::env::var("BIND_ADDR") {
         Ok(addr) => Some(addr),
@@ -61,7 +108,7 @@ pub fn start(mode: SimulationMode) {
 
     match mode {
         SimulationMode::ClientOnly => {
-            let client_id = Client::initialize(&mut app);
+            let client_id = Client::initialize(&mut app, data_hash);
 
             app.init_resource::<WorldState>()
                 .init_resource::<NetLimiter>()
@@ -108,7 +155,7 @@ pub fn start(mode: SimulationMode) {
             cypher_world::systems::server::register_server_systems(&mut app);
         }
         SimulationMode::ClientAndServer => {
-            let client_id = Client::initialize(&mut app);
+            let client_id = Client::initialize(&mut app, data_hash);
             GameServer::initialize(&mut app);
 
             app.init_resource::<WorldState>()
@@ -181,17 +228,38 @@ fn on_item_picked_up(
 
         // ZJ-TODO: have equip be different
 
-        let item_instance = deserializer
-            .deserialize(&mut serde_json::Deserializer::from_slice(
-                item_instance_raw.as_slice(),
-            ))
-            .unwrap();
+        let item_instance = match deserializer.deserialize(
+            &mut serde_json::Deserializer::from_slice(item_instance_raw.as_slice()),
+        ) {
+            Ok(item_instance) => item_instance,
+            Err(err) => {
+                println!("Failed to deserialize picked up item: {err}");
+                continue;
+            }
+        };
+
+        println!(
+            "Picked up {}",
+            item_instance.text(
+                &data_manager.localization,
+                &data_manager.stat_registry,
+                false
+            )
+        );
 
         let mut character = character_query.single_mut();
         character
-            .equipment
             .equip(item_instance)
             .expect("ZJ-TODO: UI to show failure to equip");
-        println!("{}", character.equipment);
+        for item in character.equipment().items() {
+            println!(
+                "Equipped {}",
+                item.text(
+                    &data_manager.localization,
+                    &data_manager.stat_registry,
+                    false
+                )
+            );
+        }
     }
 }
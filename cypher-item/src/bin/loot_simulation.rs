@@ -0,0 +1,184 @@
+use std::{path::PathBuf, sync::Arc};
+
+use cypher_core::{
+    affix::database::AffixDefinitionDatabase,
+    affix_pool::database::AffixPoolDefinitionDatabase,
+    data::{DataDefinitionDatabase, DataLoadError},
+};
+use cypher_item::{
+    item::{database::ItemDefinitionDatabase, definition::ItemDefinitionId},
+    loot_pool::{
+        database::LootPoolDefinitionDatabase, generator::LootPoolCriteria,
+        simulation::LootSimulation,
+    },
+    validation::validate_databases,
+};
+
+const USAGE: &str = "usage: loot_simulation [--pool ID] [--iterations N] [--seed N] [--item-level N]
+    [--increased-rarity PERCENT] [--increased-quantity PERCENT] [--item ID]... [--json] [--data DIR]
+
+Generates N drops from a loot pool and reports how often each item, rarity, affix and affix tier dropped.
+--item prints the average kills until that item drops. --json prints the whole report as JSON instead.
+--data reads every data file from DIR, such as cypher-game/assets/game_data, rather than the source data.";
+
+struct Options {
+    pool_id: u32,
+    simulation: LootSimulation,
+    criteria: LootPoolCriteria,
+    item_ids: Vec<ItemDefinitionId>,
+    json: bool,
+    data_dir: Option<PathBuf>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        pool_id: 1,
+        simulation: LootSimulation {
+            iterations: 10_000,
+            seed: 0,
+        },
+        criteria: LootPoolCriteria {
+            item_level: 1,
+            ..Default::default()
+        },
+        item_ids: vec![],
+        json: false,
+        data_dir: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            options.json = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{arg} is missing a value"))?;
+        let invalid = || format!("{value} is not a valid value for {arg}");
+
+        match arg.as_str() {
+            "--pool" => options.pool_id = value.parse().map_err(|_| invalid())?,
+            "--iterations" => {
+                options.simulation.iterations = value.parse().map_err(|_| invalid())?
+            }
+            "--seed" => options.simulation.seed = value.parse().map_err(|_| invalid())?,
+            "--item-level" => options.criteria.item_level = value.parse().map_err(|_| invalid())?,
+            "--increased-rarity" => {
+                options.criteria.increased_rarity = value.parse().map_err(|_| invalid())?;
+            }
+            "--increased-quantity" => {
+                options.criteria.increased_quantity = value.parse().map_err(|_| invalid())?;
+            }
+            "--item" => options.item_ids.push(value.parse().map_err(|_| invalid())?),
+            "--data" => options.data_dir = Some(value.into()),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn exit_on_error<T>(err: DataLoadError) -> T {
+    eprintln!("Failed to load game data: {err}");
+    std::process::exit(1);
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let options = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        std::process::exit(2);
+    });
+
+    // Source data is split between crates, while game data directories hold every file
+    let path_of = |source_crate: &str, file_name: &str| {
+        let path = match &options.data_dir {
+            Some(data_dir) => data_dir.join(file_name),
+            None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join(source_crate)
+                .join("data")
+                .join(file_name),
+        };
+        path.to_str().unwrap().to_owned()
+    };
+    let affix_db = Arc::new(
+        AffixDefinitionDatabase::load_from(
+            path_of("cypher-core", AffixDefinitionDatabase::FILE_NAME),
+            &(),
+        )
+        .unwrap_or_else(exit_on_error),
+    );
+    let affix_pool_db = Arc::new(
+        AffixPoolDefinitionDatabase::load_from(
+            path_of("cypher-core", AffixPoolDefinitionDatabase::FILE_NAME),
+            &affix_db,
+        )
+        .unwrap_or_else(exit_on_error),
+    );
+    let item_db = Arc::new(
+        ItemDefinitionDatabase::load_from(
+            path_of("cypher-item", ItemDefinitionDatabase::FILE_NAME),
+            &(affix_db.clone(), affix_pool_db.clone()),
+        )
+        .unwrap_or_else(exit_on_error),
+    );
+    let loot_pool_db = Arc::new(
+        LootPoolDefinitionDatabase::load_from(
+            path_of("cypher-item", LootPoolDefinitionDatabase::FILE_NAME),
+            &item_db,
+        )
+        .unwrap_or_else(exit_on_error),
+    );
+
+    // Data from --data may not have been validated by a build, and invalid pools can't be simulated
+    let report = validate_databases(&affix_db, &affix_pool_db, &item_db, &loot_pool_db);
+    for warning in &report.warnings {
+        eprintln!("Game data warning: {warning}");
+    }
+    if !report.is_valid() {
+        eprintln!("Game data failed validation:");
+        for error in &report.errors {
+            eprintln!("\t{error}");
+        }
+        std::process::exit(1);
+    }
+
+    let Some(definition) = loot_pool_db.definition(options.pool_id) else {
+        eprintln!("There is no loot pool {}", options.pool_id);
+        std::process::exit(1);
+    };
+
+    let report = options.simulation.run(
+        definition,
+        &options.criteria,
+        &(affix_db, affix_pool_db, item_db, loot_pool_db),
+    );
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    print!("{report}");
+
+    if !options.item_ids.is_empty() {
+        println!();
+    }
+    for item_id in &options.item_ids {
+        match report.expected_kills_to_drop(*item_id) {
+            Some(kills) => println!("Item {item_id} drops every {kills:.1} kills on average"),
+            None => println!(
+                "Item {item_id} never dropped in {} kills",
+                options.simulation.iterations
+            ),
+        }
+    }
+}
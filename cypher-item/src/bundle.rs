@@ -0,0 +1,293 @@
+use std::{path::Path, sync::Arc};
+
+use cypher_core::{
+    affix::database::AffixDefinitionDatabase,
+    affix_pool::database::AffixPoolDefinitionDatabase,
+    data::{DataDefinitionDatabase, DataLoadError},
+    stat_derivation::database::StatDerivationDatabase,
+    stat_registry::database::StatRegistry,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enemy::database::EnemyDefinitionDatabase, item::database::ItemDefinitionDatabase,
+    loot_pool::database::LootPoolDefinitionDatabase,
+};
+
+/// All game data databases, pre-validated and encoded as a single binary file.
+/// Built by cypher-game's build script so the game doesn't parse JSON at startup.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GameDataBundle {
+    /// Bundles written with a different schema version can't be decoded and must be rebuilt.
+    pub schema_version: u32,
+
+    /// Hash of the encoded data, useful for checking that a client and server were built from the same data.
+    pub content_hash: u64,
+
+    affixes: Vec<u8>,
+    affix_pools: Vec<u8>,
+    enemies: Vec<u8>,
+    items: Vec<u8>,
+    loot_pools: Vec<u8>,
+    stat_derivations: Vec<u8>,
+    stats: Vec<u8>,
+}
+
+/// The databases decoded from a [GameDataBundle], in dependency order.
+pub type BundledDatabases = (
+    Arc<AffixDefinitionDatabase>,
+    Arc<AffixPoolDefinitionDatabase>,
+    Arc<ItemDefinitionDatabase>,
+    Arc<LootPoolDefinitionDatabase>,
+    Arc<EnemyDefinitionDatabase>,
+    Arc<StatDerivationDatabase>,
+    Arc<StatRegistry>,
+);
+
+impl GameDataBundle {
+    pub const FILE_NAME: &'static str = "game_data.bundle";
+
+    /// Bump whenever the bundle's own fields change, as they are encoded by order.
+    /// Definitions are encoded with their field names, so only need a bump for changes older data can't be read as,
+    /// such as a field being renamed.
+    pub const SCHEMA_VERSION: u32 = 11;
+
+    /// Data files whose definitions are bundled. String tables aren't bundled.
+    pub const SOURCE_FILE_NAMES: [&'static str; 7] = [
+        AffixDefinitionDatabase::FILE_NAME,
+        AffixPoolDefinitionDatabase::FILE_NAME,
+        ItemDefinitionDatabase::FILE_NAME,
+        LootPoolDefinitionDatabase::FILE_NAME,
+        EnemyDefinitionDatabase::FILE_NAME,
+        StatDerivationDatabase::FILE_NAME,
+        StatRegistry::FILE_NAME,
+    ];
+
+    /// Whether the bundle in `data_root` was written after every bundled data file next to it was last modified.
+    /// Data files edited since the bundle was built, such as while tweaking balance, make it stale.
+    pub fn is_current(data_root: &Path) -> bool {
+        let modified =
+            |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+
+        let Ok(bundle_modified) = modified(&data_root.join(Self::FILE_NAME)) else {
+            return false;
+        };
+
+        Self::SOURCE_FILE_NAMES.iter().all(|file_name| {
+            modified(&data_root.join(file_name))
+                .map_or(true, |file_modified| file_modified <= bundle_modified)
+        })
+    }
+
+    pub fn from_databases(
+        affix_db: &AffixDefinitionDatabase,
+        affix_pool_db: &AffixPoolDefinitionDatabase,
+        item_db: &ItemDefinitionDatabase,
+        loot_pool_db: &LootPoolDefinitionDatabase,
+        enemy_db: &EnemyDefinitionDatabase,
+        stat_derivation_db: &StatDerivationDatabase,
+        stat_registry: &StatRegistry,
+    ) -> GameDataBundle {
+        let affixes = affix_db.to_bytes();
+        let affix_pools = affix_pool_db.to_bytes();
+        let enemies = enemy_db.to_bytes();
+        let items = item_db.to_bytes();
+        let loot_pools = loot_pool_db.to_bytes();
+        let stat_derivations = stat_derivation_db.to_bytes();
+        let stats = stat_registry.to_bytes();
+
+        let content_hash = content_hash(
+            Self::SCHEMA_VERSION,
+            [
+                &affixes,
+                &affix_pools,
+                &enemies,
+                &items,
+                &loot_pools,
+                &stat_derivations,
+                &stats,
+            ],
+        );
+
+        GameDataBundle {
+            schema_version: Self::SCHEMA_VERSION,
+            content_hash,
+            affixes,
+            affix_pools,
+            enemies,
+            items,
+            loot_pools,
+            stat_derivations,
+            stats,
+        }
+    }
+
+    pub fn write_to<S: Into<String>>(&self, path: S) {
+        let serialized = postcard::to_extend(self, vec![]).expect("failed to serialize bundle");
+        std::fs::write(path.into(), serialized).expect("failed to write bundle to path");
+    }
+
+    /// Reads a bundle, rejecting it if it was written with another schema version or has been modified since.
+    pub fn read_from(path: &str) -> Result<GameDataBundle, DataLoadError> {
+        let bytes = std::fs::read(path).map_err(|source| DataLoadError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        let bundle: GameDataBundle =
+            postcard::from_bytes(&bytes).map_err(|err| DataLoadError::InvalidBundle {
+                path: path.to_owned(),
+                reason: err.to_string(),
+            })?;
+
+        if bundle.schema_version != Self::SCHEMA_VERSION {
+            return Err(DataLoadError::InvalidBundle {
+                path: path.to_owned(),
+                reason: format!(
+                    "schema version is {}, expected {}",
+                    bundle.schema_version,
+                    Self::SCHEMA_VERSION
+                ),
+            });
+        }
+
+        let expected_hash = content_hash(
+            bundle.schema_version,
+            [
+                &bundle.affixes,
+                &bundle.affix_pools,
+                &bundle.enemies,
+                &bundle.items,
+                &bundle.loot_pools,
+                &bundle.stat_derivations,
+                &bundle.stats,
+            ],
+        );
+        if bundle.content_hash != expected_hash {
+            return Err(DataLoadError::InvalidBundle {
+                path: path.to_owned(),
+                reason: String::from("content hash does not match its data"),
+            });
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn databases(&self) -> Result<BundledDatabases, DataLoadError> {
+        let affix_db = Arc::new(AffixDefinitionDatabase::load_from_bytes(
+            &self.affixes,
+            &(),
+        )?);
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::load_from_bytes(
+            &self.affix_pools,
+            &affix_db,
+        )?);
+        let item_db = Arc::new(ItemDefinitionDatabase::load_from_bytes(
+            &self.items,
+            &(affix_db.clone(), affix_pool_db.clone()),
+        )?);
+        let loot_pool_db = Arc::new(LootPoolDefinitionDatabase::load_from_bytes(
+            &self.loot_pools,
+            &item_db,
+        )?);
+        let enemy_db = Arc::new(EnemyDefinitionDatabase::load_from_bytes(
+            &self.enemies,
+            &loot_pool_db,
+        )?);
+
+        let stat_derivation_db = Arc::new(StatDerivationDatabase::load_from_bytes(
+            &self.stat_derivations,
+            &(),
+        )?);
+        let stat_registry = Arc::new(StatRegistry::load_from_bytes(&self.stats, &())?);
+
+        Ok((
+            affix_db,
+            affix_pool_db,
+            item_db,
+            loot_pool_db,
+            enemy_db,
+            stat_derivation_db,
+            stat_registry,
+        ))
+    }
+}
+
+/// FNV-1a, as std's hashers aren't guaranteed to be stable between builds.
+fn content_hash(schema_version: u32, blobs: [&Vec<u8>; 7]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+
+    write(&schema_version.to_le_bytes());
+    for blob in blobs {
+        // Length prefix, so that bytes moving between blobs changes the hash
+        write(&(blob.len() as u64).to_le_bytes());
+        write(blob);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_roundtrips_shipped_data() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+        let item_db = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_db.clone(),
+            affix_pool_db.clone(),
+        ));
+        let loot_pool_db = Arc::new(LootPoolDefinitionDatabase::initialize(item_db.clone()));
+        let enemy_db = EnemyDefinitionDatabase::initialize(loot_pool_db.clone());
+        let stat_derivation_db = StatDerivationDatabase::initialize();
+        let stat_registry = StatRegistry::initialize();
+
+        let bundle = GameDataBundle::from_databases(
+            &affix_db,
+            &affix_pool_db,
+            &item_db,
+            &loot_pool_db,
+            &enemy_db,
+            &stat_derivation_db,
+            &stat_registry,
+        );
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_roundtrip.bundle");
+        bundle.write_to(path.to_str().unwrap());
+
+        let read_bundle = GameDataBundle::read_from(path.to_str().unwrap()).unwrap();
+        assert_eq!(bundle.content_hash, read_bundle.content_hash);
+
+        let (
+            read_affix_db,
+            read_affix_pool_db,
+            read_item_db,
+            read_loot_pool_db,
+            read_enemy_db,
+            read_stat_derivation_db,
+            read_stat_registry,
+        ) = read_bundle.databases().unwrap();
+        assert_eq!(affix_db.to_bytes(), read_affix_db.to_bytes());
+        assert_eq!(affix_pool_db.to_bytes(), read_affix_pool_db.to_bytes());
+        assert_eq!(item_db.to_bytes(), read_item_db.to_bytes());
+        assert_eq!(loot_pool_db.to_bytes(), read_loot_pool_db.to_bytes());
+        assert_eq!(enemy_db.to_bytes(), read_enemy_db.to_bytes());
+        assert_eq!(
+            stat_derivation_db.to_bytes(),
+            read_stat_derivation_db.to_bytes()
+        );
+        assert_eq!(stat_registry.to_bytes(), read_stat_registry.to_bytes());
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use cypher_core::data::{DataDefinitionDatabase, DefinitionRegistry, DefinitionStore};
+
+use crate::loot_pool::database::LootPoolDefinitionDatabase;
+
+use super::definition::EnemyDefinition;
+
+pub struct EnemyDefinitionDatabase {
+    enemies: DefinitionStore<EnemyDefinition>,
+}
+
+impl EnemyDefinitionDatabase {
+    pub fn initialize(loot_pool_db: Arc<LootPoolDefinitionDatabase>) -> Self {
+        let mut path = std::env::current_dir().unwrap();
+        path.push("..");
+        path.push("cypher-item");
+        path.push("data");
+        path.push(Self::FILE_NAME);
+
+        Self::load_from(path.to_str().unwrap(), &loot_pool_db)
+            .expect("failed to load enemy database")
+    }
+}
+
+impl DataDefinitionDatabase<EnemyDefinition> for EnemyDefinitionDatabase {
+    type DataDependencies = Arc<LootPoolDefinitionDatabase>;
+
+    const FILE_NAME: &'static str = "enemy.json";
+
+    fn from_store(enemies: DefinitionStore<EnemyDefinition>) -> Self {
+        EnemyDefinitionDatabase { enemies }
+    }
+
+    fn store(&self) -> &DefinitionStore<EnemyDefinition> {
+        &self.enemies
+    }
+
+    fn registry(loot_pool_db: &Self::DataDependencies) -> DefinitionRegistry {
+        DefinitionRegistry::default().with(loot_pool_db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cypher_core::{
+        affix::database::AffixDefinitionDatabase,
+        affix_pool::database::AffixPoolDefinitionDatabase, data::DataDefinitionDatabase,
+    };
+
+    use super::EnemyDefinitionDatabase;
+    use crate::{
+        item::database::ItemDefinitionDatabase, loot_pool::database::LootPoolDefinitionDatabase,
+    };
+
+    #[test]
+    fn enemy_initialize() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+        let loot_pool_database = Arc::new(LootPoolDefinitionDatabase::initialize(
+            item_database.clone(),
+        ));
+        let enemy_database = EnemyDefinitionDatabase::initialize(loot_pool_database.clone());
+
+        assert!(enemy_database.validate());
+
+        let enemy = enemy_database.definition(1).unwrap();
+        assert_eq!(enemy.loot_pools[0].loot_pool.id, 1);
+    }
+}
@@ -0,0 +1,95 @@
+use cypher_core::{
+    data::{DataDefinition, DefRef},
+    localization::LocalizationKey,
+    stat::{StatId, StatList, StatModifier, StatModifierKind},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::loot_pool::definition::LootPoolDefinition;
+
+pub type EnemyDefinitionId = u64;
+
+/// A stat modifier every enemy of a definition has, such as armor or magic find.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EnemyStat {
+    pub stat: StatId,
+    pub kind: StatModifierKind,
+    pub value: f32,
+}
+
+/// A loot pool an enemy drops from when it dies.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EnemyLootPool {
+    pub loot_pool: DefRef<LootPoolDefinition>,
+
+    /// Chance from 0 to 1 that the pool is generated at all. Each pool is rolled separately.
+    pub chance: f32,
+}
+
+/// An [EnemyDefinition] declares a kind of enemy: how it looks, how tough it is and what it drops.
+/// Servers spawn enemies by definition ID, which is also sent to clients so they can draw them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EnemyDefinition {
+    pub id: EnemyDefinitionId,
+
+    /// Key of the display name in the string tables.
+    pub name: LocalizationKey,
+
+    /// Stats the enemy defends with. [MAGIC_FIND](cypher_core::stat::MAGIC_FIND) and
+    /// [ITEM_QUANTITY](cypher_core::stat::ITEM_QUANTITY) improve its drops.
+    #[serde(default)]
+    pub base_stats: Vec<EnemyStat>,
+
+    pub hit_points: f32,
+
+    pub team: u16,
+
+    /// RGB color, from 0 to 1, that the enemy is drawn with. Tints the sprite if there is one.
+    pub color: [f32; 3],
+
+    /// Asset path of the enemy's sprite. Enemies without one are drawn as a square of their color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprite: Option<String>,
+
+    /// Width and height of the enemy, in world units.
+    pub size: f32,
+
+    #[serde(default)]
+    pub loot_pools: Vec<EnemyLootPool>,
+
+    /// Experience the enemy is worth when killed.
+    /// ZJ-TODO: characters don't gain experience yet
+    pub experience: u32,
+}
+
+impl EnemyDefinition {
+    /// The enemy's base stats as a [StatList].
+    pub fn stats(&self) -> StatList {
+        let mods = self
+            .base_stats
+            .iter()
+            .map(|stat| StatModifier(stat.stat, stat.value, stat.kind))
+            .collect::<Vec<_>>();
+
+        StatList::from(&mods)
+    }
+}
+
+impl DataDefinition for EnemyDefinition {
+    type DefinitionTypeId = EnemyDefinitionId;
+
+    const TYPE_NAME: &'static str = "enemy";
+
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn validate(&self) -> bool {
+        self.hit_points > 0.
+            && self.size > 0.
+            && self
+                .loot_pools
+                .iter()
+                .all(|loot_pool| (0.0..=1.0).contains(&loot_pool.chance))
+    }
+}
@@ -1,98 +1,53 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
+use std::sync::Arc;
 
 use cypher_core::{
     affix::database::AffixDefinitionDatabase,
     affix_pool::database::AffixPoolDefinitionDatabase,
-    data::{DataDefinition, DataDefinitionDatabase},
+    data::{DataDefinitionDatabase, DefinitionRegistry, DefinitionStore},
 };
-use serde::de::DeserializeSeed;
 
-use super::{
-    definition::{ItemDefinition, ItemDefinitionId},
-    deserializer::ItemDefinitionDatabaseDeserializer,
-};
+use super::definition::ItemDefinition;
 
 pub struct ItemDefinitionDatabase {
-    pub(super) items: HashMap<ItemDefinitionId, Arc<Mutex<ItemDefinition>>>,
+    items: DefinitionStore<ItemDefinition>,
 }
 
 impl ItemDefinitionDatabase {
     pub fn initialize(
-        affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
-        affix_pool_db: Arc<Mutex<AffixPoolDefinitionDatabase>>,
+        affix_db: Arc<AffixDefinitionDatabase>,
+        affix_pool_db: Arc<AffixPoolDefinitionDatabase>,
     ) -> Self {
         let mut path = std::env::current_dir().unwrap();
         path.push("..");
         path.push("cypher-item");
         path.push("data");
-        path.push("item.json");
+        path.push(Self::FILE_NAME);
 
         Self::load_from(path.to_str().unwrap(), &(affix_db, affix_pool_db))
+            .expect("failed to load item database")
     }
 }
 
 impl DataDefinitionDatabase<ItemDefinition> for ItemDefinitionDatabase {
     type DataDependencies = (
-        Arc<Mutex<AffixDefinitionDatabase>>,
-        Arc<Mutex<AffixPoolDefinitionDatabase>>,
+        Arc<AffixDefinitionDatabase>,
+        Arc<AffixPoolDefinitionDatabase>,
     );
 
-    fn load_from<S: Into<String>>(path: S, dependencies: &Self::DataDependencies) -> Self {
-        let item_file = String::from_utf8(std::fs::read(path.into()).unwrap()).unwrap();
-
-        let (affix_db, affix_pool_db) = dependencies;
-
-        let item_def_deserializer = ItemDefinitionDatabaseDeserializer {
-            affix_db: affix_db.clone(),
-            affix_pool_db: affix_pool_db.clone(),
-        };
-        let definitions: Vec<ItemDefinition> = item_def_deserializer
-            .deserialize(&mut serde_json::Deserializer::from_str(item_file.as_str()))
-            .unwrap();
-
-        let items = definitions
-            .into_iter()
-            .map(|item| (item.id, Arc::new(Mutex::new(item))))
-            .collect::<HashMap<_, _>>();
+    const FILE_NAME: &'static str = "item.json";
 
+    fn from_store(items: DefinitionStore<ItemDefinition>) -> Self {
         ItemDefinitionDatabase { items }
     }
 
-    fn write_to<S: Into<String>>(&self, path: S) {
-        let definition_clones = self
-            .items
-            .values()
-            .map(|def| def.lock().unwrap().to_owned())
-            .collect::<Vec<ItemDefinition>>();
-
-        let serialized = serde_json::ser::to_string(&definition_clones)
-            .expect("failed to serialize item database");
-
-        std::fs::write(path.into(), serialized).expect("failed to write serialized data to path");
-    }
-
-    fn validate(&self) -> bool {
-        !self.items.is_empty()
-            && self
-                .items
-                .values()
-                .all(|item_def| item_def.lock().unwrap().validate())
+    fn store(&self) -> &DefinitionStore<ItemDefinition> {
+        &self.items
     }
 
-    fn definition(&self, id: ItemDefinitionId) -> Option<Arc<Mutex<ItemDefinition>>> {
-        self.items.get(&id).map(|arc| arc.to_owned())
-    }
-
-    fn definitions(&self) -> Vec<Arc<Mutex<ItemDefinition>>> {
-        self.items.values().map(|def| def.to_owned()).collect()
-    }
-
-    fn add_definition(&mut self, definition: ItemDefinition) {
-        self.items
-            .insert(definition.id, Arc::new(Mutex::new(definition)));
+    fn registry((affix_db, affix_pool_db): &Self::DataDependencies) -> DefinitionRegistry {
+        DefinitionRegistry::default()
+            .with(affix_db)
+            .with(affix_pool_db)
     }
 }
 
@@ -102,17 +57,52 @@ mod tests {
         affix::database::AffixDefinitionDatabase, affix_pool::database::AffixPoolDefinitionDatabase,
     };
 
+    use cypher_core::data::DataLoadError;
+
     use super::{ItemDefinitionDatabase, *};
 
     #[test]
     fn init_item_database() {
-        let affix_db = Arc::new(Mutex::new(AffixDefinitionDatabase::initialize()));
-        let affix_pool_db = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::initialize(
-            affix_db.clone(),
-        )));
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
         let _item_db = Arc::new(ItemDefinitionDatabase::initialize(
             affix_db.clone(),
             affix_pool_db.clone(),
         ));
     }
+
+    #[test]
+    fn unknown_fixed_affix_reports_missing_reference() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_dangling_item.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":1,"classification":{"Equippable":"Boots"},"affix_pools":[1],"fixed_affixes":[],"name":"Fine"},
+{"id":42,"classification":{"Equippable":"Head"},"fixed_affixes":[7,9999],"name":"Dangling"}]"#,
+        )
+        .unwrap();
+
+        let result = ItemDefinitionDatabase::load_from(
+            path.to_str().unwrap(),
+            &(affix_db.clone(), affix_pool_db.clone()),
+        );
+
+        let Err(DataLoadError::MissingReference {
+            line,
+            definition_id,
+            referenced_type,
+            referenced_id,
+            ..
+        }) = result
+        else {
+            panic!("expected missing reference error");
+        };
+        assert_eq!(line, 2);
+        assert_eq!(definition_id, 42);
+        assert_eq!(referenced_type, "affix");
+        assert_eq!(referenced_id, 9999);
+    }
 }
@@ -1,54 +1,87 @@
-use std::{
-    collections::HashSet,
-    sync::{Arc, Mutex},
-};
-use uuid::{self, Uuid};
+use std::{collections::HashSet, sync::Arc};
+use uuid::Builder;
 
 use cypher_core::{
     affix::{
         database::AffixDefinitionDatabase,
         generator::{AffixGenerationCriteria, AffixGenerator},
         instance::AffixInstance,
+        placement::AffixPlacement,
+        tag::AffixTagRules,
     },
     affix_pool::{
         database::AffixPoolDefinitionDatabase,
         definition::AffixPoolDefinition,
         generator::{AffixPoolGenerationCriteria, AffixPoolGenerator},
     },
-    data::{DataDefinitionDatabase, DataInstanceGenerator},
+    data::DataInstanceGenerator,
 };
 
 use rand::{distributions::WeightedIndex, prelude::*};
 
-use super::{definition::ItemDefinition, instance::ItemInstance};
+use super::{
+    definition::ItemDefinition,
+    instance::{ItemInstance, ItemInstanceRarityTier},
+};
 
 pub struct ItemDefinitionCriteria {
+    /// How likely is this item to roll each rarity? Items with fixed affixes are always [ItemInstanceRarityTier::Fabled].
+    pub rarity_weighting: Vec<(ItemInstanceRarityTier, u64 /* weight */)>,
+
     /// How many affixes can this item roll? Stored as tuples, where tuple.0 = number of affixes possible, and tuple.1 = affix weight
+    /// Only counts within the rolled rarity's [ItemInstanceRarityTier::affix_count_range] are considered.
     pub affix_count_weighting: Vec<(u8 /* count */, u64 /* weight */)>,
 
     pub affix_generation_criteria: AffixGenerationCriteria,
+
+    /// Tags rolled affixes must, may not, or are more likely to have, on top of their pools' own rules.
+    pub affix_tag_rules: AffixTagRules,
 }
 
 impl Default for ItemDefinitionCriteria {
     fn default() -> Self {
         Self {
+            rarity_weighting: vec![
+                (ItemInstanceRarityTier::Common, 800),
+                (ItemInstanceRarityTier::Uncommon, 120),
+                (ItemInstanceRarityTier::Rare, 6),
+            ],
             affix_count_weighting: vec![(1, 500), (2, 300), (3, 100), (4, 20), (5, 5), (6, 1)],
             affix_generation_criteria: Default::default(),
+            affix_tag_rules: Default::default(),
+        }
+    }
+}
+
+impl ItemDefinitionCriteria {
+    /// Reweights rarities and affix counts towards rarer items with more affixes, such as for magic find.
+    /// Each weight is multiplied by `1 + increased_rarity / 100` once for every entry listed before it,
+    /// so entries should be listed from least to most rare, as they are by default.
+    pub fn with_increased_rarity(mut self, increased_rarity: f32) -> Self {
+        let factor = 1. + increased_rarity.max(0.) as f64 / 100.;
+        let boost =
+            |rank: usize, weight: u64| (weight as f64 * factor.powi(rank as i32)).round() as u64;
+
+        for (rank, pair) in self.rarity_weighting.iter_mut().enumerate() {
+            pair.1 = boost(rank, pair.1);
+        }
+        for (rank, pair) in self.affix_count_weighting.iter_mut().enumerate() {
+            pair.1 = boost(rank, pair.1);
         }
+
+        self
     }
 }
 
 pub struct ItemGenerator;
 
-fn generate_from_affix_pool(
+fn roll_rarity<R: Rng + ?Sized>(
     criteria: &ItemDefinitionCriteria,
-    definition: Arc<Mutex<ItemDefinition>>,
-    affix_db: Arc<Mutex<AffixDefinitionDatabase>>,
-    affix_pool_db: Arc<Mutex<AffixPoolDefinitionDatabase>>,
-) -> Vec<AffixInstance> {
+    rng: &mut R,
+) -> ItemInstanceRarityTier {
     let distribution = WeightedIndex::new(
         criteria
-            .affix_count_weighting
+            .rarity_weighting
             .iter()
             .map(|pair| pair.1)
             .collect::<Vec<u64>>()
@@ -56,50 +89,96 @@ fn generate_from_affix_pool(
     )
     .unwrap();
 
-    let mut rng = rand::thread_rng();
-    let affix_count = criteria.affix_count_weighting[distribution.sample(&mut rng)].0;
+    criteria.rarity_weighting[distribution.sample(rng)].0
+}
+
+fn generate_from_affix_pool<R: Rng + ?Sized>(
+    criteria: &ItemDefinitionCriteria,
+    definition: Arc<ItemDefinition>,
+    rarity: ItemInstanceRarityTier,
+    affix_db: Arc<AffixDefinitionDatabase>,
+    rng: &mut R,
+) -> Vec<AffixInstance> {
+    let affix_count_range = rarity.affix_count_range();
+    let affix_count_weighting = criteria
+        .affix_count_weighting
+        .iter()
+        .filter(|pair| affix_count_range.contains(&pair.0))
+        .collect::<Vec<_>>();
+
+    let affix_count = match WeightedIndex::new(
+        affix_count_weighting
+            .iter()
+            .map(|pair| pair.1)
+            .collect::<Vec<u64>>()
+            .as_slice(),
+    ) {
+        Ok(distribution) => affix_count_weighting[distribution.sample(rng)].0,
+        Err(_) => *affix_count_range.end(),
+    };
+    let affix_cap = rarity.affix_cap();
 
     let mut affix_pool_members = vec![];
 
-    let item_definition = definition.lock().unwrap();
-    for pool_def in item_definition.affix_pools.clone() {
-        let affix_pool = affix_pool_db
-            .lock()
-            .unwrap()
-            .definition(pool_def.lock().unwrap().id)
-            .unwrap(); // TODO: remove unwrap
-        for member in &affix_pool.lock().unwrap().members {
-            affix_pool_members.push(member.to_owned());
-        }
+    let item_definition = definition;
+    for affix_pool in &item_definition.affix_pools {
+        affix_pool_members.extend(affix_pool.eligible_members());
     }
 
-    let pool = Arc::new(Mutex::new(AffixPoolDefinition::with_members(
-        affix_pool_members,
-    )));
+    let pool = Arc::new(AffixPoolDefinition::with_members(affix_pool_members));
     let affix_generator = AffixGenerator {};
     let affix_pool_generator = AffixPoolGenerator {};
 
     let mut affixes = vec![];
 
-    let mut affix_pool_criteria = AffixPoolGenerationCriteria::default();
+    let mut affix_pool_criteria = AffixPoolGenerationCriteria {
+        tag_rules: criteria.affix_tag_rules.clone(),
+        ..Default::default()
+    };
+
+    let mut prefix_count = 0;
+    let mut suffix_count = 0;
 
     for _ in 0..affix_count {
-        if let Some(affix_def) =
-            affix_pool_generator.generate(pool.clone(), &affix_pool_criteria, &(affix_db.clone()))
-        {
+        affix_pool_criteria.placement = match (prefix_count < affix_cap, suffix_count < affix_cap) {
+            (true, true) => None,
+            (true, false) => Some(AffixPlacement::Prefix),
+            (false, true) => Some(AffixPlacement::Suffix),
+            (false, false) => break,
+        };
+
+        if let Some(affix_def) = affix_pool_generator.generate(
+            pool.clone(),
+            &affix_pool_criteria,
+            &(affix_db.clone()),
+            rng,
+        ) {
             let affix_criteria = &criteria.affix_generation_criteria;
-            let affix = affix_generator.generate(affix_def.clone(), affix_criteria, &());
+            let affix = affix_generator.generate(affix_def.clone(), affix_criteria, &(), rng);
 
             if let Some(affix_instance) = affix {
                 affixes.push(affix_instance);
 
+                match affix_def.placement {
+                    AffixPlacement::Prefix => prefix_count += 1,
+                    AffixPlacement::Suffix => suffix_count += 1,
+                    AffixPlacement::Invalid => {}
+                }
+
                 if let Some(ids) = &mut affix_pool_criteria.disallowed_ids {
-                    ids.insert(affix_def.lock().unwrap().id);
+                    ids.insert(affix_def.id);
                 } else {
                     let mut hash_set = HashSet::new();
-                    hash_set.insert(affix_def.lock().unwrap().id);
+                    hash_set.insert(affix_def.id);
                     affix_pool_criteria.disallowed_ids = Some(hash_set);
                 }
+
+                if let Some(mod_group) = &affix_def.mod_group {
+                    affix_pool_criteria
+                        .disallowed_mod_groups
+                        .get_or_insert_with(HashSet::new)
+                        .insert(mod_group.clone());
+                }
             } else {
                 // TODO: don't continue? this seems bad
                 continue;
@@ -113,22 +192,22 @@ fn generate_from_affix_pool(
     affixes
 }
 
-fn generate_from_fixed_affixes(
+fn generate_from_fixed_affixes<R: Rng + ?Sized>(
     criteria: &ItemDefinitionCriteria,
-    definition: Arc<Mutex<ItemDefinition>>,
+    definition: Arc<ItemDefinition>,
+    rng: &mut R,
 ) -> Vec<AffixInstance> {
     let mut affixes = vec![];
 
     let affix_generator = AffixGenerator {};
     let affix_criteria = &criteria.affix_generation_criteria;
 
-    for fixed_affix in &definition.lock().unwrap().fixed_affixes {
-        let affix = affix_generator.generate(fixed_affix.clone(), affix_criteria, &());
+    // Fixed affixes whose tiers are all out of reach of the criteria, such as a low item level, are left off the item
+    for fixed_affix in &definition.fixed_affixes {
+        let affix = affix_generator.generate(Arc::clone(fixed_affix), affix_criteria, &(), rng);
 
         if let Some(affix_instance) = affix {
             affixes.push(affix_instance);
-        } else {
-            panic!("Failed to generate fixed affix")
         }
     }
 
@@ -137,74 +216,92 @@ fn generate_from_fixed_affixes(
 
 impl DataInstanceGenerator<ItemDefinition, ItemInstance, ItemDefinitionCriteria> for ItemGenerator {
     type DataDependencies = (
-        Arc<Mutex<AffixDefinitionDatabase>>,
-        Arc<Mutex<AffixPoolDefinitionDatabase>>,
+        Arc<AffixDefinitionDatabase>,
+        Arc<AffixPoolDefinitionDatabase>,
     );
 
-    fn generate(
+    fn generate<R: Rng + ?Sized>(
         &self,
-        definition: Arc<Mutex<ItemDefinition>>,
+        definition: Arc<ItemDefinition>,
         criteria: &ItemDefinitionCriteria,
         dependencies: &Self::DataDependencies,
+        rng: &mut R,
     ) -> Option<ItemInstance> {
-        let (affix_db, affix_pool_db) = dependencies;
+        let (affix_db, _) = dependencies;
 
-        let has_fixed_affixes = !definition.lock().unwrap().fixed_affixes.is_empty();
+        let has_fixed_affixes = !definition.fixed_affixes.is_empty();
+
+        let rarity = if has_fixed_affixes {
+            ItemInstanceRarityTier::Fabled
+        } else {
+            roll_rarity(criteria, rng)
+        };
 
         let affixes = {
             if has_fixed_affixes {
-                generate_from_fixed_affixes(criteria, definition.clone())
+                generate_from_fixed_affixes(criteria, definition.clone(), rng)
             } else {
                 generate_from_affix_pool(
                     criteria,
                     definition.clone(),
+                    rarity,
                     affix_db.to_owned(),
-                    affix_pool_db.to_owned(),
+                    rng,
                 )
             }
         };
 
+        // The GUID is drawn from the same RNG as the affixes, so that a seed fully reproduces an item
         Some(ItemInstance {
-            guid: Uuid::new_v4().to_string(),
+            guid: Builder::from_random_bytes(rng.gen())
+                .into_uuid()
+                .to_string(),
             definition,
             affixes,
+            rarity,
+            item_level: criteria.affix_generation_criteria.item_level.unwrap_or(0),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
 
-    use cypher_core::data::DataInstanceGenerator;
+    use cypher_core::data::{
+        DataDefinitionDatabase, DataInstanceGenerator, DefRef, DefinitionStore,
+    };
     use cypher_core::{
-        affix::database::AffixDefinitionDatabase, affix_pool::database::AffixPoolDefinitionDatabase,
+        affix::{
+            database::AffixDefinitionDatabase,
+            definition::{AffixDefinition, AffixDefinitionTier},
+            generator::AffixGenerationCriteria,
+        },
+        affix_pool::database::AffixPoolDefinitionDatabase,
     };
-    use rand::seq::SliceRandom;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
-    use crate::item::database::ItemDefinitionDatabase;
-    use crate::item::definition::ItemDefinition;
+    use crate::item::{
+        classification::{ItemClassification, ItemEquipSlot},
+        database::ItemDefinitionDatabase,
+        definition::ItemDefinition,
+    };
 
-    use super::{ItemDefinitionCriteria, ItemGenerator};
+    use super::{AffixPlacement, ItemDefinitionCriteria, ItemGenerator, ItemInstanceRarityTier};
 
     #[test]
     fn loot_generation() {
-        let affix_database = Arc::new(Mutex::new(AffixDefinitionDatabase::initialize()));
-        let affix_pool_database = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::initialize(
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
             affix_database.clone(),
-        )));
-        let item_database = Arc::new(Mutex::new(ItemDefinitionDatabase::initialize(
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
             affix_database.clone(),
             affix_pool_database.clone(),
-        )));
+        ));
 
         let definition = item_database
-            .lock()
-            .unwrap()
-            .items
-            .iter()
-            .map(|item| item.1.to_owned())
-            .collect::<Vec<Arc<Mutex<ItemDefinition>>>>()
+            .definitions()
             .choose(&mut rand::thread_rng())
             .unwrap()
             .to_owned();
@@ -217,7 +314,152 @@ mod tests {
                 definition.clone(),
                 &criteria,
                 &(affix_database.clone(), affix_pool_database.clone()),
+                &mut rand::thread_rng(),
             );
         }
     }
+
+    #[test]
+    fn same_seed_generates_same_item() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+
+        let criteria = ItemDefinitionCriteria::default();
+        let item_generator = ItemGenerator;
+
+        let definitions = item_database.definitions();
+        for definition in definitions {
+            let generate = |seed: u64| {
+                let item = item_generator
+                    .generate(
+                        definition.clone(),
+                        &criteria,
+                        &(affix_database.clone(), affix_pool_database.clone()),
+                        &mut StdRng::seed_from_u64(seed),
+                    )
+                    .unwrap();
+
+                serde_json::to_string(&item).unwrap()
+            };
+
+            assert_eq!(generate(1234), generate(1234));
+            assert_ne!(generate(1234), generate(4321));
+        }
+    }
+
+    #[test]
+    fn affixes_stay_within_rarity_caps() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+
+        // Every item rolls rare, to fill as many affix slots as possible
+        let criteria = ItemDefinitionCriteria {
+            rarity_weighting: vec![(ItemInstanceRarityTier::Rare, 1)],
+            ..Default::default()
+        };
+        let item_generator = ItemGenerator;
+        let mut rng = StdRng::seed_from_u64(20);
+
+        for definition in item_database.definitions() {
+            for _ in 0..50 {
+                let item = item_generator
+                    .generate(
+                        definition.clone(),
+                        &criteria,
+                        &(affix_database.clone(), affix_pool_database.clone()),
+                        &mut rng,
+                    )
+                    .unwrap();
+
+                if !definition.fixed_affixes.is_empty() {
+                    assert_eq!(item.rarity, ItemInstanceRarityTier::Fabled);
+                    continue;
+                }
+
+                assert_eq!(item.rarity, ItemInstanceRarityTier::Rare);
+                let count = |placement: AffixPlacement| {
+                    item.affixes
+                        .iter()
+                        .filter(|affix| affix.definition.placement == placement)
+                        .count() as u8
+                };
+                assert!(count(AffixPlacement::Prefix) <= item.rarity.affix_cap());
+                assert!(count(AffixPlacement::Suffix) <= item.rarity.affix_cap());
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_affixes_out_of_item_level_are_left_off() {
+        let fixed_affix = Arc::new(AffixDefinition {
+            id: 1,
+            placement: AffixPlacement::Prefix,
+            tiers: [(
+                1,
+                AffixDefinitionTier {
+                    tier: 1,
+                    stats: vec![],
+                    item_level_req: Some(50),
+                    precision_places: None,
+                    weight: None,
+                },
+            )]
+            .into(),
+            name: String::from("affix.fixed"),
+            tags: vec![],
+            mod_group: None,
+        });
+        let definition = Arc::new(ItemDefinition {
+            id: 1,
+            classification: ItemClassification::Equippable(ItemEquipSlot::Head),
+            affix_pools: vec![],
+            fixed_affixes: vec![DefRef::new(fixed_affix)],
+            name: String::from("item.unique"),
+        });
+        let dependencies = (
+            Arc::new(AffixDefinitionDatabase::from_store(DefinitionStore::new(
+                vec![],
+            ))),
+            Arc::new(AffixPoolDefinitionDatabase::from_store(
+                DefinitionStore::new(vec![]),
+            )),
+        );
+
+        let generate = |item_level: u8| {
+            let criteria = ItemDefinitionCriteria {
+                affix_generation_criteria: AffixGenerationCriteria {
+                    item_level: Some(item_level),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            ItemGenerator
+                .generate(
+                    definition.clone(),
+                    &criteria,
+                    &dependencies,
+                    &mut StdRng::seed_from_u64(1),
+                )
+                .unwrap()
+        };
+
+        let item = generate(1);
+        assert_eq!(item.rarity, ItemInstanceRarityTier::Fabled);
+        assert!(item.affixes.is_empty());
+
+        assert_eq!(generate(50).affixes.len(), 1);
+    }
 }
@@ -1,9 +1,9 @@
-use cypher_core::affix::instance::AffixInstance;
-use serde::{Serialize, Serializer};
-use std::{
-    fmt::Display,
-    sync::{Arc, Mutex},
+use cypher_core::{
+    affix::instance::AffixInstance, localization::Localization,
+    stat_registry::database::StatRegistry,
 };
+use serde::{Deserialize, Serialize, Serializer};
+use std::{fmt::Display, ops::RangeInclusive, sync::Arc};
 
 use super::definition::ItemDefinition;
 
@@ -13,19 +13,25 @@ pub struct ItemInstance {
 
     #[serde(serialize_with = "serialize_definition")]
     #[serde(rename = "item_def_id")]
-    pub definition: Arc<Mutex<ItemDefinition>>,
+    pub definition: Arc<ItemDefinition>,
 
     pub affixes: Vec<AffixInstance>,
+
+    pub rarity: ItemInstanceRarityTier,
+
+    /// Level of whatever dropped the item, which sets the affix tiers it could roll.
+    pub item_level: u8,
 }
 
-fn serialize_definition<S>(definition: &Arc<Mutex<ItemDefinition>>, s: S) -> Result<S::Ok, S::Error>
+fn serialize_definition<S>(definition: &Arc<ItemDefinition>, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    s.serialize_u64(definition.lock().unwrap().id)
+    s.serialize_u64(definition.id)
 }
 
 /// Rarity is a misnomer in our implementation, but is the standard for the genre
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub enum ItemInstanceRarityTier {
     /// An item with at most one prefix and one suffix
     Common,
@@ -40,24 +46,52 @@ pub enum ItemInstanceRarityTier {
     Fabled,
 }
 
-impl ItemInstance {
-    pub fn rarity(&self) -> ItemInstanceRarityTier {
-        if !self.definition.lock().unwrap().fixed_affixes.is_empty() {
-            return ItemInstanceRarityTier::Fabled;
+impl ItemInstanceRarityTier {
+    /// Most prefixes, and separately most suffixes, an item of this rarity can roll. Fabled items only have fixed affixes.
+    pub fn affix_cap(&self) -> u8 {
+        match self {
+            ItemInstanceRarityTier::Common => 1,
+            ItemInstanceRarityTier::Uncommon => 2,
+            ItemInstanceRarityTier::Rare => 3,
+            ItemInstanceRarityTier::Fabled => 0,
+        }
+    }
+
+    /// How many affixes in total an item of this rarity rolls, so that each rarity has more than the one below it.
+    pub fn affix_count_range(&self) -> RangeInclusive<u8> {
+        match self {
+            ItemInstanceRarityTier::Common => 1..=2,
+            ItemInstanceRarityTier::Uncommon => 3..=4,
+            ItemInstanceRarityTier::Rare => 5..=6,
+            ItemInstanceRarityTier::Fabled => 0..=0,
         }
+    }
+}
 
-        match self.affixes.len() {
-            0..=2 => ItemInstanceRarityTier::Common,
-            3..=4 => ItemInstanceRarityTier::Uncommon,
-            5..=6 => ItemInstanceRarityTier::Rare,
-            _ => panic!("abnormal affix count found"),
+impl ItemInstance {
+    /// Display text of the item, as shown in tooltips: its name, then every stat its affixes rolled, one per line.
+    /// If `show_details`, the name is followed by the item level, and each stat by the range it was rolled from.
+    pub fn text(
+        &self,
+        localization: &Localization,
+        stat_registry: &StatRegistry,
+        show_details: bool,
+    ) -> String {
+        let mut lines = vec![localization.text(&self.definition.name).to_owned()];
+        if show_details {
+            lines.push(format!("Item Level {}", self.item_level));
         }
+        for affix in &self.affixes {
+            lines.push(localization.affix_text(stat_registry, affix, show_details));
+        }
+
+        lines.join("\n")
     }
 }
 
 impl Display for ItemInstance {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let definition = self.definition.lock().unwrap();
+        let definition = &self.definition;
 
         let mut buffer = String::new();
         for affix in &self.affixes {
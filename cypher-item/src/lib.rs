@@ -1,3 +1,6 @@
+pub mod bundle;
+pub mod enemy;
 pub mod inventory;
 pub mod item;
 pub mod loot_pool;
+pub mod validation;
@@ -1,117 +1,275 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
+use std::{collections::HashMap, sync::Arc};
 
-use cypher_core::data::{DataDefinition, DataDefinitionDatabase};
-use serde::de::DeserializeSeed;
+use cypher_core::data::{
+    DataDefinitionDatabase, DataMigrations, DefinitionRegistry, DefinitionStore,
+};
 
 use crate::item::database::ItemDefinitionDatabase;
 
 use super::{
     definition::{LootPoolDefinition, LootPoolDefinitionId},
-    deserializer::LootPoolDatabaseDeserializer,
+    member::LootPoolEntry,
 };
 
 pub struct LootPoolDefinitionDatabase {
-    pub(crate) pools: HashMap<LootPoolDefinitionId, Arc<Mutex<LootPoolDefinition>>>,
+    pools: DefinitionStore<LootPoolDefinition>,
 }
 
 impl LootPoolDefinitionDatabase {
-    pub fn initialize(item_db: Arc<Mutex<ItemDefinitionDatabase>>) -> Self {
+    pub fn initialize(item_db: Arc<ItemDefinitionDatabase>) -> Self {
         let mut path = std::env::current_dir().unwrap();
         path.push("..");
         path.push("cypher-item");
         path.push("data");
-        path.push("loot_pool.json");
+        path.push(Self::FILE_NAME);
 
         Self::load_from(path.to_str().unwrap(), &item_db)
+            .expect("failed to load loot pool database")
+    }
+
+    /// IDs of a chain of nested loot pools that leads back to its first pool, if any pools reference each other in a cycle.
+    pub fn find_cycle(&self) -> Option<Vec<LootPoolDefinitionId>> {
+        find_cycle(&self.pools)
     }
 }
 
-impl DataDefinitionDatabase<LootPoolDefinition> for LootPoolDefinitionDatabase {
-    type DataDependencies = Arc<Mutex<ItemDefinitionDatabase>>;
+/// Describes a cycle found by [LootPoolDefinitionDatabase::find_cycle].
+pub fn cycle_message(cycle: &[LootPoolDefinitionId]) -> String {
+    let ids = cycle.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+    format!("nested loot pools form a cycle: {}", ids.join(" -> "))
+}
 
-    fn load_from<S: Into<String>>(path: S, dependencies: &Self::DataDependencies) -> Self {
-        let loot_pool_file = String::from_utf8(std::fs::read(path.into()).unwrap()).unwrap();
+/// Whether a pool's nested pools are still being visited, or have been fully visited without finding a cycle.
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
 
-        let loot_pool_deserializer = LootPoolDatabaseDeserializer {
-            item_db: dependencies.clone(),
-        };
-        let pools_database: Vec<LootPoolDefinition> = loot_pool_deserializer
-            .deserialize(&mut serde_json::Deserializer::from_str(
-                loot_pool_file.as_str(),
-            ))
-            .unwrap();
+fn find_cycle(pools: &DefinitionStore<LootPoolDefinition>) -> Option<Vec<LootPoolDefinitionId>> {
+    fn visit(
+        pools: &DefinitionStore<LootPoolDefinition>,
+        pool_id: LootPoolDefinitionId,
+        visits: &mut HashMap<LootPoolDefinitionId, Visit>,
+        path: &mut Vec<LootPoolDefinitionId>,
+    ) -> Option<Vec<LootPoolDefinitionId>> {
+        match visits.get(&pool_id) {
+            Some(Visit::Done) => return None,
+            Some(Visit::InProgress) => {
+                let start = path.iter().position(|id| *id == pool_id).unwrap();
+                let mut cycle = path[start..].to_vec();
+                cycle.push(pool_id);
+                return Some(cycle);
+            }
+            None => {}
+        }
 
-        let pools = pools_database
-            .into_iter()
-            .map(|pool| (pool.id, Arc::new(Mutex::new(pool))))
-            .collect::<HashMap<_, _>>();
+        // Missing pools are reported by check_definitions, not here
+        let pool = pools.get(pool_id as u64)?;
 
-        LootPoolDefinitionDatabase { pools }
+        visits.insert(pool_id, Visit::InProgress);
+        path.push(pool_id);
+        for nested_id in pool.nested_pool_ids() {
+            if let Some(cycle) = visit(pools, nested_id, visits, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        visits.insert(pool_id, Visit::Done);
+
+        None
+    }
+
+    let mut pool_ids = pools.values().map(|pool| pool.id).collect::<Vec<_>>();
+    pool_ids.sort();
+
+    let mut visits = HashMap::new();
+    pool_ids
+        .into_iter()
+        .find_map(|pool_id| visit(pools, pool_id, &mut visits, &mut vec![]))
+}
+
+/// Version 2 members drop an entry, which may be an item, another loot pool or nothing, rather than always an item.
+fn members_drop_entries(definitions: &mut serde_json::Value) -> Result<(), String> {
+    let definitions = definitions
+        .as_array_mut()
+        .ok_or("definitions are not a list")?;
+
+    for definition in definitions {
+        let Some(members) = definition.get_mut("members").and_then(|m| m.as_array_mut()) else {
+            continue;
+        };
+
+        for member in members {
+            let member = member.as_object_mut().ok_or("member is not an object")?;
+            if let Some(item_id) = member.remove("item_id") {
+                member.insert("entry".into(), serde_json::json!({ "item": item_id }));
+            }
+        }
     }
 
-    fn write_to<S: Into<String>>(&self, path: S) {
-        let definition_clones = self
-            .pools
-            .values()
-            .map(|def| def.lock().unwrap().to_owned())
-            .collect::<Vec<LootPoolDefinition>>();
+    Ok(())
+}
+
+impl DataDefinitionDatabase<LootPoolDefinition> for LootPoolDefinitionDatabase {
+    type DataDependencies = Arc<ItemDefinitionDatabase>;
 
-        let serialized = serde_json::ser::to_string(&definition_clones)
-            .expect("failed to serialize loot pool database");
+    const FILE_NAME: &'static str = "loot_pool.json";
 
-        std::fs::write(path.into(), serialized).expect("failed to write serialized data to path");
+    fn from_store(pools: DefinitionStore<LootPoolDefinition>) -> Self {
+        LootPoolDefinitionDatabase { pools }
     }
 
-    fn validate(&self) -> bool {
-        !self.pools.is_empty()
-            && self
-                .pools
-                .values()
-                .all(|pool_def| pool_def.lock().unwrap().validate())
+    fn store(&self) -> &DefinitionStore<LootPoolDefinition> {
+        &self.pools
     }
 
-    fn definition(&self, id: LootPoolDefinitionId) -> Option<Arc<Mutex<LootPoolDefinition>>> {
-        self.pools.get(&id).map(|arc| arc.to_owned())
+    fn registry(item_db: &Self::DataDependencies) -> DefinitionRegistry {
+        DefinitionRegistry::default().with(item_db)
     }
-    fn definitions(&self) -> Vec<Arc<Mutex<LootPoolDefinition>>> {
-        self.pools.values().map(|def| def.to_owned()).collect()
+
+    /// Item references are resolved while loading, but nested pools are only referenced by ID, so are checked here
+    /// along with anything else that would make generating from a pool fail.
+    fn check_definitions(pools: &DefinitionStore<LootPoolDefinition>) -> Result<(), String> {
+        let mut sorted_pools = pools.values().collect::<Vec<_>>();
+        sorted_pools.sort_by_key(|pool| pool.id);
+
+        for pool in sorted_pools {
+            for member in &pool.members {
+                if let LootPoolEntry::Pool(pool_id) = member.entry {
+                    if pools.get(pool_id as u64).is_none() {
+                        return Err(format!(
+                            "loot pool {} references missing loot pool {pool_id}",
+                            pool.id
+                        ));
+                    }
+                }
+
+                if member.count_range().is_empty() {
+                    return Err(format!(
+                        "loot pool {} has a member with a minimum count of {} but a maximum count of {}",
+                        pool.id,
+                        member.count_range().start(),
+                        member.count_range().end()
+                    ));
+                }
+            }
+        }
+
+        match find_cycle(pools) {
+            Some(cycle) => Err(cycle_message(&cycle)),
+            None => Ok(()),
+        }
     }
 
-    fn add_definition(&mut self, definition: LootPoolDefinition) {
-        self.pools
-            .insert(definition.id, Arc::new(Mutex::new(definition)));
+    fn migrations() -> DataMigrations {
+        DataMigrations::default().with(members_drop_entries)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
 
     use cypher_core::{
         affix::database::AffixDefinitionDatabase,
-        affix_pool::database::AffixPoolDefinitionDatabase, data::DataDefinitionDatabase,
+        affix_pool::database::AffixPoolDefinitionDatabase,
+        data::{DataDefinitionDatabase, DataLoadError},
     };
 
     use super::LootPoolDefinitionDatabase;
-    use crate::item::database::ItemDefinitionDatabase;
+    use crate::{
+        item::database::ItemDefinitionDatabase,
+        loot_pool::member::{LootPoolEntry, LootPoolMember},
+    };
 
     #[test]
     fn loot_pool_initialize() {
-        let affix_database = Arc::new(Mutex::new(AffixDefinitionDatabase::initialize()));
-        let affix_pool_database = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::initialize(
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
             affix_database.clone(),
-        )));
-        let item_database = Arc::new(Mutex::new(ItemDefinitionDatabase::initialize(
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
             affix_database.clone(),
             affix_pool_database.clone(),
-        )));
-        let loot_pool_database = Arc::new(Mutex::new(LootPoolDefinitionDatabase::initialize(
+        ));
+        let loot_pool_database = Arc::new(LootPoolDefinitionDatabase::initialize(
             item_database.clone(),
-        )));
+        ));
+
+        assert!(loot_pool_database.validate())
+    }
+
+    #[test]
+    fn nested_pool_cycles_fail_to_load() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_cyclic_loot_pool.json");
+        std::fs::write(
+            &path,
+            r#"{"schema_version":2,"definitions":[
+{"id":1,"name":"Outer","members":[{"entry":{"pool":2},"weight":1}]},
+{"id":2,"name":"Inner","members":[{"entry":{"item":1},"weight":1},{"entry":{"pool":3},"weight":1}]},
+{"id":3,"name":"Back","members":[{"entry":{"pool":2},"weight":1},{"entry":"nothing","weight":1}]}]}"#,
+        )
+        .unwrap();
+
+        let result = LootPoolDefinitionDatabase::load_from(path.to_str().unwrap(), &item_database);
+
+        let Err(DataLoadError::InvalidDefinitions { reason, .. }) = result else {
+            panic!("expected cyclic loot pools to fail to load");
+        };
+        assert_eq!(reason, "nested loot pools form a cycle: 2 -> 3 -> 2");
+    }
+
+    #[test]
+    fn unusable_members_fail_to_load() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+
+        let load = |file_name: &str, contents: &str| {
+            let mut path = std::env::temp_dir();
+            path.push(file_name);
+            std::fs::write(&path, contents).unwrap();
+
+            match LootPoolDefinitionDatabase::load_from(path.to_str().unwrap(), &item_database) {
+                Err(DataLoadError::InvalidDefinitions { reason, .. }) => reason,
+                _ => panic!("expected {file_name} to fail to load"),
+            }
+        };
+
+        assert_eq!(
+            load(
+                "cypher_dangling_loot_pool.json",
+                r#"{"schema_version":2,"definitions":[{"id":1,"name":"Outer","members":[{"entry":{"pool":9999},"weight":1}]}]}"#,
+            ),
+            "loot pool 1 references missing loot pool 9999"
+        );
+        assert_eq!(
+            load(
+                "cypher_empty_count_loot_pool.json",
+                r#"{"schema_version":2,"definitions":[{"id":1,"name":"Outer","members":[{"entry":"nothing","weight":1,"min_count":3,"max_count":2}]}]}"#,
+            ),
+            "loot pool 1 has a member with a minimum count of 3 but a maximum count of 2"
+        );
 
-        assert!(loot_pool_database.lock().unwrap().validate())
+        // Without a maximum, the minimum is also the maximum
+        let mut member = LootPoolMember::new(LootPoolEntry::Nothing, 1);
+        member.min_count = Some(3);
+        assert_eq!(member.count_range(), 3..=3);
     }
 }
@@ -1,14 +1,14 @@
 use cypher_core::data::DataDefinition;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use super::member::LootPoolMember;
+use super::member::{LootPoolEntry, LootPoolMember};
 
 pub type LootPoolDefinitionId = u32;
 
-/// A [LootPoolDefinition] is a collection of [LootPoolMember]s. When generating items from a [LootPool],
-/// the item will be chosen from one of the [LootPoolMember]s.
+/// A [LootPoolDefinition] is a collection of [LootPoolMember]s. When generating items from a loot pool,
+/// guaranteed members always drop, then one of the other [LootPoolMember]s is chosen for every roll.
 /// Enemies may have one or more [LootPoolDefinition]s.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LootPoolDefinition {
     pub id: LootPoolDefinitionId,
 
@@ -16,16 +16,40 @@ pub struct LootPoolDefinition {
 
     /// All [LootPoolMember]s that can drop as part of this [LootPoolDefinition].
     pub members: Vec<LootPoolMember>,
+
+    /// How many times a member is chosen each time the pool is generated. Defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolls: Option<u32>,
+}
+
+impl LootPoolDefinition {
+    pub fn rolls(&self) -> u32 {
+        self.rolls.unwrap_or(1)
+    }
+
+    /// IDs of every loot pool this pool's members drop from.
+    pub fn nested_pool_ids(&self) -> impl Iterator<Item = LootPoolDefinitionId> + '_ {
+        self.members.iter().filter_map(|member| match member.entry {
+            LootPoolEntry::Pool(pool_id) => Some(pool_id),
+            _ => None,
+        })
+    }
 }
 
 impl DataDefinition for LootPoolDefinition {
     type DefinitionTypeId = LootPoolDefinitionId;
 
+    const TYPE_NAME: &'static str = "loot pool";
+
     fn id(&self) -> u64 {
         self.id as u64
     }
 
     fn validate(&self) -> bool {
         !self.members.is_empty()
+            && self
+                .members
+                .iter()
+                .all(|member| !member.count_range().is_empty())
     }
 }
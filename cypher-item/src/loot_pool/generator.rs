@@ -1,117 +1,458 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use cypher_core::{
-    affix::database::AffixDefinitionDatabase,
+    affix::{database::AffixDefinitionDatabase, generator::AffixGenerationCriteria},
     affix_pool::database::AffixPoolDefinitionDatabase,
     data::{DataDefinitionDatabase, DataInstanceGenerator},
 };
-use rand::{distributions::WeightedIndex, prelude::Distribution};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
 
 use crate::item::{
+    classification::{ItemClassification, ItemEquipSlot},
     database::ItemDefinitionDatabase,
+    definition::ItemDefinition,
     generator::{ItemDefinitionCriteria, ItemGenerator},
     instance::ItemInstance,
 };
 
-use super::definition::LootPoolDefinition;
+use super::{
+    database::LootPoolDefinitionDatabase,
+    definition::LootPoolDefinition,
+    member::{LootPoolEntry, LootPoolMember},
+};
 
 #[derive(Default)]
-pub struct LootPoolCriteria {}
+pub struct LootPoolCriteria {
+    /// Item level of every item generated, which sets the affix tiers they can roll.
+    pub item_level: u8,
+
+    /// Percentage by which rarer items, and items with more affixes, are more likely to drop.
+    /// See [ItemDefinitionCriteria::with_increased_rarity].
+    pub increased_rarity: f32,
+
+    /// Percentage more items to drop. Every full 100% is one more item, and the remainder is the chance of one more.
+    pub increased_quantity: f32,
+
+    /// Only items of these classifications can drop, if set.
+    pub allowed_classifications: Option<Vec<ItemClassification>>,
+
+    /// Only items equipped in these slots can drop, if set.
+    pub allowed_slots: Option<Vec<ItemEquipSlot>>,
+}
+
+impl LootPoolCriteria {
+    /// Whether items of `definition` can drop under these criteria.
+    pub fn allows(&self, definition: &ItemDefinition) -> bool {
+        let classification = definition.classification;
+
+        self.allowed_classifications
+            .as_ref()
+            .is_none_or(|classifications| classifications.contains(&classification))
+            && self.allowed_slots.as_ref().is_none_or(|slots| {
+                matches!(classification, ItemClassification::Equippable(slot) if slots.contains(&slot))
+            })
+    }
+
+    /// How many times a pool that rolls `base_rolls` times is rolled for a single drop.
+    /// Increased quantity adds rolls, but never guaranteed drops.
+    pub fn rolls<R: Rng + ?Sized>(&self, base_rolls: u32, rng: &mut R) -> u32 {
+        let expected = base_rolls as f64 * (1. + self.increased_quantity.max(0.) as f64 / 100.);
+        let guaranteed = expected.floor();
+
+        guaranteed as u32 + u32::from(rng.gen_bool(expected - guaranteed))
+    }
+}
+
+/// Databases needed to generate items from a [LootPoolDefinition], including the loot pools it nests.
+pub type LootPoolDependencies = (
+    Arc<AffixDefinitionDatabase>,
+    Arc<AffixPoolDefinitionDatabase>,
+    Arc<ItemDefinitionDatabase>,
+    Arc<LootPoolDefinitionDatabase>,
+);
 
 #[derive(Clone, Default)]
 pub struct LootPoolItemGenerator;
 
-impl DataInstanceGenerator<LootPoolDefinition, ItemInstance, LootPoolCriteria>
-    for LootPoolItemGenerator
-{
-    type DataDependencies = (
-        Arc<Mutex<AffixDefinitionDatabase>>,
-        Arc<Mutex<AffixPoolDefinitionDatabase>>,
-        Arc<Mutex<ItemDefinitionDatabase>>,
-    );
+/// Everything shared while rolling a single drop through a loot pool and the pools nested in it.
+struct LootPoolRoll<'a> {
+    criteria: &'a LootPoolCriteria,
+    item_criteria: ItemDefinitionCriteria,
+    dependencies: &'a LootPoolDependencies,
+}
 
-    fn generate(
+impl LootPoolRoll<'_> {
+    /// Drops every guaranteed member of `pool`, then a weighted member for each of `rolls`.
+    fn roll_pool<R: Rng + ?Sized>(
         &self,
-        definition: Arc<Mutex<LootPoolDefinition>>,
-        _criteria: &LootPoolCriteria,
-        dependencies: &Self::DataDependencies,
-    ) -> Option<ItemInstance> {
-        let (affix_db, affix_pool_db, item_db) = dependencies;
-
-        let weights = definition
-            .lock()
-            .unwrap()
+        pool: &LootPoolDefinition,
+        rolls: u32,
+        rng: &mut R,
+        items: &mut Vec<ItemInstance>,
+    ) {
+        let (guaranteed, members): (Vec<_>, Vec<_>) = pool
             .members
+            .iter()
+            .filter(|member| match &member.entry {
+                LootPoolEntry::Item(item_def) => self.criteria.allows(item_def),
+                _ => true,
+            })
+            .partition(|member| member.guaranteed);
+
+        for member in guaranteed {
+            self.drop_member(member, rng, items);
+        }
+
+        let weights = members
             .iter()
             .map(|member| member.weight)
             .collect::<Vec<u64>>();
 
-        let distribution = WeightedIndex::new(weights.as_slice()).unwrap();
-        let mut rng = rand::thread_rng();
-        let item_id = definition.lock().unwrap().members[distribution.sample(&mut rng)]
-            .item_def
-            .lock()
-            .unwrap()
-            .id;
+        let Ok(distribution) = WeightedIndex::new(weights.as_slice()) else {
+            return;
+        };
+
+        for _ in 0..rolls {
+            self.drop_member(members[distribution.sample(rng)], rng, items);
+        }
+    }
 
-        let definition = item_db.lock().unwrap().definition(item_id).unwrap();
+    fn drop_member<R: Rng + ?Sized>(
+        &self,
+        member: &LootPoolMember,
+        rng: &mut R,
+        items: &mut Vec<ItemInstance>,
+    ) {
+        let (affix_db, affix_pool_db, item_db, loot_pool_db) = self.dependencies;
 
-        let item_generator = ItemGenerator;
+        // Loading rejects empty count ranges and missing pools, but anything unresolved is skipped rather than panicking
+        let count_range = member.count_range();
+        if count_range.is_empty() {
+            return;
+        }
 
-        item_generator.generate(
-            definition,
-            &ItemDefinitionCriteria::default(),
-            &(affix_db.clone(), affix_pool_db.clone()),
-        )
+        for _ in 0..rng.gen_range(count_range) {
+            match &member.entry {
+                LootPoolEntry::Item(item_def) => {
+                    let Some(definition) = item_db.definition(item_def.id) else {
+                        continue;
+                    };
+
+                    items.extend(ItemGenerator.generate(
+                        definition,
+                        &self.item_criteria,
+                        &(affix_db.clone(), affix_pool_db.clone()),
+                        rng,
+                    ));
+                }
+                LootPoolEntry::Pool(pool_id) => {
+                    let Some(pool) = loot_pool_db.definition(*pool_id) else {
+                        continue;
+                    };
+                    self.roll_pool(&pool, pool.rolls(), rng, items);
+                }
+                LootPoolEntry::Nothing => {}
+            }
+        }
+    }
+}
+
+impl DataInstanceGenerator<LootPoolDefinition, Vec<ItemInstance>, LootPoolCriteria>
+    for LootPoolItemGenerator
+{
+    type DataDependencies = LootPoolDependencies;
+
+    /// Generates every item of a single drop, which may be none at all.
+    fn generate<R: Rng + ?Sized>(
+        &self,
+        definition: Arc<LootPoolDefinition>,
+        criteria: &LootPoolCriteria,
+        dependencies: &Self::DataDependencies,
+        rng: &mut R,
+    ) -> Option<Vec<ItemInstance>> {
+        let roll = LootPoolRoll {
+            criteria,
+            item_criteria: ItemDefinitionCriteria {
+                affix_generation_criteria: AffixGenerationCriteria {
+                    item_level: Some(criteria.item_level),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .with_increased_rarity(criteria.increased_rarity),
+            dependencies,
+        };
+
+        let mut items = vec![];
+        roll.roll_pool(
+            &definition,
+            criteria.rolls(definition.rolls(), rng),
+            rng,
+            &mut items,
+        );
+
+        Some(items)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
 
     use cypher_core::{
         affix::database::AffixDefinitionDatabase,
-        affix_pool::database::AffixPoolDefinitionDatabase, data::DataInstanceGenerator,
+        affix_pool::database::AffixPoolDefinitionDatabase,
+        data::{DataDefinitionDatabase, DataInstanceGenerator, DefinitionStore},
     };
 
     use crate::{
-        item::database::ItemDefinitionDatabase,
-        loot_pool::{database::LootPoolDefinitionDatabase, generator::LootPoolCriteria},
+        item::{
+            classification::ItemEquipSlot,
+            database::ItemDefinitionDatabase,
+            instance::{ItemInstance, ItemInstanceRarityTier},
+        },
+        loot_pool::{
+            database::LootPoolDefinitionDatabase,
+            definition::LootPoolDefinition,
+            generator::{LootPoolCriteria, LootPoolDependencies},
+            member::{LootPoolEntry, LootPoolMember},
+        },
     };
 
     use super::LootPoolItemGenerator;
+    use rand::{rngs::StdRng, SeedableRng};
 
-    #[test]
-    fn loot_pool_generation() {
-        let affix_database = Arc::new(Mutex::new(AffixDefinitionDatabase::initialize()));
-        let affix_pool_database = Arc::new(Mutex::new(AffixPoolDefinitionDatabase::initialize(
+    /// The shipped affixes, affix pools, items and loot pools.
+    fn shipped_databases() -> LootPoolDependencies {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
             affix_database.clone(),
-        )));
-        let item_database = Arc::new(Mutex::new(ItemDefinitionDatabase::initialize(
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
             affix_database.clone(),
             affix_pool_database.clone(),
-        )));
-        let loot_pool_database = Arc::new(Mutex::new(LootPoolDefinitionDatabase::initialize(
+        ));
+        let loot_pool_database = Arc::new(LootPoolDefinitionDatabase::initialize(
             item_database.clone(),
-        )));
-
-        let database = loot_pool_database.lock().unwrap();
-        let definition = database.pools.get(&1).unwrap();
-
-        let generator = LootPoolItemGenerator;
-
-        for _ in 0..10 {
-            let item = generator.generate(
-                definition.to_owned(),
-                &LootPoolCriteria::default(),
-                &(
-                    affix_database.clone(),
-                    affix_pool_database.clone(),
-                    item_database.clone(),
-                ),
-            );
-            println!("{:?}", item);
+        ));
+
+        (
+            affix_database,
+            affix_pool_database,
+            item_database,
+            loot_pool_database,
+        )
+    }
+
+    #[test]
+    fn loot_pool_generation() {
+        let dependencies = shipped_databases();
+        let definition = dependencies.3.definition(1).unwrap();
+
+        let drops = || {
+            let mut rng = StdRng::seed_from_u64(20);
+            (0..10)
+                .map(|_| {
+                    LootPoolItemGenerator
+                        .generate(
+                            definition.clone(),
+                            &LootPoolCriteria::default(),
+                            &dependencies,
+                            &mut rng,
+                        )
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let guids = |drops: Vec<Vec<ItemInstance>>| {
+            drops
+                .into_iter()
+                .flatten()
+                .map(|item| item.guid)
+                .collect::<Vec<_>>()
+        };
+
+        let first_drops = drops();
+        for items in &first_drops {
+            assert_eq!(items.len(), 1);
+            assert!(definition.members.iter().any(|member| matches!(
+                &member.entry,
+                LootPoolEntry::Item(item_def) if item_def.id == items[0].definition.id
+            )));
+        }
+
+        // The same seed always drops the same items
+        assert_eq!(guids(first_drops), guids(drops()));
+    }
+
+    #[test]
+    fn item_level_unlocks_higher_tiers() {
+        let dependencies = shipped_databases();
+        let definition = dependencies.3.definition(1).unwrap();
+
+        let highest_tier = |item_level: u8| {
+            let mut rng = StdRng::seed_from_u64(21);
+            (0..500)
+                .flat_map(|_| {
+                    LootPoolItemGenerator
+                        .generate(
+                            definition.clone(),
+                            &LootPoolCriteria {
+                                item_level,
+                                ..Default::default()
+                            },
+                            &dependencies,
+                            &mut rng,
+                        )
+                        .unwrap()
+                })
+                .inspect(|item| assert_eq!(item.item_level, item_level))
+                .flat_map(|item| item.affixes)
+                .filter(|affix| affix.definition.tiers.len() == 7)
+                .map(|affix| affix.tier)
+                .max()
+                .unwrap()
+        };
+
+        assert_eq!(highest_tier(1), 4);
+        assert_eq!(highest_tier(50), 7);
+    }
+
+    #[test]
+    fn criteria_filter_and_improve_drops() {
+        let dependencies = shipped_databases();
+        let definition = dependencies.3.definition(1).unwrap();
+
+        let drops = |criteria: &LootPoolCriteria| {
+            let mut rng = StdRng::seed_from_u64(22);
+            (0..500)
+                .map(|_| {
+                    LootPoolItemGenerator
+                        .generate(definition.clone(), criteria, &dependencies, &mut rng)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let common_count = |criteria: &LootPoolCriteria| {
+            drops(criteria)
+                .into_iter()
+                .flatten()
+                .filter(|item| item.rarity == ItemInstanceRarityTier::Common)
+                .count()
+        };
+
+        let boots_only = LootPoolCriteria {
+            allowed_slots: Some(vec![ItemEquipSlot::Boots]),
+            ..Default::default()
+        };
+        for item in drops(&boots_only).into_iter().flatten() {
+            assert_eq!(item.definition.id, 1);
+        }
+
+        let more_items = LootPoolCriteria {
+            increased_quantity: 150.,
+            ..Default::default()
+        };
+        for drop in drops(&more_items) {
+            assert!((2..=3).contains(&drop.len()));
         }
+
+        let magic_find = LootPoolCriteria {
+            increased_rarity: 200.,
+            ..Default::default()
+        };
+        assert!(common_count(&magic_find) < common_count(&LootPoolCriteria::default()));
+    }
+
+    #[test]
+    fn nested_pools_guaranteed_drops_and_counts() {
+        let (affix_database, affix_pool_database, item_database, _) = shipped_databases();
+
+        let member = |entry: LootPoolEntry, count: (u32, u32), guaranteed: bool| LootPoolMember {
+            entry,
+            weight: 1,
+            min_count: Some(count.0),
+            max_count: Some(count.1),
+            guaranteed,
+        };
+        let boots = LootPoolEntry::Item(item_database.definition(1).unwrap().into());
+        let aviators = LootPoolEntry::Item(item_database.definition(2).unwrap().into());
+
+        let loot_pool_database = Arc::new(LootPoolDefinitionDatabase::from_store(
+            DefinitionStore::new(vec![
+                LootPoolDefinition {
+                    id: 1,
+                    name: String::from("Boss"),
+                    members: vec![
+                        member(boots, (2, 2), true),
+                        member(LootPoolEntry::Pool(2), (1, 1), false),
+                    ],
+                    rolls: Some(3),
+                },
+                LootPoolDefinition {
+                    id: 2,
+                    name: String::from("Helmets"),
+                    members: vec![member(aviators, (1, 2), false)],
+                    rolls: None,
+                },
+                LootPoolDefinition {
+                    id: 3,
+                    name: String::from("Empty"),
+                    members: vec![member(LootPoolEntry::Nothing, (1, 1), false)],
+                    rolls: Some(5),
+                },
+            ]),
+        ));
+        let dependencies = (
+            affix_database,
+            affix_pool_database,
+            item_database,
+            loot_pool_database.clone(),
+        );
+
+        let mut rng = StdRng::seed_from_u64(23);
+        for _ in 0..50 {
+            let items = LootPoolItemGenerator
+                .generate(
+                    loot_pool_database.definition(1).unwrap(),
+                    &LootPoolCriteria::default(),
+                    &dependencies,
+                    &mut rng,
+                )
+                .unwrap();
+
+            let boots_count = items.iter().filter(|item| item.definition.id == 1).count();
+            let aviators_count = items.iter().filter(|item| item.definition.id == 2).count();
+            assert_eq!(boots_count, 2);
+            assert!((3..=6).contains(&aviators_count));
+
+            let nothing = LootPoolItemGenerator
+                .generate(
+                    loot_pool_database.definition(3).unwrap(),
+                    &LootPoolCriteria::default(),
+                    &dependencies,
+                    &mut rng,
+                )
+                .unwrap();
+            assert!(nothing.is_empty());
+        }
+
+        // Quantity adds rolls, but not guaranteed drops
+        let items = LootPoolItemGenerator
+            .generate(
+                loot_pool_database.definition(1).unwrap(),
+                &LootPoolCriteria {
+                    increased_quantity: 100.,
+                    ..Default::default()
+                },
+                &dependencies,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(
+            items.iter().filter(|item| item.definition.id == 1).count(),
+            2
+        );
+        assert!(items.iter().filter(|item| item.definition.id == 2).count() >= 6);
     }
 }
@@ -1,31 +1,68 @@
-use std::sync::{Arc, Mutex};
+use std::ops::RangeInclusive;
 
-use serde::{Serialize, Serializer};
+use cypher_core::data::DefRef;
+use serde::{Deserialize, Serialize};
 
 use crate::item::definition::ItemDefinition;
 
-/// A [LootPoolMember] is a pairing of an item that can drop, in tandem with the chance that item will drop.
+use super::definition::LootPoolDefinitionId;
+
+/// What a [LootPoolMember] drops when it is chosen.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LootPoolEntry {
+    /// An item of this definition.
+    /// The affixes of the item are resolved when generating the item itself, outside of the purview of loot pools.
+    Item(DefRef<ItemDefinition>),
+
+    /// Whatever the loot pool with this ID drops, rolled as many times as that pool rolls.
+    /// Referenced by ID, as pools are in the same database; cycles are rejected when the database is loaded.
+    Pool(LootPoolDefinitionId),
+
+    /// No drop, so that pools can have a chance to drop less.
+    Nothing,
+}
+
+/// A [LootPoolMember] is a pairing of something that can drop, in tandem with the chance it will drop.
 ///
-/// The lifetime `item` is that of the [ItemDefinitionDatabase], as each [LootPoolMember] contains a reference
-/// to an [ItemDefinition] within the [ItemDefinitionDatabase] instance.
-#[derive(Clone, Debug, Serialize)]
+/// Item entries reference an [ItemDefinition] within the [ItemDefinitionDatabase](crate::item::database::ItemDefinitionDatabase)
+/// instance the loot pools were loaded against.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LootPoolMember {
-    #[serde(serialize_with = "serialize_item_def_member")]
-    #[serde(rename = "item_id")]
-    /// What item will be generated when selected.
-    /// The affixes of the item are resolved when generating the item itself, outside of the purview of [LootPool]s.
-    pub item_def: Arc<Mutex<ItemDefinition>>,
+    pub entry: LootPoolEntry,
 
     /// Weight indicates how often this member will be chosen. A higher value = more common.
+    /// Unused by guaranteed members.
     pub weight: u64,
+
+    /// Fewest times the entry drops when chosen. Defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_count: Option<u32>,
+
+    /// Most times the entry drops when chosen. Defaults to the minimum count, or 1 if that is unset too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u32>,
+
+    /// Guaranteed members drop every time their pool is generated, rather than being rolled for.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub guaranteed: bool,
 }
 
-fn serialize_item_def_member<S>(
-    definition: &Arc<Mutex<ItemDefinition>>,
-    s: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    s.serialize_u64(definition.lock().unwrap().id)
+impl LootPoolMember {
+    /// A member that drops `entry` once, chosen by `weight`.
+    pub fn new(entry: LootPoolEntry, weight: u64) -> Self {
+        LootPoolMember {
+            entry,
+            weight,
+            min_count: None,
+            max_count: None,
+            guaranteed: false,
+        }
+    }
+
+    /// How many times the entry can drop when chosen.
+    pub fn count_range(&self) -> RangeInclusive<u32> {
+        let min_count = self.min_count.unwrap_or(1);
+        min_count..=self.max_count.unwrap_or(min_count.max(1))
+    }
 }
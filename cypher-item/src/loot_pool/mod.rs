@@ -1,5 +1,5 @@
 pub mod database;
 pub mod definition;
-pub mod deserializer;
 pub mod generator;
 pub mod member;
+pub mod simulation;
@@ -0,0 +1,302 @@
+use std::{collections::BTreeMap, fmt::Display, sync::Arc};
+
+use cypher_core::{
+    affix::definition::{AffixDefinitionId, AffixTierId},
+    data::DataInstanceGenerator,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+
+use crate::item::{definition::ItemDefinitionId, instance::ItemInstanceRarityTier};
+
+use super::{
+    definition::{LootPoolDefinition, LootPoolDefinitionId},
+    generator::{LootPoolCriteria, LootPoolDependencies, LootPoolItemGenerator},
+};
+
+/// Generates many drops from a loot pool, to see how often items, rarities and affixes drop without
+/// having to farm for them. Drops are generated in order from a single seeded RNG, so the same
+/// simulation of the same data always produces the same report.
+pub struct LootSimulation {
+    /// How many drops to generate, as if this many enemies were killed.
+    pub iterations: u32,
+
+    pub seed: u64,
+}
+
+/// How often items of a single definition dropped during a [LootSimulation].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ItemDropSummary {
+    /// Localization key of the item's name.
+    pub name: String,
+
+    /// Items of this definition dropped in total.
+    pub drops: u64,
+
+    /// Drops that included at least one item of this definition.
+    pub kills_with_drop: u64,
+
+    /// Average items of this definition per drop.
+    pub drops_per_kill: f64,
+
+    /// Average kills until an item of this definition drops, if it dropped at all.
+    pub expected_kills_to_drop: Option<f64>,
+}
+
+/// How often a single affix rolled during a [LootSimulation], and at which tiers.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AffixRollSummary {
+    /// Localization key of the affix's name.
+    pub name: String,
+
+    /// Times the affix rolled on any item.
+    pub rolls: u64,
+
+    /// Share of all dropped items that rolled this affix.
+    pub frequency: f64,
+
+    /// Times each tier of the affix rolled.
+    pub tiers: BTreeMap<AffixTierId, u64>,
+}
+
+/// Results of a [LootSimulation]. Serializes to JSON, and displays as tables.
+#[derive(Clone, Debug, Serialize)]
+pub struct LootSimulationReport {
+    pub loot_pool_id: LootPoolDefinitionId,
+    pub iterations: u32,
+    pub seed: u64,
+
+    /// Items dropped in total, across every drop.
+    pub items_dropped: u64,
+
+    /// Drops that didn't contain any items.
+    pub empty_drops: u64,
+
+    pub items: BTreeMap<ItemDefinitionId, ItemDropSummary>,
+    pub rarities: BTreeMap<ItemInstanceRarityTier, u64>,
+    pub affixes: BTreeMap<AffixDefinitionId, AffixRollSummary>,
+}
+
+impl LootSimulation {
+    /// Generates [LootSimulation::iterations] drops from `definition` under `criteria`.
+    pub fn run(
+        &self,
+        definition: Arc<LootPoolDefinition>,
+        criteria: &LootPoolCriteria,
+        dependencies: &LootPoolDependencies,
+    ) -> LootSimulationReport {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let mut report = LootSimulationReport {
+            loot_pool_id: definition.id,
+            iterations: self.iterations,
+            seed: self.seed,
+            items_dropped: 0,
+            empty_drops: 0,
+            items: BTreeMap::new(),
+            rarities: BTreeMap::new(),
+            affixes: BTreeMap::new(),
+        };
+
+        for _ in 0..self.iterations {
+            let items = LootPoolItemGenerator
+                .generate(definition.clone(), criteria, dependencies, &mut rng)
+                .unwrap_or_default();
+
+            if items.is_empty() {
+                report.empty_drops += 1;
+            }
+
+            let mut dropped_ids = vec![];
+            for item in &items {
+                report.items_dropped += 1;
+                *report.rarities.entry(item.rarity).or_default() += 1;
+
+                let summary =
+                    report
+                        .items
+                        .entry(item.definition.id)
+                        .or_insert_with(|| ItemDropSummary {
+                            name: item.definition.name.clone(),
+                            ..Default::default()
+                        });
+                summary.drops += 1;
+                if !dropped_ids.contains(&item.definition.id) {
+                    dropped_ids.push(item.definition.id);
+                    summary.kills_with_drop += 1;
+                }
+
+                for affix in &item.affixes {
+                    let summary = report
+                        .affixes
+                        .entry(affix.definition.id)
+                        .or_insert_with(|| AffixRollSummary {
+                            name: affix.definition.name.clone(),
+                            ..Default::default()
+                        });
+                    summary.rolls += 1;
+                    *summary.tiers.entry(affix.tier).or_default() += 1;
+                }
+            }
+        }
+
+        let iterations = self.iterations.max(1) as f64;
+        for summary in report.items.values_mut() {
+            summary.drops_per_kill = summary.drops as f64 / iterations;
+            summary.expected_kills_to_drop =
+                (summary.kills_with_drop > 0).then(|| iterations / summary.kills_with_drop as f64);
+        }
+
+        let items_dropped = report.items_dropped.max(1) as f64;
+        for summary in report.affixes.values_mut() {
+            summary.frequency = summary.rolls as f64 / items_dropped;
+        }
+
+        report
+    }
+}
+
+impl LootSimulationReport {
+    /// Average kills until an item of `item_id` drops, if it dropped at all.
+    pub fn expected_kills_to_drop(&self, item_id: ItemDefinitionId) -> Option<f64> {
+        self.items.get(&item_id)?.expected_kills_to_drop
+    }
+}
+
+impl Display for LootSimulationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let percent = |count: u64, total: u64| 100. * count as f64 / total.max(1) as f64;
+
+        writeln!(
+            f,
+            "Loot pool {} over {} kills (seed {}): {} items, {} empty drops",
+            self.loot_pool_id, self.iterations, self.seed, self.items_dropped, self.empty_drops
+        )?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>6}  {:<32} {:>10} {:>10} {:>12}",
+            "Item", "Name", "Drops", "Per kill", "Kills/drop"
+        )?;
+        for (item_id, summary) in &self.items {
+            let kills_to_drop = summary
+                .expected_kills_to_drop
+                .map_or(String::from("-"), |kills| format!("{kills:.1}"));
+            writeln!(
+                f,
+                "{:>6}  {:<32} {:>10} {:>10.4} {:>12}",
+                item_id, summary.name, summary.drops, summary.drops_per_kill, kills_to_drop
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<10} {:>10} {:>9}", "Rarity", "Items", "Share")?;
+        for (rarity, count) in &self.rarities {
+            writeln!(
+                f,
+                "{:<10} {:>10} {:>8.2}%",
+                format!("{rarity:?}"),
+                count,
+                percent(*count, self.items_dropped)
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>6}  {:<32} {:>10} {:>9}  Tiers",
+            "Affix", "Name", "Rolls", "Per item"
+        )?;
+        for (affix_id, summary) in &self.affixes {
+            let tiers = summary
+                .tiers
+                .iter()
+                .map(|(tier, count)| format!("T{tier}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "{:>6}  {:<32} {:>10} {:>8.2}%  {}",
+                affix_id,
+                summary.name,
+                summary.rolls,
+                100. * summary.frequency,
+                tiers
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cypher_core::{
+        affix::database::AffixDefinitionDatabase,
+        affix_pool::database::AffixPoolDefinitionDatabase, data::DataDefinitionDatabase,
+    };
+
+    use super::LootSimulation;
+    use crate::{
+        item::database::ItemDefinitionDatabase,
+        loot_pool::{database::LootPoolDefinitionDatabase, generator::LootPoolCriteria},
+    };
+
+    #[test]
+    fn simulation_is_reproducible_and_adds_up() {
+        let affix_database = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_database = Arc::new(AffixPoolDefinitionDatabase::initialize(
+            affix_database.clone(),
+        ));
+        let item_database = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_database.clone(),
+            affix_pool_database.clone(),
+        ));
+        let loot_pool_database = Arc::new(LootPoolDefinitionDatabase::initialize(
+            item_database.clone(),
+        ));
+        let dependencies = (
+            affix_database.clone(),
+            affix_pool_database.clone(),
+            item_database.clone(),
+            loot_pool_database.clone(),
+        );
+        let definition = loot_pool_database.definition(1).unwrap();
+
+        let simulation = LootSimulation {
+            iterations: 2000,
+            seed: 24,
+        };
+        let criteria = LootPoolCriteria {
+            item_level: 50,
+            ..Default::default()
+        };
+        let report = simulation.run(definition.clone(), &criteria, &dependencies);
+
+        // The generic pool always drops exactly one item
+        assert_eq!(report.items_dropped, 2000);
+        assert_eq!(report.empty_drops, 0);
+        assert_eq!(report.rarities.values().sum::<u64>(), 2000);
+        assert_eq!(
+            report.items.values().map(|item| item.drops).sum::<u64>(),
+            2000
+        );
+
+        // Wrist blades have 8 of the pool's 17 weight
+        let wrist_blade_kills = report.expected_kills_to_drop(4).unwrap();
+        assert!((1.9..2.4).contains(&wrist_blade_kills));
+
+        for affix in report.affixes.values() {
+            assert_eq!(affix.tiers.values().sum::<u64>(), affix.rolls);
+        }
+
+        let rerun = simulation.run(definition, &criteria, &dependencies);
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            serde_json::to_string(&rerun).unwrap()
+        );
+    }
+}
@@ -0,0 +1,869 @@
+use std::{collections::HashSet, fmt::Display};
+
+use cypher_core::{
+    affix::database::AffixDefinitionDatabase,
+    affix_pool::database::AffixPoolDefinitionDatabase,
+    data::{DataDefinition, DataDefinitionDatabase},
+    localization::Localization,
+    stat::{
+        StatModifierKind, ADDED_CHAOS_DAMAGE, ADDED_ELEMENTAL_DAMAGE, ADDED_PHYSICAL_DAMAGE, ARMOR,
+        CHAOS_RESISTANCE, ELEMENTAL_RESISTANCE, HEALTH, ITEM_QUANTITY, MAGIC_FIND, MOVE_SPEED,
+    },
+    stat_derivation::database::StatDerivationDatabase,
+    stat_registry::database::StatRegistry,
+    stat_text,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    enemy::database::EnemyDefinitionDatabase,
+    item::database::ItemDefinitionDatabase,
+    loot_pool::{
+        database::{cycle_message, LootPoolDefinitionDatabase},
+        member::LootPoolEntry,
+    },
+};
+
+/// A single problem found while validating game data, keyed by the file and definition it was found in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    pub file: &'static str,
+    pub definition_id: u64,
+    pub message: String,
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} #{}: {}", self.file, self.definition_id, self.message)
+    }
+}
+
+/// Result of validating every database against each other.
+/// Errors are data that will fail at runtime; warnings are data that is likely a mistake, but still works.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns whether the data is safe to use, ignoring warnings.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Adds every issue in `other` to this report.
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
+    fn error(&mut self, file: &'static str, definition_id: u64, message: String) {
+        self.errors.push(ValidationIssue {
+            file,
+            definition_id,
+            message,
+        });
+    }
+
+    fn warning(&mut self, file: &'static str, definition_id: u64, message: String) {
+        self.warnings.push(ValidationIssue {
+            file,
+            definition_id,
+            message,
+        });
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "error: {error}")?;
+        }
+
+        for warning in &self.warnings {
+            writeln!(f, "warning: {warning}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks references between all game data databases, on top of each definition's own validation.
+pub fn validate_databases(
+    affix_db: &AffixDefinitionDatabase,
+    affix_pool_db: &AffixPoolDefinitionDatabase,
+    item_db: &ItemDefinitionDatabase,
+    loot_pool_db: &LootPoolDefinitionDatabase,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    check_duplicates(&mut report, affix_db);
+    check_duplicates(&mut report, affix_pool_db);
+    check_duplicates(&mut report, item_db);
+    check_duplicates(&mut report, loot_pool_db);
+
+    let mut reachable_affix_ids = HashSet::new();
+    let mut reachable_affix_pool_ids = HashSet::new();
+    let mut reachable_item_ids = HashSet::new();
+
+    let mut affixes = affix_db.definitions();
+    affixes.sort_by_key(|def| def.id);
+    for affix in &affixes {
+        if !affix.validate() {
+            report.error(
+                AffixDefinitionDatabase::FILE_NAME,
+                affix.id(),
+                String::from("definition is invalid"),
+            );
+        }
+
+        if affix.tiers.values().map(|tier| tier.weight()).sum::<u64>() == 0 {
+            report.error(
+                AffixDefinitionDatabase::FILE_NAME,
+                affix.id(),
+                String::from("total tier weight is 0, so no tier can roll"),
+            );
+        }
+    }
+
+    let mut affix_pools = affix_pool_db.definitions();
+    affix_pools.sort_by_key(|def| def.id);
+    for affix_pool in &affix_pools {
+        let file = AffixPoolDefinitionDatabase::FILE_NAME;
+
+        if !affix_pool.validate() {
+            report.error(file, affix_pool.id(), String::from("definition is invalid"));
+        }
+
+        for member in &affix_pool.members {
+            let affix_id = member.affix_def.id;
+            reachable_affix_ids.insert(affix_id);
+
+            if affix_db.definition(affix_id).is_none() {
+                report.error(
+                    file,
+                    affix_pool.id(),
+                    format!("member references missing affix {affix_id}"),
+                );
+            }
+        }
+
+        if affix_pool
+            .members
+            .iter()
+            .map(|member| member.weight)
+            .sum::<u64>()
+            == 0
+        {
+            report.error(
+                file,
+                affix_pool.id(),
+                String::from("total member weight is 0, so no affix can roll"),
+            );
+        }
+
+        for tag in affix_pool.tag_rules.tags() {
+            if !affixes.iter().any(|affix| affix.tags.contains(tag)) {
+                report.warning(
+                    file,
+                    affix_pool.id(),
+                    format!("tag rules mention tag {tag}, but no affix has it"),
+                );
+            }
+        }
+
+        if affix_pool.eligible_members().is_empty() && !affix_pool.members.is_empty() {
+            report.error(
+                file,
+                affix_pool.id(),
+                String::from("tag rules exclude every member, so no affix can roll"),
+            );
+        }
+    }
+
+    let mut items = item_db.definitions();
+    items.sort_by_key(|def| def.id);
+    for item in &items {
+        let file = ItemDefinitionDatabase::FILE_NAME;
+
+        if !item.validate() {
+            report.error(file, item.id(), String::from("definition is invalid"));
+        }
+
+        for affix_pool in &item.affix_pools {
+            let affix_pool_id = affix_pool.id;
+            reachable_affix_pool_ids.insert(affix_pool_id);
+
+            if affix_pool_db.definition(affix_pool_id).is_none() {
+                report.error(
+                    file,
+                    item.id(),
+                    format!("references missing affix pool {affix_pool_id}"),
+                );
+            }
+        }
+
+        for fixed_affix in &item.fixed_affixes {
+            reachable_affix_ids.insert(fixed_affix.id);
+
+            if affix_db.definition(fixed_affix.id).is_none() {
+                report.error(
+                    file,
+                    item.id(),
+                    format!("references missing fixed affix {}", fixed_affix.id),
+                );
+                continue;
+            }
+
+            // Fixed affixes must always roll, so an item can't generate at all if none of the affix's tiers are reachable
+            match fixed_affix
+                .tiers
+                .values()
+                .map(|tier| tier.item_level_req.unwrap_or(0))
+                .min()
+            {
+                None => report.error(
+                    file,
+                    item.id(),
+                    format!(
+                        "fixed affix {} has no tiers, so the item can never roll",
+                        fixed_affix.id
+                    ),
+                ),
+                Some(item_level_req) if item_level_req > 0 => report.warning(
+                    file,
+                    item.id(),
+                    format!(
+                        "fixed affix {} requires item level {item_level_req}, so the item can't roll below it",
+                        fixed_affix.id
+                    ),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    let mut loot_pools = loot_pool_db.definitions();
+    loot_pools.sort_by_key(|def| def.id);
+    for loot_pool in &loot_pools {
+        let file = LootPoolDefinitionDatabase::FILE_NAME;
+
+        if !loot_pool.validate() {
+            report.error(file, loot_pool.id(), String::from("definition is invalid"));
+        }
+
+        for member in &loot_pool.members {
+            match &member.entry {
+                LootPoolEntry::Item(item_def) => {
+                    let item_id = item_def.id;
+                    reachable_item_ids.insert(item_id);
+
+                    if item_db.definition(item_id).is_none() {
+                        report.error(
+                            file,
+                            loot_pool.id(),
+                            format!("member references missing item {item_id}"),
+                        );
+                    }
+                }
+                LootPoolEntry::Pool(pool_id) => {
+                    if loot_pool_db.definition(*pool_id).is_none() {
+                        report.error(
+                            file,
+                            loot_pool.id(),
+                            format!("member references missing loot pool {pool_id}"),
+                        );
+                    }
+                }
+                LootPoolEntry::Nothing => {}
+            }
+
+            if member.count_range().is_empty() {
+                report.error(
+                    file,
+                    loot_pool.id(),
+                    format!(
+                        "member has a minimum count of {} but a maximum count of {}",
+                        member.count_range().start(),
+                        member.count_range().end()
+                    ),
+                );
+            }
+        }
+
+        let rolled_weight = loot_pool
+            .members
+            .iter()
+            .filter(|member| !member.guaranteed)
+            .map(|member| member.weight)
+            .sum::<u64>();
+        let has_guaranteed = loot_pool.members.iter().any(|member| member.guaranteed);
+
+        if rolled_weight == 0 && !has_guaranteed {
+            report.error(
+                file,
+                loot_pool.id(),
+                String::from("total member weight is 0, so no item can drop"),
+            );
+        } else if loot_pool.rolls() == 0 && !has_guaranteed {
+            report.warning(
+                file,
+                loot_pool.id(),
+                String::from("rolls 0 times and has no guaranteed members, so nothing drops"),
+            );
+        }
+    }
+
+    if let Some(cycle) = loot_pool_db.find_cycle() {
+        report.error(
+            LootPoolDefinitionDatabase::FILE_NAME,
+            cycle[0] as u64,
+            cycle_message(&cycle),
+        );
+    }
+
+    for affix in &affixes {
+        if !reachable_affix_ids.contains(&affix.id) {
+            report.warning(
+                AffixDefinitionDatabase::FILE_NAME,
+                affix.id(),
+                String::from("not a member of any affix pool or fixed on any item"),
+            );
+        }
+    }
+
+    for affix_pool in &affix_pools {
+        if !reachable_affix_pool_ids.contains(&affix_pool.id) {
+            report.warning(
+                AffixPoolDefinitionDatabase::FILE_NAME,
+                affix_pool.id(),
+                String::from("not referenced by any item"),
+            );
+        }
+    }
+
+    for item in &items {
+        if !reachable_item_ids.contains(&item.id) {
+            report.warning(
+                ItemDefinitionDatabase::FILE_NAME,
+                item.id(),
+                String::from("not a member of any loot pool"),
+            );
+        }
+    }
+
+    report
+}
+
+/// Checks the stat registry, and that every stat referenced by affixes and stat derivations is in it.
+pub fn validate_stats(
+    stat_registry: &StatRegistry,
+    affix_db: &AffixDefinitionDatabase,
+    stat_derivation_db: &StatDerivationDatabase,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    check_duplicates(&mut report, stat_registry);
+    check_duplicates(&mut report, stat_derivation_db);
+
+    let mut stats = stat_registry.definitions();
+    stats.sort_by_key(|def| def.id);
+    for stat in &stats {
+        if !stat.validate() {
+            report.error(
+                StatRegistry::FILE_NAME,
+                stat.id(),
+                String::from("definition is invalid"),
+            );
+        }
+    }
+
+    for stat_id in [
+        HEALTH,
+        MOVE_SPEED,
+        ARMOR,
+        ELEMENTAL_RESISTANCE,
+        CHAOS_RESISTANCE,
+        ADDED_PHYSICAL_DAMAGE,
+        ADDED_ELEMENTAL_DAMAGE,
+        ADDED_CHAOS_DAMAGE,
+        MAGIC_FIND,
+        ITEM_QUANTITY,
+    ] {
+        if stat_registry.definition(stat_id).is_none() {
+            report.error(
+                StatRegistry::FILE_NAME,
+                stat_id as u64,
+                String::from("stat is read by the game, but isn't defined"),
+            );
+        }
+    }
+
+    let mut affixes = affix_db.definitions();
+    affixes.sort_by_key(|def| def.id);
+    for affix in &affixes {
+        for tier in affix.tiers.values() {
+            for stat in &tier.stats {
+                if stat_registry.definition(stat.stat).is_none() {
+                    report.error(
+                        AffixDefinitionDatabase::FILE_NAME,
+                        affix.id(),
+                        format!("tier {} references missing stat {}", tier.tier, stat.stat),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut derivations = stat_derivation_db.definitions();
+    derivations.sort_by_key(|def| def.id);
+    for derivation in &derivations {
+        let file = StatDerivationDatabase::FILE_NAME;
+
+        if !derivation.validate() {
+            report.error(file, derivation.id(), String::from("definition is invalid"));
+        }
+
+        for stat_id in [derivation.source, derivation.target] {
+            if stat_registry.definition(stat_id).is_none() {
+                report.error(
+                    file,
+                    derivation.id(),
+                    format!("references missing stat {stat_id}"),
+                );
+            }
+        }
+    }
+
+    report
+}
+
+/// Checks every enemy's own values, and that the stats and names enemies reference exist.
+/// Loot pool references are already checked when enemies are loaded.
+pub fn validate_enemies(
+    enemy_db: &EnemyDefinitionDatabase,
+    stat_registry: &StatRegistry,
+    localization: &Localization,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let file = EnemyDefinitionDatabase::FILE_NAME;
+
+    check_duplicates(&mut report, enemy_db);
+
+    let mut enemies = enemy_db.definitions();
+    enemies.sort_by_key(|def| def.id);
+    for enemy in &enemies {
+        if enemy.hit_points <= 0. {
+            report.error(
+                file,
+                enemy.id(),
+                format!("has {} hit points, so it can't be killed", enemy.hit_points),
+            );
+        }
+
+        if enemy.size <= 0. {
+            report.error(
+                file,
+                enemy.id(),
+                format!("has a size of {}, so it can't be seen or hit", enemy.size),
+            );
+        }
+
+        for loot_pool in &enemy.loot_pools {
+            if !(0.0..=1.0).contains(&loot_pool.chance) {
+                report.error(
+                    file,
+                    enemy.id(),
+                    format!(
+                        "loot pool {} has a drop chance of {}, which is not between 0 and 1",
+                        loot_pool.loot_pool.id, loot_pool.chance
+                    ),
+                );
+            } else if loot_pool.chance == 0. {
+                report.warning(
+                    file,
+                    enemy.id(),
+                    format!(
+                        "loot pool {} has a drop chance of 0, so it never drops",
+                        loot_pool.loot_pool.id
+                    ),
+                );
+            }
+        }
+
+        for stat in &enemy.base_stats {
+            if stat_registry.definition(stat.stat).is_none() {
+                report.error(
+                    file,
+                    enemy.id(),
+                    format!("base stats reference missing stat {}", stat.stat),
+                );
+            }
+        }
+
+        for locale in localization.locales() {
+            if localization.contains(locale, &enemy.name) {
+                continue;
+            }
+
+            let message = format!(
+                "localization key {} is missing from locale {locale}",
+                enemy.name
+            );
+            if locale == Localization::FALLBACK_LOCALE {
+                report.error(file, enemy.id(), message);
+            } else {
+                report.warning(file, enemy.id(), message);
+            }
+        }
+    }
+
+    report
+}
+
+/// Checks that every name and stat referenced by definitions has a string in each locale.
+/// Keys missing from the fallback locale are errors, as they'd be shown as raw keys; other locales fall back, so
+/// keys missing from them are warnings.
+pub fn validate_localization(
+    localization: &Localization,
+    affix_db: &AffixDefinitionDatabase,
+    affix_pool_db: &AffixPoolDefinitionDatabase,
+    item_db: &ItemDefinitionDatabase,
+    stat_registry: &StatRegistry,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let mut keys: Vec<(&'static str, u64, String)> = vec![];
+
+    // Templates may be left out of locales that are happy with the stat's own or default template,
+    // but every template that is there must only use known placeholders
+    let mut templates: Vec<(&'static str, u64, String)> = vec![];
+
+    for affix in affix_db.definitions() {
+        keys.push((
+            AffixDefinitionDatabase::FILE_NAME,
+            affix.id(),
+            affix.name.clone(),
+        ));
+
+        for tier in affix.tiers.values() {
+            for template in tier.stats.iter().filter_map(|stat| stat.template.as_ref()) {
+                keys.push((
+                    AffixDefinitionDatabase::FILE_NAME,
+                    affix.id(),
+                    template.clone(),
+                ));
+                templates.push((
+                    AffixDefinitionDatabase::FILE_NAME,
+                    affix.id(),
+                    template.clone(),
+                ));
+            }
+        }
+    }
+
+    for stat in stat_registry.definitions() {
+        keys.push((StatRegistry::FILE_NAME, stat.id(), stat.name.clone()));
+
+        for kind in StatModifierKind::iter() {
+            templates.push((
+                StatRegistry::FILE_NAME,
+                stat.id(),
+                format!("{}.{}", stat.name, stat_text::template_key_suffix(kind)),
+            ));
+        }
+    }
+
+    for affix_pool in affix_pool_db.definitions() {
+        keys.push((
+            AffixPoolDefinitionDatabase::FILE_NAME,
+            affix_pool.id(),
+            affix_pool.name.clone(),
+        ));
+    }
+
+    for item in item_db.definitions() {
+        keys.push((
+            ItemDefinitionDatabase::FILE_NAME,
+            item.id(),
+            item.name.clone(),
+        ));
+    }
+
+    keys.sort();
+    keys.dedup();
+
+    for locale in localization.locales() {
+        for (file, definition_id, key) in &keys {
+            if localization.contains(locale, key) {
+                continue;
+            }
+
+            let message = format!("localization key {key} is missing from locale {locale}");
+            if locale == Localization::FALLBACK_LOCALE {
+                report.error(file, *definition_id, message);
+            } else {
+                report.warning(file, *definition_id, message);
+            }
+        }
+    }
+
+    templates.sort();
+    templates.dedup();
+
+    for locale in localization.locales() {
+        for (file, definition_id, key) in &templates {
+            let Some(template) = localization.get_in(locale, key) else {
+                continue;
+            };
+
+            for placeholder in stat_text::unknown_placeholders(template) {
+                report.error(
+                    file,
+                    *definition_id,
+                    format!(
+                        "template {key} in locale {locale} uses unknown placeholder {{{placeholder}}}"
+                    ),
+                );
+            }
+        }
+    }
+
+    report
+}
+
+fn check_duplicates<DataDefinitionType, DatabaseType>(
+    report: &mut ValidationReport,
+    database: &DatabaseType,
+) where
+    DataDefinitionType: DataDefinition,
+    DatabaseType: DataDefinitionDatabase<DataDefinitionType>,
+{
+    for id in database.duplicate_ids() {
+        report.error(
+            DatabaseType::FILE_NAME,
+            id,
+            String::from("ID is defined more than once"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cypher_core::{
+        data::DefinitionStore,
+        stat_registry::definition::{StatDefinition, StatDisplayFormat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn shipped_data_is_valid() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+        let item_db = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_db.clone(),
+            affix_pool_db.clone(),
+        ));
+        let loot_pool_db = Arc::new(LootPoolDefinitionDatabase::initialize(item_db.clone()));
+        let enemy_db = EnemyDefinitionDatabase::initialize(loot_pool_db.clone());
+
+        let mut report = validate_databases(&affix_db, &affix_pool_db, &item_db, &loot_pool_db);
+
+        let mut data_path = std::env::current_dir().unwrap();
+        data_path.push("..");
+        data_path.push("cypher-core");
+        data_path.push("data");
+        let localization = Localization::load_from_layers(&[data_path]).unwrap();
+        let stat_registry = StatRegistry::initialize();
+        report.extend(validate_localization(
+            &localization,
+            &affix_db,
+            &affix_pool_db,
+            &item_db,
+            &stat_registry,
+        ));
+        report.extend(validate_stats(
+            &stat_registry,
+            &affix_db,
+            &StatDerivationDatabase::initialize(),
+        ));
+        report.extend(validate_enemies(&enemy_db, &stat_registry, &localization));
+
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn missing_stats_are_reported() {
+        let affix_db = AffixDefinitionDatabase::initialize();
+        let stat_derivation_db = StatDerivationDatabase::initialize();
+        let stat_registry = StatRegistry::from_store(DefinitionStore::new(vec![StatDefinition {
+            id: MOVE_SPEED,
+            name: String::from("stat.move_speed"),
+            default_value: 100.,
+            min: None,
+            max: None,
+            integer: false,
+            format: StatDisplayFormat::Number,
+        }]));
+
+        let report = validate_stats(&stat_registry, &affix_db, &stat_derivation_db);
+
+        assert!(report.errors.contains(&ValidationIssue {
+            file: "stat.json",
+            definition_id: HEALTH as u64,
+            message: String::from("stat is read by the game, but isn't defined"),
+        }));
+        assert!(report.errors.iter().any(|issue| issue.file == "affix.json"));
+        assert!(report
+            .errors
+            .iter()
+            .any(|issue| issue.file == "stat_derivation.json"));
+    }
+
+    #[test]
+    fn duplicate_ids_and_zero_weight_are_errors() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_duplicate_item.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":1,"classification":{"Equippable":"Boots"},"affix_pools":[1],"fixed_affixes":[],"name":"First"},
+{"id":1,"classification":{"Equippable":"Boots"},"affix_pools":[1],"fixed_affixes":[],"name":"Second"}]"#,
+        )
+        .unwrap();
+
+        let item_db = Arc::new(
+            ItemDefinitionDatabase::load_from(
+                path.to_str().unwrap(),
+                &(affix_db.clone(), affix_pool_db.clone()),
+            )
+            .unwrap(),
+        );
+
+        path.set_file_name("cypher_zero_weight_loot_pool.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":7,"name":"Empty","members":[{"item_id":1,"weight":0}]}]"#,
+        )
+        .unwrap();
+
+        let loot_pool_db =
+            LootPoolDefinitionDatabase::load_from(path.to_str().unwrap(), &item_db).unwrap();
+
+        let report = validate_databases(&affix_db, &affix_pool_db, &item_db, &loot_pool_db);
+
+        assert!(report.errors.contains(&ValidationIssue {
+            file: "item.json",
+            definition_id: 1,
+            message: String::from("ID is defined more than once"),
+        }));
+        assert!(report.errors.contains(&ValidationIssue {
+            file: "loot_pool.json",
+            definition_id: 7,
+            message: String::from("total member weight is 0, so no item can drop"),
+        }));
+    }
+
+    #[test]
+    fn invalid_enemies_are_reported() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+        let item_db = Arc::new(ItemDefinitionDatabase::initialize(
+            affix_db.clone(),
+            affix_pool_db.clone(),
+        ));
+        let loot_pool_db = Arc::new(LootPoolDefinitionDatabase::initialize(item_db.clone()));
+
+        let mut path = std::env::temp_dir();
+        path.push("cypher_invalid_enemy.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":4,"name":"enemy.ghost","base_stats":[{"stat":999,"kind":"flat","value":1.0}],"hit_points":0.0,
+"team":2,"color":[1.0,1.0,1.0],"size":20.0,"loot_pools":[{"loot_pool":1,"chance":1.5}],"experience":1}]"#,
+        )
+        .unwrap();
+
+        let enemy_db =
+            EnemyDefinitionDatabase::load_from(path.to_str().unwrap(), &loot_pool_db).unwrap();
+
+        let mut data_path = std::env::current_dir().unwrap();
+        data_path.push("..");
+        data_path.push("cypher-core");
+        data_path.push("data");
+        let localization = Localization::load_from_layers(&[data_path]).unwrap();
+        let report = validate_enemies(&enemy_db, &StatRegistry::initialize(), &localization);
+
+        for message in [
+            "has 0 hit points, so it can't be killed",
+            "loot pool 1 has a drop chance of 1.5, which is not between 0 and 1",
+            "base stats reference missing stat 999",
+            "localization key enemy.ghost is missing from locale en",
+        ] {
+            assert!(
+                report.errors.contains(&ValidationIssue {
+                    file: "enemy.json",
+                    definition_id: 4,
+                    message: String::from(message),
+                }),
+                "{report}"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_localization_keys_are_reported() {
+        let affix_db = Arc::new(AffixDefinitionDatabase::initialize());
+        let affix_pool_db = Arc::new(AffixPoolDefinitionDatabase::initialize(affix_db.clone()));
+        let item_db = ItemDefinitionDatabase::initialize(affix_db.clone(), affix_pool_db.clone());
+
+        let mut data_path = std::env::temp_dir();
+        data_path.push("cypher_missing_localization");
+        let locale_path = data_path.join(Localization::DIRECTORY_NAME);
+        std::fs::create_dir_all(&locale_path).unwrap();
+        std::fs::write(
+            locale_path.join("en.json"),
+            r#"{"item.panopticon":"Panopticon","stat.move_speed.increased":"{+value}% faster{rnage}"}"#,
+        )
+        .unwrap();
+        std::fs::write(locale_path.join("fr.json"), r#"{}"#).unwrap();
+
+        let localization = Localization::load_from_layers(&[data_path]).unwrap();
+        let stat_registry = StatRegistry::initialize();
+        let report = validate_localization(
+            &localization,
+            &affix_db,
+            &affix_pool_db,
+            &item_db,
+            &stat_registry,
+        );
+
+        let leather_boots_missing = ValidationIssue {
+            file: "item.json",
+            definition_id: 1,
+            message: String::from("localization key item.leather_boots is missing from locale en"),
+        };
+        assert!(report.errors.contains(&leather_boots_missing));
+        assert!(!report
+            .errors
+            .iter()
+            .any(|issue| issue.definition_id == 6 && issue.file == "item.json"));
+        assert!(report.warnings.contains(&ValidationIssue {
+            file: "item.json",
+            definition_id: 6,
+            message: String::from("localization key item.panopticon is missing from locale fr"),
+        }));
+        assert!(report.errors.contains(&ValidationIssue {
+            file: "stat.json",
+            definition_id: MOVE_SPEED as u64,
+            message: String::from(
+                "template stat.move_speed.increased in locale en uses unknown placeholder {rnage}"
+            ),
+        }));
+    }
+}
@@ -8,12 +8,14 @@ use std::time::SystemTime;
 
 use rand::Rng;
 
-use crate::protocol::PROTOCOL_ID;
+use crate::protocol::{data_hash_user_data, PROTOCOL_ID};
 
 pub struct Client;
 
 impl Client {
-    pub fn initialize(app: &mut App) -> ClientId {
+    /// Connects with `data_hash`, the [content hash](cypher_data::resources::data_manager::DataManager::content_hash)
+    /// of the client's game data, which the server checks against its own.
+    pub fn initialize(app: &mut App, data_hash: u64) -> ClientId {
         app.add_plugins((RenetClientPlugin, NetcodeClientPlugin));
 
         let renet_client = RenetClient::new(ConnectionConfig::default());
@@ -24,7 +26,7 @@ impl Client {
         let auth = ClientAuthentication::Unsecure {
             server_addr: SocketAddr::new("127.0.0.1".parse().unwrap(), 5000),
             client_id,
-            user_data: None,
+            user_data: Some(data_hash_user_data(data_hash)),
             protocol_id: PROTOCOL_ID,
         };
 
@@ -34,6 +34,7 @@ pub enum ServerMessage {
         transform: Transform,
     },
     EnemySpawned {
+        /// ID of the enemy's definition, which clients look up to draw it.
         enemy_id: u64,
         net_entity_id: NetEntityT,
         transform: Transform,
@@ -1,2 +1,16 @@
+use bevy_renet::renet::transport::NETCODE_USER_DATA_BYTES;
+
 // ZJ-TODO: have this auto-generate from builds
 pub const PROTOCOL_ID: u64 = 1;
+
+/// Connect token user data carrying a client's game data hash, so the server can turn away clients whose data differs.
+pub fn data_hash_user_data(data_hash: u64) -> [u8; NETCODE_USER_DATA_BYTES] {
+    let mut user_data = [0; NETCODE_USER_DATA_BYTES];
+    user_data[..8].copy_from_slice(&data_hash.to_le_bytes());
+    user_data
+}
+
+/// The game data hash a client sent in its connect token's user data.
+pub fn data_hash_from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> u64 {
+    u64::from_le_bytes(user_data[..8].try_into().unwrap())
+}
@@ -1,14 +1,19 @@
-use bevy::prelude::{Commands, EventReader, ResMut};
-use bevy_renet::renet::{DefaultChannel, RenetServer, ServerEvent};
+use bevy::prelude::{Commands, EventReader, Res, ResMut};
+use bevy_renet::renet::{
+    transport::NetcodeServerTransport, DefaultChannel, RenetServer, ServerEvent,
+};
+use cypher_data::resources::data_manager::DataManager;
 
 use crate::{
     messages::server::server_message::ServerMessage,
+    protocol::data_hash_from_user_data,
     resources::{
         lobby::Lobby, server_message_dispatcher::ServerToServerMessageDispatcher,
         server_net_entity_registry::ServerNetEntityRegistry,
     },
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_events(
     mut server_events: EventReader<ServerEvent>,
     mut commands: Commands,
@@ -16,10 +21,33 @@ pub fn process_events(
     mut server: ResMut<RenetServer>,
     mut net_entities: ResMut<ServerNetEntityRegistry>,
     mut dispatcher: ResMut<ServerToServerMessageDispatcher>,
+    transport: Res<NetcodeServerTransport>,
+    data_manager: Res<DataManager>,
 ) {
     for event in server_events.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
+                // Clients with different game data would disagree with the server about what every definition ID means
+                let client_data_hash = transport
+                    .user_data(*client_id)
+                    .map(|user_data| data_hash_from_user_data(&user_data));
+                let data_hash = data_manager.content_hash();
+                if client_data_hash != Some(data_hash) {
+                    match client_data_hash {
+                        Some(client_data_hash) => println!(
+                            "Player {} rejected, their game data hash {client_data_hash:016x} doesn't match {data_hash:016x}.",
+                            client_id.raw()
+                        ),
+                        None => println!(
+                            "Player {} rejected, they didn't send a game data hash.",
+                            client_id.raw()
+                        ),
+                    }
+
+                    server.disconnect(*client_id);
+                    continue;
+                }
+
                 println!("Player {} connected.", client_id.raw());
 
                 // Tell the entire server that a new player has joined
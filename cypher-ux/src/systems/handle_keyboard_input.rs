@@ -5,8 +5,8 @@ use bevy::{
     time::Time,
 };
 use bevy_renet::renet::{DefaultChannel, RenetClient};
-use cypher_character::character::Character;
-use cypher_core::stat::Stat;
+use cypher_character::character_stats::CharacterStats;
+use cypher_core::stat::MOVE_SPEED;
 use cypher_net::{
     messages::client::client_message::ClientMessage, resources::net_limiter::NetLimiter,
 };
@@ -19,7 +19,7 @@ use crate::resources::player_settings::PlayerSettings;
 
 pub fn handle_keyboard_input(
     mut player: Query<
-        (&mut Transform, &Character),
+        (&mut Transform, &CharacterStats),
         (
             With<WorldEntity>,
             With<PlayerController>,
@@ -34,14 +34,13 @@ pub fn handle_keyboard_input(
     mut net_limiter: ResMut<NetLimiter>,
 ) {
     let maybe_player = player.get_single_mut();
-    let Ok((mut player_transform, character)) = maybe_player else {
+    let Ok((mut player_transform, character_stats)) = maybe_player else {
         println!("Failed to find player to handle input for");
         return;
     };
 
     let mut trans = (0.0, 0.0);
-    const BASE_MOVE_SPEED: f32 = 100.;
-    let move_speed = BASE_MOVE_SPEED + character.stats().get_stat(&Stat::MoveSpeed).unwrap_or(&0.);
+    let move_speed = character_stats.stat(MOVE_SPEED);
     let delta = time.delta().as_secs_f32() * move_speed;
 
     if keyboard_input.pressed(KeyCode::KeyW) {
@@ -5,7 +5,11 @@ use bevy::{
     ui::BackgroundColor,
     window::{PrimaryWindow, Window},
 };
-use cypher_core::affix::instance::AffixInstance;
+use cypher_core::{
+    affix::instance::AffixInstance, localization::Localization,
+    stat_registry::database::StatRegistry,
+};
+use cypher_data::resources::data_manager::DataManager;
 use cypher_world::components::dropped_item::DroppedItem;
 
 use crate::{
@@ -13,6 +17,8 @@ use crate::{
     resources::player_settings::PlayerSettings,
 };
 
+// Bevy systems take every query and resource as an argument - allow them
+#[allow(clippy::too_many_arguments)]
 pub fn show_loot_on_hover(
     mut ui_elements: Query<&mut BackgroundColor, With<UiItemTextBox>>,
     mut ui_text: Query<&mut Text, With<UiItemText>>,
@@ -21,6 +27,7 @@ pub fn show_loot_on_hover(
     window_query: Query<&Window, With<PrimaryWindow>>,
     asset_server: Res<AssetServer>,
     player_settings: Res<PlayerSettings>,
+    data_manager: Res<DataManager>,
 ) {
     let mut color = ui_elements.get_single_mut().unwrap();
     let mut text = ui_text.get_single_mut().unwrap();
@@ -57,26 +64,34 @@ pub fn show_loot_on_hover(
 
             color.0 = Color::rgba(0.15, 0.15, 0.15, 1.0);
             text.sections.push(TextSection {
-                value: item_instance
-                    .lock()
-                    .unwrap()
-                    .definition
-                    .lock()
-                    .unwrap()
-                    .name
-                    .clone(),
+                value: data_manager
+                    .localization
+                    .text(&item_instance.lock().unwrap().definition.name)
+                    .to_owned(),
                 style: TextStyle {
                     font: asset_server.load("fonts/Exo-Regular.ttf"),
                     font_size: 15.0,
                     color: Color::WHITE,
                 },
             });
+            if player_settings.alt_mode_enabled {
+                text.sections.push(TextSection {
+                    value: format!("\nItem Level {}", item_instance.lock().unwrap().item_level),
+                    style: TextStyle {
+                        font: asset_server.load("fonts/Exo-Regular.ttf"),
+                        font_size: 15.0,
+                        color: Color::GRAY,
+                    },
+                });
+            }
             for affix in &item_instance.lock().unwrap().affixes {
                 add_affix_to_display(
                     affix,
                     &mut text,
                     player_settings.alt_mode_enabled,
                     &asset_server,
+                    &data_manager.localization,
+                    &data_manager.stat_registry,
                 );
             }
             break;
@@ -87,11 +102,14 @@ pub fn show_loot_on_hover(
 fn add_affix_to_display(
     affix: &AffixInstance,
     text_component: &mut Text,
-    should_display_tier: bool,
+    should_display_details: bool,
     asset_server: &AssetServer,
+    localization: &Localization,
+    stat_registry: &StatRegistry,
 ) {
-    let mut affix_str = "\n".to_owned() + &affix.stats.to_string();
-    if should_display_tier {
+    let mut affix_str =
+        "\n".to_owned() + &localization.affix_text(stat_registry, affix, should_display_details);
+    if should_display_details {
         affix_str += format!(" (T{})", affix.tier).as_str();
     }
     text_component.sections.push(TextSection {
@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+use cypher_core::stat::StatList;
+
+/// Stats of an entity that isn't a character, such as the defences an enemy was defined with.
+#[derive(Component)]
+pub struct BaseStats {
+    pub stats: StatList,
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+/// Level of an enemy, which sets the item level of everything it drops.
+#[derive(Component)]
+pub struct Level {
+    pub level: u8,
+}
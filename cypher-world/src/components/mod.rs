@@ -1,7 +1,9 @@
+pub mod base_stats;
 pub mod camera_follow;
 pub mod collider;
 pub mod dropped_item;
 pub mod hit_points;
+pub mod level;
 pub mod player_controller;
 pub mod projectile;
 pub mod team;
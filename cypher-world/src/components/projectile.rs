@@ -1,9 +1,16 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Component, Entity};
+use cypher_core::damage::Damage;
 
 #[derive(Component)]
 pub struct Projectile {
     pub move_speed: f32,
     pub lifetime: f32,
-    pub damage: f32,
+
+    /// Damage before the owner's added damage, which is added on hit, as some of it may only apply against the target's team.
+    pub damage: Damage,
+
     pub team_id: u16,
+
+    /// Entity that fired this projectile, credited with any kills it makes.
+    pub owner: Option<Entity>,
 }
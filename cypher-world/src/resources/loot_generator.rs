@@ -1,11 +1,33 @@
 use crate::resources::world_state::DeathEvent;
 use bevy::ecs::event::ManualEventReader;
 use cypher_item::loot_pool::generator::LootPoolItemGenerator;
+use rand::{rngs::StdRng, SeedableRng};
 
 use bevy::prelude::Resource;
 
-#[derive(Default, Resource)]
+#[derive(Resource)]
 pub struct LootGenerator {
     pub event_reader: ManualEventReader<DeathEvent>,
     pub loot_pool_generator: LootPoolItemGenerator,
+
+    /// Produces a seed for every drop. Seeded from `LOOT_SEED` if set, so a whole session's loot can be replayed.
+    pub rng: StdRng,
+}
+
+impl Default for LootGenerator {
+    fn default() -> Self {
+        let rng = match std::env::var("LOOT_SEED").map(|seed| seed.parse::<u64>()) {
+            Ok(Ok(seed)) => {
+                println!("Initializing loot generator with seed {seed}");
+                StdRng::seed_from_u64(seed)
+            }
+            _ => StdRng::from_entropy(),
+        };
+
+        Self {
+            event_reader: Default::default(),
+            loot_pool_generator: Default::default(),
+            rng,
+        }
+    }
 }
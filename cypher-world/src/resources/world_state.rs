@@ -7,14 +7,26 @@ use std::sync::{Arc, Mutex};
 // ZJ-TODO: refactor
 #[derive(Component, Clone)]
 pub struct LootPoolDropper {
-    // change this name pls
-    pub loot_pool_def: Arc<Mutex<LootPoolDefinition>>,
+    /// Loot pools generated on death, each with the chance from 0 to 1 that it's generated at all.
+    pub loot_pools: Vec<(Arc<LootPoolDefinition>, f32)>,
+
+    /// Percentage increased rarity of this dropper's items, added to the killer's [MAGIC_FIND](cypher_core::stat::MAGIC_FIND).
+    pub increased_rarity: f32,
+
+    /// Percentage increased quantity of this dropper's items, added to the killer's [ITEM_QUANTITY](cypher_core::stat::ITEM_QUANTITY).
+    pub increased_quantity: f32,
 }
 
 #[derive(Event)]
 pub struct DeathEvent {
     pub loot_pool: Option<LootPoolDropper>,
     pub position: Vec2,
+
+    /// Entity credited with the kill, if any.
+    pub killer: Option<Entity>,
+
+    /// Level of whatever died, if it has one. Its drops use [WorldState::zone_level] otherwise.
+    pub level: Option<u8>,
 }
 
 #[derive(Resource)]
@@ -24,6 +36,9 @@ pub struct WorldState {
     pub death_events: Events<DeathEvent>,
 
     pub has_spawned_enemies: bool, // ZJ-TODO: overhaul
+
+    /// Item level of drops from sources without a level of their own.
+    pub zone_level: u8,
 }
 
 impl Default for WorldState {
@@ -32,6 +47,7 @@ impl Default for WorldState {
             item_drops: HashMap::new(),
             death_events: default(),
             has_spawned_enemies: false,
+            zone_level: 1,
         }
     }
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::{Component, EventReader, Query, Res, With};
+use cypher_character::character::Character;
+use cypher_data::resources::{data_manager::DataManager, data_watcher::DataReloaded};
+
+/// Points every [Character] at the reloaded stat data once game data is reloaded, so that their stats are recomputed from it.
+/// Filtered by `T`, so that the server and client each update only their own characters when running in the same app.
+pub fn apply_reloaded_data<T: Component>(
+    mut data_reloaded: EventReader<DataReloaded>,
+    data_manager: Res<DataManager>,
+    mut characters: Query<&mut Character, With<T>>,
+) {
+    // Several reloads in one frame all end up at the current data
+    if data_reloaded.read().count() == 0 {
+        return;
+    }
+
+    for mut character in &mut characters {
+        character.set_game_data(
+            data_manager.stat_registry.clone(),
+            data_manager.stat_derivation_db.clone(),
+        );
+    }
+}
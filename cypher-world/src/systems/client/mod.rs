@@ -1,4 +1,11 @@
 use bevy::app::{App, Update};
+use bevy::prelude::IntoSystemConfigs;
+use cypher_character::character_stats::CharacterStatsChanged;
+use cypher_net::components::client_entity::ClientEntity;
+
+use super::apply_reloaded_data::apply_reloaded_data;
+use super::update_character_stats::update_character_stats;
+use super::update_stat_conditions::update_stat_conditions;
 
 mod handle_entity_destroyed;
 mod spawn_dropped_item;
@@ -8,7 +15,7 @@ mod spawn_projectile;
 mod update_entity_transform;
 
 pub fn register_client_systems(app: &mut App) {
-    app.add_systems(
+    app.add_event::<CharacterStatsChanged>().add_systems(
         Update,
         (
             spawn_player::listen_for_spawn_player,
@@ -17,6 +24,11 @@ pub fn register_client_systems(app: &mut App) {
             handle_entity_destroyed::handle_entity_destroyed,
             spawn_enemy::listen_for_spawn_enemy,
             spawn_dropped_item::listen_for_item_dropped,
+            update_stat_conditions::<ClientEntity>,
+            apply_reloaded_data::<ClientEntity>,
+            update_character_stats::<ClientEntity>
+                .after(update_stat_conditions::<ClientEntity>)
+                .after(apply_reloaded_data::<ClientEntity>),
         ),
     );
 }
@@ -35,13 +35,17 @@ pub fn listen_for_item_dropped(
                 item_db: data_manager.item_db.clone(),
             };
 
-            let item_instance = deserializer
-                .deserialize(&mut serde_json::Deserializer::from_slice(
-                    item_instance_raw.as_slice(),
-                ))
-                .unwrap();
+            let item_instance = match deserializer.deserialize(
+                &mut serde_json::Deserializer::from_slice(item_instance_raw.as_slice()),
+            ) {
+                Ok(item_instance) => item_instance,
+                Err(err) => {
+                    println!("Failed to deserialize dropped item: {err}");
+                    continue;
+                }
+            };
 
-            let rarity = item_instance.rarity();
+            let rarity = item_instance.rarity;
             let item_arc = Arc::new(Mutex::new(item_instance));
 
             let entity_builder = commands.spawn((
@@ -1,7 +1,11 @@
 use crate::components::collider::Collider;
 use crate::components::team::Team;
 use bevy::ecs::event::ManualEventReader;
-use bevy::prelude::{default, Color, Commands, ResMut, Sprite, SpriteBundle, Vec2};
+use bevy::prelude::{
+    default, AssetServer, Color, Commands, Res, ResMut, Sprite, SpriteBundle, Vec2,
+};
+use cypher_core::data::DataDefinitionDatabase;
+use cypher_data::resources::data_manager::DataManager;
 use cypher_net::messages::server::server_message::{ServerMessage, ServerMessageVariant};
 use cypher_net::resources::client_net_entity_registry::ClientNetEntityRegistry;
 use cypher_net::resources::server_message_dispatcher::ServerToClientMessageDispatcher;
@@ -10,13 +14,15 @@ pub fn listen_for_spawn_enemy(
     mut commands: Commands,
     mut dispatcher: ResMut<ServerToClientMessageDispatcher>,
     mut net_entities: ResMut<ClientNetEntityRegistry>,
+    data_manager: Res<DataManager>,
+    asset_server: Res<AssetServer>,
 ) {
     let maybe_events = dispatcher.get_events(ServerMessageVariant::EnemySpawned);
     if let Some(events) = maybe_events {
         let mut reader: ManualEventReader<ServerMessage> = Default::default();
         for event in reader.read(events) {
             let ServerMessage::EnemySpawned {
-                enemy_id: _,
+                enemy_id,
                 net_entity_id,
                 transform,
             } = event
@@ -24,15 +30,27 @@ pub fn listen_for_spawn_enemy(
                 panic!("dispatcher be screwing up");
             };
 
+            // The server turns away clients whose data differs from its own, but only checks when they connect
+            let Some(enemy) = data_manager.enemy_db.definition(*enemy_id) else {
+                println!("Client - can't spawn enemy {enemy_id}, it isn't defined");
+                continue;
+            };
+
+            let [red, green, blue] = enemy.color;
             let entity = commands.spawn((
                 Collider,
-                Team { id: 2 },
+                Team { id: enemy.team },
                 SpriteBundle {
                     sprite: Sprite {
-                        color: Color::rgb(1.0, 0.0, 0.3),
+                        color: Color::rgb(red, green, blue),
                         custom_size: Some(Vec2 { x: 1., y: 1. }),
                         ..default()
                     },
+                    texture: enemy
+                        .sprite
+                        .as_ref()
+                        .map(|sprite| asset_server.load(sprite))
+                        .unwrap_or_default(),
                     transform: *transform,
                     ..default()
                 },
@@ -1,6 +1,7 @@
 use bevy::ecs::event::ManualEventReader;
 use bevy::prelude::*;
-use cypher_character::character::Character;
+use cypher_character::{character::Character, character_stats::CharacterStats};
+use cypher_data::resources::data_manager::DataManager;
 use cypher_net::messages::server::server_message::{ServerMessage, ServerMessageVariant};
 use cypher_net::resources::client_net_entity_registry::ClientNetEntityRegistry;
 use cypher_net::resources::client_state::ClientState;
@@ -17,6 +18,7 @@ pub fn listen_for_spawn_player(
     mut commands: Commands,
     mut net_entities: ResMut<ClientNetEntityRegistry>,
     client_state: Res<ClientState>,
+    data_manager: Res<DataManager>,
 ) {
     let maybe_events = dispatcher.get_events(ServerMessageVariant::PlayerSpawned);
     if let Some(events) = maybe_events {
@@ -27,6 +29,7 @@ pub fn listen_for_spawn_player(
                 event.to_owned(),
                 &mut net_entities,
                 &client_state,
+                &data_manager,
             );
         }
     }
@@ -37,6 +40,7 @@ fn spawn_player(
     spawn_event: ServerMessage,
     net_entities: &mut ResMut<ClientNetEntityRegistry>,
     client_state: &Res<ClientState>,
+    data_manager: &DataManager,
 ) {
     if let ServerMessage::PlayerSpawned {
         player_id,
@@ -46,8 +50,15 @@ fn spawn_player(
     {
         println!("Spawning player");
 
+        let character = Character::with_starting_stats(
+            data_manager.stat_registry.clone(),
+            data_manager.stat_derivation_db.clone(),
+        );
+        let character_stats = CharacterStats::new(&character);
+
         let mut entity_builder = commands.spawn((
-            Character::default(),
+            character,
+            character_stats,
             PlayerController,
             WorldEntity {
                 entity_type: crate::components::world_entity::EntityType::Player { id: player_id },
@@ -1,5 +1,6 @@
 use bevy::ecs::event::ManualEventReader;
 use bevy::prelude::{default, Color, Commands, ResMut, Sprite, SpriteBundle, Vec2};
+use cypher_core::damage::{Damage, DamageType};
 use cypher_net::components::client_entity::ClientEntity;
 use cypher_net::messages::server::server_message::{ServerMessage, ServerMessageVariant};
 use cypher_net::resources::client_net_entity_registry::ClientNetEntityRegistry;
@@ -25,8 +26,9 @@ pub fn listen_for_spawn_projectile(
                 let projectile = Projectile {
                     move_speed: 500.0,
                     lifetime: 800.0,
-                    damage: 1.0,
+                    damage: Damage::of(DamageType::Physical, 1.0),
                     team_id: 1,
+                    owner: None,
                 };
 
                 let entity_id = commands
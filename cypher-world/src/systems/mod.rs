@@ -2,3 +2,7 @@
 pub mod client;
 
 pub mod server;
+
+mod apply_reloaded_data;
+mod update_character_stats;
+mod update_stat_conditions;
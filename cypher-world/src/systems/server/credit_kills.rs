@@ -0,0 +1,22 @@
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::{Local, Query, Res};
+use cypher_character::character::Character;
+
+use crate::resources::world_state::{DeathEvent, WorldState};
+
+/// Starts the kill timer of every [Character] credited with a kill, for modifiers that apply after a kill.
+pub fn credit_kills(
+    mut characters: Query<&mut Character>,
+    game_state: Res<WorldState>,
+    mut reader: Local<ManualEventReader<DeathEvent>>,
+) {
+    for death_event in reader.read(&game_state.death_events) {
+        let Some(killer) = death_event.killer else {
+            continue;
+        };
+
+        if let Ok(mut character) = characters.get_mut(killer) {
+            character.conditions.record_kill();
+        }
+    }
+}
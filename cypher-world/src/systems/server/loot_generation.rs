@@ -2,16 +2,21 @@ use crate::components::dropped_item::DroppedItem;
 use crate::components::world_entity::{EntityType, WorldEntity};
 use crate::resources::loot_generator::LootGenerator;
 use crate::resources::world_state::WorldState;
-use bevy::prelude::{default, Commands, Res, ResMut, Transform, Vec3};
+use bevy::prelude::{default, Commands, EventReader, Query, Res, ResMut, Transform, Vec2, Vec3};
 use bevy_renet::renet::{DefaultChannel, RenetServer};
-use cypher_core::data::DataInstanceGenerator;
+use cypher_character::character_stats::CharacterStats;
+use cypher_core::data::{DataDefinitionDatabase, DataInstanceGenerator};
+use cypher_core::stat::{ITEM_QUANTITY, MAGIC_FIND};
 use cypher_data::resources::data_manager::DataManager;
+use cypher_data::resources::data_watcher::DataReloaded;
 use cypher_item::loot_pool::generator::LootPoolCriteria;
 use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::server::server_message::ServerMessage;
 use cypher_net::resources::server_net_entity_registry::ServerNetEntityRegistry;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::sync::{Arc, Mutex};
 
+#[allow(clippy::too_many_arguments)]
 pub fn loot_generation(
     mut commands: Commands,
     mut game_state: ResMut<WorldState>,
@@ -19,33 +24,82 @@ pub fn loot_generation(
     mut server: ResMut<RenetServer>,
     mut net_entities: ResMut<ServerNetEntityRegistry>,
     data_manager: Res<DataManager>,
+    mut data_reloaded: EventReader<DataReloaded>,
+    killers: Query<&CharacterStats>,
 ) {
+    // Droppers keep the loot pool they spawned with, so pools are looked up again in the current snapshot
+    for _ in data_reloaded.read() {
+        println!("Server - game data reloaded, new drops use the updated loot pools");
+    }
+
     game_state.death_events.update();
 
+    let generator = generator.as_mut();
     let loot_pool_generator = generator.loot_pool_generator.clone();
     let death_events = &game_state.death_events;
 
     let mut new_drops = vec![];
     for death_event in generator.event_reader.read(death_events) {
-        println!("Server - received death event");
+        // Each drop gets its own seed so that a single drop can be replayed from the logs
+        let drop_seed: u64 = generator.rng.gen();
+        let item_level = death_event.level.unwrap_or(game_state.zone_level);
+        println!(
+            "Server - received death event, generating item level {item_level} loot with seed {drop_seed}"
+        );
 
         let dropper = death_event.loot_pool.as_ref().unwrap();
-        let item = loot_pool_generator.generate(
-            dropper.loot_pool_def.clone(),
-            &LootPoolCriteria {},
-            &(
-                data_manager.affix_db.clone(),
-                data_manager.affix_pool_db.clone(),
-                data_manager.item_db.clone(),
-            ),
-        );
 
-        if let Some(item_instance) = item {
+        // Killers without stats, such as the environment, don't improve drops
+        let killer_stats = death_event
+            .killer
+            .and_then(|killer| killers.get(killer).ok());
+        let killer_stat = |stat| killer_stats.map_or(0., |stats| stats.stat(stat));
+
+        let criteria = LootPoolCriteria {
+            item_level,
+            increased_rarity: dropper.increased_rarity + killer_stat(MAGIC_FIND),
+            increased_quantity: dropper.increased_quantity + killer_stat(ITEM_QUANTITY),
+            ..Default::default()
+        };
+
+        // Every pool rolls from the same seeded RNG, so the whole drop replays from its seed
+        let mut rng = StdRng::seed_from_u64(drop_seed);
+        let mut items = vec![];
+        for (dropper_pool, chance) in &dropper.loot_pools {
+            if rng.gen::<f32>() >= *chance {
+                continue;
+            }
+
+            let loot_pool_def = data_manager
+                .loot_pool_db
+                .definition(dropper_pool.id)
+                .unwrap_or_else(|| dropper_pool.clone());
+
+            items.extend(
+                loot_pool_generator
+                    .generate(
+                        loot_pool_def,
+                        &criteria,
+                        &(
+                            data_manager.affix_db.clone(),
+                            data_manager.affix_pool_db.clone(),
+                            data_manager.item_db.clone(),
+                            data_manager.loot_pool_db.clone(),
+                        ),
+                        &mut rng,
+                    )
+                    .unwrap_or_default(),
+            );
+        }
+
+        for (index, item_instance) in items.into_iter().enumerate() {
             let item_instance_raw = serde_json::ser::to_vec(&item_instance).unwrap();
             let item_arc = Arc::new(Mutex::new(item_instance));
 
+            // Spread out drops of more than one item, so each can be hovered and picked up
             let transform = Transform {
-                translation: death_event.position.extend(0.0),
+                translation: (death_event.position + Vec2::new(index as f32 * 15.0, 0.0))
+                    .extend(0.0),
                 scale: Vec3 {
                     x: 10.0,
                     y: 10.0,
@@ -1,6 +1,13 @@
 use bevy::app::{App, Update};
 use bevy::prelude::IntoSystemConfigs;
+use cypher_character::character_stats::CharacterStatsChanged;
+use cypher_net::components::server_entity::ServerEntity;
 
+use super::apply_reloaded_data::apply_reloaded_data;
+use super::update_character_stats::update_character_stats;
+use super::update_stat_conditions::update_stat_conditions;
+
+mod credit_kills;
 mod handle_item_pickup;
 mod loot_generation;
 mod player_transform_update;
@@ -10,7 +17,7 @@ mod spawn_projectile;
 mod update_projectile;
 
 pub fn register_server_systems(app: &mut App) {
-    app.add_systems(
+    app.add_event::<CharacterStatsChanged>().add_systems(
         Update,
         (
             spawn_player::listen_for_spawn_player,
@@ -20,6 +27,12 @@ pub fn register_server_systems(app: &mut App) {
             handle_item_pickup::listen_for_item_pickup,
             player_transform_update::listen_for_player_transform_update,
             spawn_enemy::spawn_initial_enemies.run_if(spawn_enemy::should_spawn_initial_enemies),
+            update_stat_conditions::<ServerEntity>,
+            apply_reloaded_data::<ServerEntity>,
+            update_character_stats::<ServerEntity>
+                .after(update_stat_conditions::<ServerEntity>)
+                .after(apply_reloaded_data::<ServerEntity>),
+            credit_kills::credit_kills,
         ),
     );
 }
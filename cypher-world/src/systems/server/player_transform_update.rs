@@ -1,17 +1,21 @@
 use bevy::ecs::event::ManualEventReader;
-use bevy::prelude::{Res, ResMut};
+use bevy::prelude::{Query, Res, ResMut, Transform, With};
 use bevy_renet::renet::{DefaultChannel, RenetServer};
+use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::client::client_message::{ClientMessage, ClientMessageVariant};
 use cypher_net::messages::server::server_message::ServerMessage;
 use cypher_net::resources::lobby::Lobby;
 use cypher_net::resources::server_message_dispatcher::{
     ClientMessageWithId, ClientToServerMessageDispatcher,
 };
+use cypher_net::resources::server_net_entity_registry::ServerNetEntityRegistry;
 
 pub fn listen_for_player_transform_update(
     mut server: ResMut<RenetServer>,
     mut dispatcher: ResMut<ClientToServerMessageDispatcher>,
     lobby: Res<Lobby>,
+    mut net_entities: ResMut<ServerNetEntityRegistry>,
+    mut transforms: Query<&mut Transform, With<ServerEntity>>,
 ) {
     let maybe_events = dispatcher.get_events(ClientMessageVariant::PlayerTransformUpdate);
     if let Some(events) = maybe_events {
@@ -29,6 +33,13 @@ pub fn listen_for_player_transform_update(
 
             let player_net_entity = lobby.player_net_ids.get(&client_id.raw()).unwrap();
 
+            // Kept in sync on the server too, so that conditions such as moving can be checked there
+            if let Some(local_entity) = net_entities.get_local_entity(player_net_entity) {
+                if let Ok(mut player_transform) = transforms.get_mut(*local_entity) {
+                    *player_transform = *transform;
+                }
+            }
+
             let server_msg = ServerMessage::EntityTransformUpdate {
                 net_entity_id: *player_net_entity,
                 transform: *transform,
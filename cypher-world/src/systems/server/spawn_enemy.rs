@@ -1,13 +1,17 @@
 use bevy::prelude::*;
 use bevy_renet::renet::{DefaultChannel, RenetServer};
 use cypher_core::data::DataDefinitionDatabase;
+use cypher_core::stat::{ITEM_QUANTITY, MAGIC_FIND};
 use cypher_data::resources::data_manager::DataManager;
+use cypher_item::enemy::definition::EnemyDefinitionId;
 use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::server::server_message::ServerMessage;
 use cypher_net::resources::server_net_entity_registry::ServerNetEntityRegistry;
 
+use crate::components::base_stats::BaseStats;
 use crate::components::collider::Collider;
 use crate::components::hit_points::HitPoints;
+use crate::components::level::Level;
 use crate::components::team::Team;
 use crate::components::world_entity::{EntityType, WorldEntity};
 use crate::resources::world_state::{LootPoolDropper, WorldState};
@@ -23,33 +27,29 @@ pub fn spawn_initial_enemies(
     mut world_state: ResMut<WorldState>,
     data_manager: Res<DataManager>,
 ) {
-    let positions = vec![
-        Vec2 {
-            x: -250.0,
-            y: 250.0,
-        },
-        Vec2 { x: 0.0, y: 250.0 },
-        Vec2 { x: 250.0, y: 250.0 },
-    ];
-
-    for pos in positions {
-        let transform = Transform {
-            translation: pos.extend(0.0),
-            scale: Vec3 {
-                x: 45.,
-                y: 45.,
-                z: 1.0,
+    // ZJ-TODO: enemies and levels should come from the zone rather than being hardcoded per position
+    let spawns = vec![
+        (
+            Vec2 {
+                x: -250.0,
+                y: 250.0,
             },
-            ..default()
-        };
+            1,
+            10,
+        ),
+        (Vec2 { x: 0.0, y: 250.0 }, 3, 30),
+        (Vec2 { x: 250.0, y: 250.0 }, 2, 50),
+    ];
 
+    for (pos, enemy_id, level) in spawns {
         spawn_enemy(
             &mut commands,
             &mut server,
             &mut net_entities,
             &data_manager,
-            1,
-            &transform,
+            enemy_id,
+            level,
+            pos,
         );
     }
 
@@ -61,26 +61,50 @@ fn spawn_enemy(
     server: &mut ResMut<RenetServer>,
     net_entities: &mut ResMut<ServerNetEntityRegistry>,
     data_manager: &Res<DataManager>,
-    enemy_id: u64,
-    transform: &Transform,
+    enemy_id: EnemyDefinitionId,
+    level: u8,
+    position: Vec2,
 ) {
+    let Some(enemy) = data_manager.enemy_db.definition(enemy_id) else {
+        println!("Server - can't spawn enemy {enemy_id}, it isn't defined");
+        return;
+    };
+
+    let transform = Transform {
+        translation: position.extend(0.0),
+        scale: Vec3 {
+            x: enemy.size,
+            y: enemy.size,
+            z: 1.0,
+        },
+        ..default()
+    };
+
+    let stats = enemy.stats();
+    let loot_pool_dropper = LootPoolDropper {
+        loot_pools: enemy
+            .loot_pools
+            .iter()
+            .map(|loot_pool| ((*loot_pool.loot_pool).clone(), loot_pool.chance))
+            .collect(),
+        increased_rarity: stats.resolve(MAGIC_FIND, 0.),
+        increased_quantity: stats.resolve(ITEM_QUANTITY, 0.),
+    };
+
     let mut entity_builder = commands.spawn((
-        HitPoints { health: 10.0 },
+        HitPoints {
+            health: enemy.hit_points,
+        },
         Collider,
-        Team { id: 2 },
+        Team { id: enemy.team },
+        Level { level },
+        BaseStats { stats },
         ServerEntity,
         WorldEntity {
-            entity_type: EntityType::Enemy { id: 1 },
-        },
-        LootPoolDropper {
-            loot_pool_def: data_manager
-                .loot_pool_db
-                .lock()
-                .unwrap()
-                .definition(1)
-                .unwrap(),
+            entity_type: EntityType::Enemy { id: enemy_id },
         },
-        *transform,
+        loot_pool_dropper,
+        transform,
     ));
 
     let entity_id = entity_builder.id();
@@ -94,7 +118,7 @@ fn spawn_enemy(
         ServerMessage::EnemySpawned {
             enemy_id,
             net_entity_id,
-            transform: *transform,
+            transform,
         }
         .serialize()
         .unwrap(),
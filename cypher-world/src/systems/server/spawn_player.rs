@@ -1,7 +1,8 @@
 use bevy::ecs::event::ManualEventReader;
 use bevy::prelude::*;
 use bevy_renet::renet::{ClientId, DefaultChannel, RenetServer};
-use cypher_character::character::Character;
+use cypher_character::{character::Character, character_stats::CharacterStats};
+use cypher_data::resources::data_manager::DataManager;
 use cypher_net::components::net_entity::NetEntity;
 use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::server::server_message::{ServerMessage, ServerMessageVariant};
@@ -15,6 +16,7 @@ use crate::components::player_controller::PlayerController;
 use crate::components::team::Team;
 use crate::components::world_entity::{EntityType, WorldEntity};
 
+#[allow(clippy::too_many_arguments)]
 pub fn listen_for_spawn_player(
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
@@ -23,6 +25,7 @@ pub fn listen_for_spawn_player(
     mut net_entities: ResMut<ServerNetEntityRegistry>,
     players: Query<(&Transform, &WorldEntity, &NetEntity), With<ServerEntity>>,
     dropped_items: Query<&DroppedItem>,
+    data_manager: Res<DataManager>,
 ) {
     let maybe_events = dispatcher.get_events(ServerMessageVariant::PlayerConnected);
     if let Some(events) = maybe_events {
@@ -37,12 +40,14 @@ pub fn listen_for_spawn_player(
                     *id,
                     &players,
                     &dropped_items,
+                    &data_manager,
                 );
             }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_player(
     commands: &mut Commands,
     server: &mut ResMut<RenetServer>,
@@ -51,6 +56,7 @@ fn spawn_player(
     player_id: u64,
     world_entities: &Query<(&Transform, &WorldEntity, &NetEntity), With<ServerEntity>>,
     dropped_items: &Query<&DroppedItem>,
+    data_manager: &DataManager,
 ) {
     let transform = Transform {
         translation: Vec2 { x: 0.0, y: 0.0 }.extend(0.0),
@@ -62,8 +68,15 @@ fn spawn_player(
         ..default()
     };
 
+    let character = Character::with_starting_stats(
+        data_manager.stat_registry.clone(),
+        data_manager.stat_derivation_db.clone(),
+    );
+    let character_stats = CharacterStats::new(&character);
+
     let mut entity_builder = commands.spawn((
-        Character::default(),
+        character,
+        character_stats,
         PlayerController,
         ServerEntity,
         WorldEntity {
@@ -1,6 +1,7 @@
 use bevy::ecs::event::ManualEventReader;
-use bevy::prelude::{Commands, ResMut};
+use bevy::prelude::{Commands, Entity, Query, ResMut, With};
 use bevy_renet::renet::{DefaultChannel, RenetServer};
+use cypher_core::damage::{Damage, DamageType};
 use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::client::client_message::{ClientMessage, ClientMessageVariant};
 use cypher_net::messages::server::server_message::ServerMessage;
@@ -10,17 +11,19 @@ use cypher_net::resources::server_message_dispatcher::{
 use cypher_net::resources::server_net_entity_registry::ServerNetEntityRegistry;
 
 use crate::components::projectile::Projectile;
+use crate::components::world_entity::{EntityType, WorldEntity};
 
 pub fn listen_for_spawn_projectile(
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
     mut dispatcher: ResMut<ClientToServerMessageDispatcher>,
     mut net_entities: ResMut<ServerNetEntityRegistry>,
+    players: Query<(Entity, &WorldEntity), With<ServerEntity>>,
 ) {
     let maybe_events = dispatcher.get_events(ClientMessageVariant::SpawnProjectile);
     if let Some(events) = maybe_events {
         let mut reader: ManualEventReader<ClientMessageWithId> = Default::default();
-        for ClientMessageWithId { msg: event, id: _ } in reader.read(events) {
+        for ClientMessageWithId { msg: event, id } in reader.read(events) {
             let ClientMessage::SpawnProjectile {
                 projectile_id,
                 transform,
@@ -29,11 +32,16 @@ pub fn listen_for_spawn_projectile(
                 panic!("dispatcher what is you doing")
             };
 
+            let shooter = players.iter().find(|(_, world_entity)| {
+                matches!(world_entity.entity_type, EntityType::Player { id: player_id } if player_id == id.raw())
+            });
+
             let projectile = Projectile {
                 move_speed: 500.0,
                 lifetime: 800.0,
-                damage: 1.0,
+                damage: Damage::of(DamageType::Physical, 1.0),
                 team_id: 1,
+                owner: shooter.map(|(entity, _)| entity),
             };
 
             let mut entity_builder = commands.spawn((projectile, *transform, ServerEntity));
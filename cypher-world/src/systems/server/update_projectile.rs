@@ -1,13 +1,19 @@
 use bevy::math::bounding::{Aabb2d, IntersectsVolume};
 use bevy::prelude::{Commands, Entity, Query, Res, ResMut, Time, Transform, Vec2, With, Without};
 use bevy_renet::renet::{DefaultChannel, RenetServer};
+use cypher_character::character::Character;
+use cypher_core::damage::mitigate;
+use cypher_core::stat::StatList;
+use cypher_data::resources::data_manager::DataManager;
 use cypher_net::components::net_entity::NetEntity;
 use cypher_net::components::server_entity::ServerEntity;
 use cypher_net::messages::server::server_message::ServerMessage;
 use cypher_net::resources::server_net_entity_registry::ServerNetEntityRegistry;
 
+use crate::components::base_stats::BaseStats;
 use crate::components::collider::Collider;
 use crate::components::hit_points::HitPoints;
+use crate::components::level::Level;
 use crate::components::projectile::Projectile;
 use crate::components::team::Team;
 use crate::resources::world_state::{DeathEvent, LootPoolDropper, WorldState};
@@ -17,11 +23,15 @@ type CollidableQueryAccessT<'a> = (
     &'a mut HitPoints,
     &'a Team,
     Option<&'a LootPoolDropper>,
+    Option<&'a Level>,
+    Option<&'a Character>,
+    Option<&'a BaseStats>,
     Entity,
     &'a NetEntity,
 );
 type CollidableQueryFilterT = (With<Collider>, Without<Projectile>, With<ServerEntity>);
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_projectiles(
     mut commands: Commands,
     mut projectiles: Query<
@@ -29,10 +39,12 @@ pub fn update_projectiles(
         With<ServerEntity>,
     >,
     mut collidables: Query<CollidableQueryAccessT, CollidableQueryFilterT>,
+    shooters: Query<&Character, With<ServerEntity>>,
     time: Res<Time>,
     mut game_state: ResMut<WorldState>,
     mut server: ResMut<RenetServer>,
     mut net_entities: ResMut<ServerNetEntityRegistry>,
+    data_manager: Res<DataManager>,
 ) {
     for (mut projectile_transform, mut projectile, entity, net_entity) in &mut projectiles {
         let forward = -projectile_transform.local_y();
@@ -60,6 +72,9 @@ pub fn update_projectiles(
             mut hit_points,
             team,
             maybe_loot,
+            maybe_level,
+            maybe_character,
+            maybe_base_stats,
             collider_entity,
             collider_net_entity,
         ) in &mut collidables
@@ -78,7 +93,22 @@ pub fn update_projectiles(
                 collidable_transform.scale.truncate(),
             );
             if projectile_aabb.intersects(&collidable_aabb) {
-                hit_points.health -= projectile.damage;
+                // Targets without a character or base stats have no defences, so only their stats' default values apply
+                let defender_stats = match (maybe_character, maybe_base_stats) {
+                    (Some(character), _) => character.stats_against(projectile.team_id),
+                    (None, Some(base_stats)) => base_stats.stats.clone(),
+                    (None, None) => StatList::from(&[]),
+                };
+                // Shooters add the damage granted by their stats, such as from affixes, some of which may only
+                // apply against the target's team
+                let damage = match projectile.owner.and_then(|owner| shooters.get(owner).ok()) {
+                    Some(shooter) => shooter.damage_against(projectile.damage.clone(), team.id),
+                    None => projectile.damage.clone(),
+                };
+                let damage_breakdown =
+                    mitigate(&damage, &data_manager.stat_registry, &defender_stats);
+
+                hit_points.health -= damage_breakdown.total();
                 if hit_points.health <= 0.0 {
                     commands.entity(collider_entity).despawn();
                     net_entities.delete(&collider_net_entity.id);
@@ -98,6 +128,8 @@ pub fn update_projectiles(
                             x: collidable_transform.translation.x,
                             y: collidable_transform.translation.y,
                         },
+                        killer: projectile.owner,
+                        level: maybe_level.map(|level| level.level),
                     });
                 }
 
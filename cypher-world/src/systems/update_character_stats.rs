@@ -0,0 +1,18 @@
+use bevy::prelude::{Component, Entity, EventWriter, Query, With};
+use cypher_character::{
+    character::Character,
+    character_stats::{CharacterStats, CharacterStatsChanged},
+};
+
+/// Recomputes the cached [CharacterStats] of characters whose stats may have changed since they were last computed.
+/// Filtered by `T`, so that the server and client each update only their own characters when running in the same app.
+pub fn update_character_stats<T: Component>(
+    mut characters: Query<(Entity, &Character, &mut CharacterStats), With<T>>,
+    mut stats_changed: EventWriter<CharacterStatsChanged>,
+) {
+    for (entity, character, mut character_stats) in &mut characters {
+        if character_stats.is_stale(character) && character_stats.refresh(character) {
+            stats_changed.send(CharacterStatsChanged { entity });
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{
+    Component, Entity, Local, Query, RemovedComponents, Res, Time, Transform, Vec3, With,
+};
+use cypher_character::{character::Character, character_stats::CharacterStats};
+use cypher_core::stat::HEALTH;
+
+use crate::components::hit_points::HitPoints;
+
+type CharacterQueryAccessT<'a> = (
+    Entity,
+    &'a mut Character,
+    &'a CharacterStats,
+    &'a Transform,
+    Option<&'a HitPoints>,
+);
+
+/// Updates the state every [Character]'s conditional stat modifiers are checked against.
+/// Filtered by `T`, so that the server and client each update only their own characters when running in the same app.
+/// Characters without [HitPoints] can't be damaged, so are always at full health.
+pub fn update_stat_conditions<T: Component>(
+    mut characters: Query<CharacterQueryAccessT, With<T>>,
+    mut removed_characters: RemovedComponents<Character>,
+    time: Res<Time>,
+    mut last_positions: Local<HashMap<Entity, Vec3>>,
+) {
+    for entity in removed_characters.read() {
+        last_positions.remove(&entity);
+    }
+
+    for (entity, mut character, character_stats, transform, maybe_hit_points) in &mut characters {
+        let moving = last_positions
+            .insert(entity, transform.translation)
+            .is_some_and(|last_position| last_position != transform.translation);
+
+        let max_health = character_stats.stat(HEALTH);
+        let current_health = maybe_hit_points.map_or(max_health, |hit_points| hit_points.health);
+
+        character.update_conditions(moving, current_health, max_health, time.delta_seconds());
+    }
+}